@@ -1,8 +1,16 @@
-use std::path::PathBuf;
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
 
-use acdc_converters_common::{Config, Doctype, GeneratorMetadata, Processable, SafeMode, Source};
+use acdc_converters_common::{
+    Config, Doctype, GeneratorMetadata, PrettyDuration, Processable, SafeMode, Source,
+};
 use anyhow::Result;
 use clap::{Parser, ValueEnum};
+use serde::Serialize;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{fmt, EnvFilter};
 
@@ -18,6 +26,29 @@ enum Backend {
     Terminal,
 }
 
+impl Backend {
+    /// Extension of the files the backend writes next to each document, for backends
+    /// that write files rather than to stdout.
+    fn extension(&self) -> Option<&'static str> {
+        match self {
+            #[cfg(feature = "html")]
+            Backend::Html => Some("html"),
+
+            #[cfg(feature = "tck")]
+            Backend::Tck => None,
+
+            #[cfg(feature = "terminal")]
+            Backend::Terminal => None,
+        }
+    }
+}
+
+#[derive(Debug, ValueEnum, Clone)]
+enum Format {
+    Text,
+    Json,
+}
+
 /// Parses files
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -26,9 +57,10 @@ struct Args {
     #[arg(conflicts_with = "stdin")]
     files: Vec<PathBuf>,
 
-    /// backend output format
-    #[arg(long, value_enum, default_value_t = Backend::Html)]
-    backend: Backend,
+    /// backend output format, or a comma-separated list of them to convert each document
+    /// with all of them
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = [Backend::Html])]
+    backend: Vec<Backend>,
 
     /// document type to use when converting document
     #[arg(long, value_enum, default_value_t = Doctype::Article)]
@@ -45,9 +77,18 @@ struct Args {
     /// timing information
     #[arg(long)]
     timings: bool,
+
+    /// how to report errors: as text, or as a JSON array of diagnostics on stderr
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// print links as plain text instead of clickable hyperlinks in the terminal backend
+    #[cfg(feature = "terminal")]
+    #[arg(long)]
+    no_hyperlinks: bool,
 }
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
 
     tracing_subscriber::registry()
@@ -73,37 +114,406 @@ fn main() -> Result<()> {
         config.source = Source::Stdin;
     }
 
-    match args.backend {
+    match args.format {
+        Format::Text => {
+            convert(&args, config)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Format::Json => convert_json(&args, config, &mut std::io::stderr()),
+    }
+}
+
+/// Converts the documents like `convert`, but writes the errors to `w` as a JSON array
+/// of diagnostics, and tells them apart from success with the exit code.
+fn convert_json(args: &Args, config: Config, w: &mut impl Write) -> Result<ExitCode> {
+    let mut diagnostics = Vec::new();
+    if args.stdin {
+        if let Err(error) = convert(args, config) {
+            diagnostics.push(JsonDiagnostic::new(&error, None));
+        }
+    } else if args.files.is_empty() {
+        diagnostics.push(JsonDiagnostic::new(
+            &anyhow::anyhow!("You must pass at least one file to this processor"),
+            None,
+        ));
+    } else {
+        // Each file is converted on its own so that every failing file is reported,
+        // along with its name.
+        for file in &args.files {
+            let config = Config {
+                source: Source::Files(vec![file.clone()]),
+                ..config.clone()
+            };
+            if let Err(error) = convert(args, config) {
+                diagnostics.push(JsonDiagnostic::new(&error, Some(file.clone())));
+            }
+        }
+    }
+    if diagnostics.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+    writeln!(w, "{}", serde_json::to_string(&diagnostics)?)?;
+    Ok(ExitCode::FAILURE)
+}
+
+fn convert(args: &Args, config: Config) -> Result<()> {
+    let backend = match args.backend.as_slice() {
+        [backend] => backend,
+        _ => {
+            if let Some(output) = convert_all(args, &config)? {
+                print!("{output}");
+            }
+            return Ok(());
+        }
+    };
+    match backend {
+        #[cfg(feature = "html")]
         Backend::Html => {
-            run_processor(&args, acdc_html::Processor::new(config))?;
+            run_processor(args, acdc_html::Processor::new(config))?;
         }
 
         #[cfg(feature = "tck")]
         Backend::Tck => {
-            config.source = Source::Stdin;
-            acdc_tck::Processor::new(config).run()?;
+            acdc_tck::Processor::new(Config {
+                source: Source::Stdin,
+                ..config
+            })
+            .run()?;
         }
 
         #[cfg(feature = "terminal")]
         Backend::Terminal => {
-            run_processor(&args, acdc_terminal::Processor::new(config))?;
+            run_processor(
+                args,
+                acdc_terminal::Processor::new(config).with_options(terminal_options(args)),
+            )?;
         }
     };
 
     Ok(())
 }
 
+/// Converts the documents with each of the backends, parsing every document only once.
+///
+/// Only one of the outputs can go to stdout, so documents read from stdin, or several
+/// backends that don't write files, are rejected. That output is returned rather than
+/// written. With `--timings`, how long parsing each document and each conversion of it
+/// took is printed.
+fn convert_all(args: &Args, config: &Config) -> Result<Option<String>> {
+    let stdout_backends = args
+        .backend
+        .iter()
+        .filter(|backend| backend.extension().is_none())
+        .count();
+    let stdout_outputs = match &config.source {
+        Source::Files(files) => stdout_backends * files.len(),
+        Source::String(_) | Source::Stdin => args.backend.len(),
+    };
+    if stdout_outputs > 1 {
+        anyhow::bail!(
+            "only one output can go to stdout: convert files rather than stdin, with at most one backend that doesn't write files"
+        );
+    }
+    let documents = match &config.source {
+        Source::Files(files) if files.is_empty() => {
+            anyhow::bail!("You must pass at least one file to this processor");
+        }
+        Source::Files(files) => files
+            .iter()
+            .map(|file| {
+                if config.timings {
+                    println!("Input file: {}", file.to_string_lossy());
+                }
+                let now = Instant::now();
+                let (document, timings) = acdc_parser::parse_file_with_timings(file)?;
+                if config.timings {
+                    print_parse_timings(now.elapsed(), &timings);
+                }
+                Ok((document, Some(file.as_path())))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        Source::String(content) => {
+            let now = Instant::now();
+            let (document, timings) = acdc_parser::parse_with_timings(content)?;
+            if config.timings {
+                print_parse_timings(now.elapsed(), &timings);
+            }
+            vec![(document, None)]
+        }
+        Source::Stdin => {
+            let mut content = String::new();
+            std::io::stdin().lock().read_to_string(&mut content)?;
+            let now = Instant::now();
+            let (document, timings) = acdc_parser::parse_with_timings(&content)?;
+            if config.timings {
+                print_parse_timings(now.elapsed(), &timings);
+            }
+            vec![(document, None)]
+        }
+    };
+    let mut stdout = None;
+    for backend in &args.backend {
+        for (document, file) in &documents {
+            let now = Instant::now();
+            let output = match backend {
+                #[cfg(feature = "html")]
+                Backend::Html => write_output(
+                    &acdc_html::Processor::new(config.clone()),
+                    document,
+                    *file,
+                    backend.extension(),
+                )?,
+
+                #[cfg(feature = "tck")]
+                Backend::Tck => {
+                    anyhow::bail!("the tck backend can't be combined with other backends")
+                }
+
+                #[cfg(feature = "terminal")]
+                Backend::Terminal => write_output(
+                    &acdc_terminal::Processor::new(config.clone())
+                        .with_options(terminal_options(args)),
+                    document,
+                    *file,
+                    backend.extension(),
+                )?,
+            };
+            if output.is_some() {
+                stdout = output;
+            }
+            if config.timings {
+                println!(
+                    "Time to convert {} with the {backend:?} backend: {}",
+                    file.map_or_else(|| "stdin".into(), Path::to_string_lossy),
+                    now.elapsed().pretty_print()
+                );
+            }
+        }
+    }
+    Ok(stdout)
+}
+
+/// Prints how long reading and parsing a document took, and each phase of it, like
+/// the backends do for `--timings`.
+fn print_parse_timings(elapsed: Duration, timings: &acdc_parser::ParseTimings) {
+    println!(
+        "  Time to read and parse source: {}",
+        elapsed.pretty_print()
+    );
+    for (phase, elapsed) in timings.phases() {
+        println!("    {phase}: {}", elapsed.pretty_print());
+    }
+}
+
+/// Writes what `processor` converts `document` to into the file next to its source with
+/// the given `extension`, or returns it for backends without one and documents read
+/// from stdin.
+fn write_output<P>(
+    processor: &P,
+    document: &acdc_parser::Document,
+    file: Option<&Path>,
+    extension: Option<&str>,
+) -> Result<Option<String>>
+where
+    P: Processable,
+    P::Error: std::error::Error + From<std::string::FromUtf8Error> + Send + Sync + 'static,
+{
+    match (file, extension) {
+        (Some(file), Some(extension)) => {
+            let output = file.with_extension(extension);
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(&output)?);
+            processor.convert(document, Some(file), &mut writer)?;
+            writer.flush()?;
+            println!(
+                "Generated {} file: {}",
+                extension.to_uppercase(),
+                output.to_string_lossy()
+            );
+            Ok(None)
+        }
+        _ => Ok(Some(processor.convert_to_string(document, file)?)),
+    }
+}
+
+/// The terminal backend's options, resolved for stdout, where its output always goes.
+#[cfg(feature = "terminal")]
+fn terminal_options(args: &Args) -> acdc_terminal::Options {
+    acdc_terminal::Options {
+        hyperlinks: !args.no_hyperlinks,
+        ..acdc_terminal::Options::default()
+    }
+    .for_stdout()
+}
+
+/// An error reported by `--format json`.
+#[derive(Debug, Serialize)]
+struct JsonDiagnostic {
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<JsonLocation>,
+}
+
+/// Where in the document a `JsonDiagnostic` is: its line and column, and its offset
+/// from the start of the document in bytes when that is known.
+#[derive(Debug, Serialize)]
+struct JsonLocation {
+    line: usize,
+    column: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,
+}
+
+impl JsonDiagnostic {
+    fn new(error: &anyhow::Error, file: Option<PathBuf>) -> Self {
+        let error_location =
+            parse_error(error).and_then(|error| Some((error.location()?, error.offset())));
+        let location = error_location.map(|(location, offset)| JsonLocation {
+            line: location.start.line,
+            column: location.start.column,
+            offset,
+        });
+        Self {
+            message: error.to_string(),
+            file,
+            location,
+        }
+    }
+}
+
+/// Finds the parser error behind an error returned by one of the backends.
+fn parse_error(error: &anyhow::Error) -> Option<&acdc_parser::Error> {
+    #[cfg(feature = "html")]
+    if let Some(acdc_html::Error::Parse(error)) = error.downcast_ref() {
+        return Some(error);
+    }
+    #[cfg(feature = "tck")]
+    if let Some(acdc_tck::Error::Parse(error)) = error.downcast_ref() {
+        return Some(error);
+    }
+    #[cfg(feature = "terminal")]
+    if let Some(acdc_terminal::Error::Parse(error)) = error.downcast_ref() {
+        return Some(error);
+    }
+    error.downcast_ref()
+}
+
 #[tracing::instrument(skip(processor))]
-fn run_processor<P: Processable>(args: &Args, processor: P) -> Result<(), P::Error> {
+fn run_processor<P>(args: &Args, processor: P) -> Result<()>
+where
+    P: Processable,
+    P::Error: std::error::Error + Send + Sync + 'static,
+{
     if args.stdin {
         let output = processor.output()?;
         println!("{output}");
     } else if args.files.is_empty() {
-        tracing::error!("You must pass at least one file to this processor");
-        std::process::exit(1);
+        anyhow::bail!("You must pass at least one file to this processor");
     } else {
         processor.run()?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_with_several_backends() {
+        let dir = std::env::temp_dir().join(format!("acdc-cli-backends-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("doc.adoc");
+        std::fs::write(&file, "= Document\n\nSome text.\n").unwrap();
+
+        let backends = if cfg!(feature = "terminal") {
+            "html,terminal"
+        } else {
+            "html"
+        };
+        let args = Args::parse_from(["acdc", "--backend", backends, file.to_str().unwrap()]);
+        let config = Config {
+            source: Source::Files(args.files.clone()),
+            ..Config::default()
+        };
+        let output = convert_all(&args, &config).unwrap();
+
+        let html = std::fs::read_to_string(dir.join("doc.html")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(html.contains("Some text."));
+        if cfg!(feature = "terminal") {
+            assert!(output.is_some_and(|output| output.contains("Some text.")));
+        } else {
+            assert_eq!(output, None);
+        }
+    }
+
+    #[test]
+    fn test_convert_without_files() {
+        let args = Args::parse_from(["acdc", "--backend", "html,html"]);
+        let config = Config {
+            source: Source::Files(Vec::new()),
+            ..Config::default()
+        };
+        assert!(convert_all(&args, &config).is_err());
+
+        let args = Args::parse_from(["acdc"]);
+        let processor = acdc_html::Processor::new(Config::default());
+        assert!(run_processor(&args, processor).is_err());
+    }
+
+    #[test]
+    fn test_convert_json_diagnostics() {
+        let dir = std::env::temp_dir().join(format!("acdc-cli-json-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("doc.adoc");
+        std::fs::write(&file, "= Document\n\nifeval::[1 +]\ntext\nendif::[]\n").unwrap();
+        let args = Args::parse_from(["acdc", "--format", "json", file.to_str().unwrap()]);
+        let config = Config {
+            source: Source::Files(args.files.clone()),
+            ..Config::default()
+        };
+        let mut stderr = Vec::new();
+        let code = convert_json(&args, config.clone(), &mut stderr).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(code, ExitCode::FAILURE);
+        let diagnostics: serde_json::Value = serde_json::from_slice(&stderr).unwrap();
+        assert_eq!(
+            diagnostics,
+            serde_json::json!([{
+                "message": "Invalid conditional directive, position: start(line: 3, column: 1), end(line: 3, column: 1)",
+                "file": file,
+                "location": {"line": 3, "column": 1},
+            }])
+        );
+
+        let args = Args::parse_from(["acdc", "--format", "json"]);
+        let mut stderr = Vec::new();
+        let code = convert_json(&args, config, &mut stderr).unwrap();
+        assert_eq!(code, ExitCode::FAILURE);
+        assert_eq!(
+            String::from_utf8(stderr).unwrap(),
+            "[{\"message\":\"You must pass at least one file to this processor\"}]\n"
+        );
+    }
+
+    #[cfg(feature = "terminal")]
+    #[test]
+    fn test_convert_rejects_several_outputs_to_stdout() {
+        let args = Args::parse_from(["acdc", "--backend", "html,terminal", "--stdin"]);
+        let config = Config {
+            source: Source::String("= Document\n\nSome text.\n".to_string()),
+            ..Config::default()
+        };
+        assert!(convert_all(&args, &config).is_err());
+
+        let args = Args::parse_from(["acdc", "--backend", "terminal,terminal", "doc.adoc"]);
+        let config = Config {
+            source: Source::Files(args.files.clone()),
+            ..Config::default()
+        };
+        assert!(convert_all(&args, &config).is_err());
+    }
+}
@@ -0,0 +1,11 @@
+// tag::setup[]
+let config = Config::default();
+// tag::internal[]
+let secret = 42;
+// end::internal[]
+let app = App::new(config);
+// end::setup[]
+fn untagged() {}
+// tag::run[]
+app.run();
+// end::run[]
@@ -0,0 +1,2 @@
+// tag::oops[]
+let x = 1;
@@ -43,7 +43,13 @@ impl Audio {
                                     "unexpected positional attribute in audio block"
                                 );
                             }
-                            Rule::EOI | Rule::comment => {}
+                            Rule::role => metadata.roles.push(pair.as_str().to_string()),
+                            Rule::option => metadata.options.push(pair.as_str().to_string()),
+                            Rule::EOI
+                            | Rule::comment
+                            | Rule::open_sb
+                            | Rule::close_sb
+                            | Rule::empty_style => {}
                             unknown => unreachable!("{unknown:?}"),
                         }
                     }
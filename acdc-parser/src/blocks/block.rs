@@ -7,8 +7,8 @@ use crate::{
     blocks::list::parse_list, inlines::parse_inlines, Admonition, AdmonitionVariant, Anchor,
     AttributeValue, Audio, Block, BlockMetadata, DelimitedBlock, DelimitedBlockType,
     DocumentAttributes, ElementAttributes, Error, Image, InlineNode, InlinePreprocessor,
-    InnerPestParser, Location, PageBreak, Paragraph, Rule, Section, TableOfContents, ThematicBreak,
-    Video,
+    InnerPestParser, Location, OrderedList, PageBreak, Paragraph, Rule, Section, TableOfContents,
+    ThematicBreak, Video,
 };
 
 impl BlockExt for Block {
@@ -21,7 +21,11 @@ impl BlockExt for Block {
             | Block::_DiscreteHeaderSection(_) => {}
             Block::PageBreak(page_break) => page_break.metadata = metadata,
             Block::UnorderedList(unordered_list) => unordered_list.metadata = metadata,
-            Block::OrderedList(ordered_list) => ordered_list.metadata = metadata,
+            Block::OrderedList(ordered_list) => {
+                ordered_list.reversed = OrderedList::reversed(&metadata);
+                ordered_list.metadata = metadata;
+            }
+            Block::CalloutList(callout_list) => callout_list.metadata = metadata,
             Block::DescriptionList(description_list) => description_list.metadata = metadata,
             Block::Section(section) => section.metadata = metadata,
             Block::DelimitedBlock(delimited_block) => delimited_block.metadata = metadata,
@@ -42,7 +46,11 @@ impl BlockExt for Block {
             | Block::_DiscreteHeaderSection(_) => {}
             Block::PageBreak(page_break) => page_break.metadata.attributes = attributes,
             Block::UnorderedList(unordered_list) => unordered_list.metadata.attributes = attributes,
-            Block::OrderedList(ordered_list) => ordered_list.metadata.attributes = attributes,
+            Block::OrderedList(ordered_list) => {
+                ordered_list.start = OrderedList::start(&attributes);
+                ordered_list.metadata.attributes = attributes;
+            }
+            Block::CalloutList(callout_list) => callout_list.metadata.attributes = attributes,
             Block::DescriptionList(description_list) => {
                 description_list.metadata.attributes = attributes;
             }
@@ -67,6 +75,7 @@ impl BlockExt for Block {
             Block::PageBreak(page_break) => page_break.metadata.anchors = anchors,
             Block::UnorderedList(unordered_list) => unordered_list.metadata.anchors = anchors,
             Block::OrderedList(ordered_list) => ordered_list.metadata.anchors = anchors,
+            Block::CalloutList(callout_list) => callout_list.metadata.anchors = anchors,
             Block::DescriptionList(description_list) => description_list.metadata.anchors = anchors,
             Block::Section(section) => section.metadata.anchors = anchors,
             Block::DelimitedBlock(delimited_block) => delimited_block.metadata.anchors = anchors,
@@ -87,6 +96,7 @@ impl BlockExt for Block {
             Block::PageBreak(page_break) => page_break.title = title,
             Block::UnorderedList(unordered_list) => unordered_list.title = title,
             Block::OrderedList(ordered_list) => ordered_list.title = title,
+            Block::CalloutList(callout_list) => callout_list.title = title,
             Block::DescriptionList(description_list) => description_list.title = title,
             Block::Section(section) => section.title = title,
             Block::DelimitedBlock(delimited_block) => delimited_block.title = title,
@@ -108,6 +118,7 @@ impl BlockExt for Block {
             Block::PageBreak(page_break) => page_break.location = location,
             Block::UnorderedList(unordered_list) => unordered_list.location = location,
             Block::OrderedList(ordered_list) => ordered_list.location = location,
+            Block::CalloutList(callout_list) => callout_list.location = location,
             Block::DescriptionList(description_list) => description_list.location = location,
             Block::Section(section) => section.location = location,
             Block::DelimitedBlock(delimited_block) => delimited_block.location = location,
@@ -151,6 +162,7 @@ impl std::fmt::Display for Block {
             Block::PageBreak(_) => write!(f, "PageBreak"),
             Block::UnorderedList(_) => write!(f, "UnorderedList"),
             Block::OrderedList(_) => write!(f, "OrderedList"),
+            Block::CalloutList(_) => write!(f, "CalloutList"),
             Block::DescriptionList(_) => write!(f, "DescriptionList"),
             Block::Section(_) => write!(f, "Section"),
             Block::DelimitedBlock(_) => write!(f, "DelimitedBlock"),
@@ -170,7 +182,7 @@ impl Block {
         pairs: Pairs<Rule>,
         parent_location: Option<&Location>,
         parent_attributes: &mut DocumentAttributes,
-    ) -> Result<Block, Error> {
+    ) -> Result<Option<Block>, Error> {
         let mut title = Vec::new();
         let mut anchors = Vec::new();
         let mut metadata = BlockMetadata::default();
@@ -200,7 +212,7 @@ impl Block {
                     let delimited_block = DelimitedBlock::parse(
                         pair.into_inner(),
                         title.clone(),
-                        &metadata,
+                        &mut metadata,
                         &attributes,
                         parent_location,
                         parent_attributes,
@@ -226,13 +238,16 @@ impl Block {
                     }
                 }
                 Rule::paragraph => {
-                    let paragraph = Paragraph::parse(
+                    let Some(paragraph) = Paragraph::parse(
                         pair,
                         &mut metadata,
                         &mut attributes,
                         parent_location,
                         parent_attributes,
-                    )?;
+                    )?
+                    else {
+                        return Ok(None);
+                    };
                     if block.is_admonition() {
                         block.set_admonition_blocks(vec![paragraph]);
                     } else {
@@ -299,7 +314,7 @@ impl Block {
                     location.shift(parent_location);
 
                     // Run inline preprocessor before parsing inlines
-                    let mut preprocessor = InlinePreprocessor::new(parent_attributes.clone());
+                    let mut preprocessor = InlinePreprocessor::new(parent_attributes);
                     let processed = preprocessor.process(text, start_pos)?;
                     let mut pairs = InnerPestParser::parse(Rule::inlines, &processed.text)
                         .map_err(|e| Error::Parse(e.to_string()))?;
@@ -346,6 +361,21 @@ impl Block {
                             } else {
                                 metadata.style = Some(value);
                             }
+                        } else if matches!(metadata.style.as_deref(), Some("quote" | "verse")) {
+                            // Quote and verse blocks take the attribution and the
+                            // citation title as their second and third positional
+                            // attributes.
+                            if !attributes.contains_key("attribution") {
+                                attributes.insert(
+                                    "attribution".to_string(),
+                                    AttributeValue::String(value),
+                                );
+                            } else if !attributes.contains_key("citetitle") {
+                                attributes
+                                    .insert("citetitle".to_string(), AttributeValue::String(value));
+                            } else {
+                                attributes.insert(value, AttributeValue::None);
+                            }
                         } else {
                             attributes.insert(value, AttributeValue::None);
                         }
@@ -364,14 +394,21 @@ impl Block {
         if parent_location.is_none() {
             block.set_location(location);
         }
-        block.set_anchors(anchors);
+        // Anchors from `[[id]]` lines come before the one in the attribute list, and are
+        // set last so that setting the metadata doesn't drop them.
+        anchors.extend(metadata.anchors.iter().cloned());
+        // A block is identified by its first anchor unless its attribute list names it.
+        if metadata.id.is_none() {
+            metadata.id = anchors.first().cloned();
+        }
         block.set_metadata(metadata);
+        block.set_anchors(anchors);
         block.set_attributes(attributes);
         if !title.is_empty() {
             block.set_title(title);
         }
 
-        Ok(block)
+        Ok(Some(block))
     }
 
     pub(crate) fn parse_named_attribute(
@@ -395,7 +432,14 @@ impl Block {
                 Rule::role => metadata.roles.push(pair.as_str().to_string()),
                 Rule::option => metadata.options.push(pair.as_str().to_string()),
                 Rule::attribute_name => name = Some(pair.as_str().to_string()),
-                Rule::named_attribute_value => value = Some(pair.as_str().to_string()),
+                Rule::named_attribute_value => {
+                    let text = pair.as_str();
+                    let text = text
+                        .strip_prefix('"')
+                        .and_then(|text| text.strip_suffix('"'))
+                        .unwrap_or(text);
+                    value = Some(text.to_string());
+                }
                 Rule::EOI | Rule::comment => {}
                 unknown => unreachable!("{unknown:?}"),
             }
@@ -1,8 +1,12 @@
 use pest::{iterators::Pairs, Parser as _};
 
 use crate::{
-    blocks, Block, BlockMetadata, DelimitedBlock, DelimitedBlockType, DocumentAttributes,
-    ElementAttributes, Error, InlineNode, InnerPestParser, Location, Plain, Raw, Rule, Table,
+    blocks,
+    inlines::parse_inlines,
+    model::{resolve_substitutions, Substitute, VERBATIM},
+    AttributeValue, Block, BlockMetadata, DelimitedBlock, DelimitedBlockType, DocumentAttributes,
+    ElementAttributes, Error, InlineNode, InlinePreprocessor, InnerPestParser, Location, Plain,
+    Position, Raw, Rule, Substitution, Table,
 };
 
 impl DelimitedBlock {
@@ -10,7 +14,7 @@ impl DelimitedBlock {
     pub(crate) fn parse(
         pairs: Pairs<Rule>,
         title: Vec<InlineNode>,
-        metadata: &BlockMetadata,
+        metadata: &mut BlockMetadata,
         attributes: &ElementAttributes,
         parent_location: Option<&Location>,
         parent_attributes: &mut DocumentAttributes,
@@ -110,20 +114,22 @@ impl DelimitedBlock {
                     )?);
                 }
                 Rule::delimited_listing => {
-                    // IMPORTANT(nlopes): this assumes only one string in the verse, I'm not 100% sure this is a fact.
-                    inner =
-                        DelimitedBlockType::DelimitedListing(vec![InlineNode::PlainText(Plain {
-                            location: inner_location.clone(),
-                            content: text.clone(),
-                        })]);
+                    inner = DelimitedBlockType::DelimitedListing(Self::parse_verbatim(
+                        &text,
+                        &inner_location,
+                        metadata,
+                        attributes,
+                        parent_attributes,
+                    )?);
                 }
                 Rule::delimited_literal => {
-                    // IMPORTANT(nlopes): this assumes only one string in the verse, I'm not 100% sure this is a fact.
-                    inner =
-                        DelimitedBlockType::DelimitedLiteral(vec![InlineNode::PlainText(Plain {
-                            location: inner_location.clone(),
-                            content: text.clone(),
-                        })]);
+                    inner = DelimitedBlockType::DelimitedLiteral(Self::parse_verbatim(
+                        &text,
+                        &inner_location,
+                        metadata,
+                        attributes,
+                        parent_attributes,
+                    )?);
                 }
                 Rule::delimited_open => {
                     let pairs = InnerPestParser::parse(Rule::blocks, text.as_str())
@@ -148,6 +154,7 @@ impl DelimitedBlock {
                 Rule::delimited_table => {
                     inner = DelimitedBlockType::DelimitedTable(Table::parse(
                         &pair,
+                        &delimiter,
                         metadata,
                         attributes,
                         parent_attributes,
@@ -165,4 +172,202 @@ impl DelimitedBlock {
             location,
         }))
     }
+
+    /// Parses the content of a listing or literal block.
+    ///
+    /// The content is kept as is unless the `subs` attribute asks for more than the
+    /// verbatim substitutions, which are left to the converters. Attribute references
+    /// are replaced here, and quotes or macros have the content parsed as inlines, one
+    /// line at a time so that blank lines are kept.
+    fn parse_verbatim(
+        text: &str,
+        location: &Location,
+        metadata: &mut BlockMetadata,
+        attributes: &ElementAttributes,
+        parent_attributes: &mut DocumentAttributes,
+    ) -> Result<Vec<InlineNode>, Error> {
+        let Some(AttributeValue::String(subs)) = attributes.get("subs") else {
+            return Ok(vec![InlineNode::PlainText(Plain {
+                location: location.clone(),
+                content: text.to_string(),
+            })]);
+        };
+        let substitutions = resolve_substitutions(subs, VERBATIM);
+        let with_attributes = substitutions.contains(&Substitution::Attributes);
+        let content = if substitutions.contains(&Substitution::Quotes)
+            || substitutions.contains(&Substitution::Macros)
+        {
+            let mut content = Vec::new();
+            let mut offset = 0;
+            for (i, line) in text.split_inclusive('\n').enumerate() {
+                let body = line.trim_end_matches(['\r', '\n']);
+                let line_location = Location {
+                    absolute_start: location.absolute_start + offset,
+                    absolute_end: location.absolute_start + offset + body.len(),
+                    start: Position {
+                        line: location.start.line + i,
+                        column: 1,
+                    },
+                    end: Position {
+                        line: location.start.line + i,
+                        column: body.len().max(1),
+                    },
+                };
+                offset += line.len();
+                if !body.trim().is_empty() {
+                    content.extend(parse_verbatim_line(
+                        body,
+                        &line_location,
+                        with_attributes,
+                        parent_attributes,
+                    )?);
+                }
+                push_verbatim_text(&mut content, &line[body.len()..], &line_location);
+            }
+            content
+        } else {
+            let text = if with_attributes {
+                text.substitute(&[Substitution::Attributes], parent_attributes)
+            } else {
+                text.to_string()
+            };
+            vec![InlineNode::PlainText(Plain {
+                location: location.clone(),
+                content: text,
+            })]
+        };
+        metadata.substitutions = Some(substitutions);
+        Ok(content)
+    }
+}
+
+/// Parses a line of a verbatim block as inlines, replacing its attribute references
+/// first when `with_attributes` is set.
+fn parse_verbatim_line(
+    line: &str,
+    location: &Location,
+    with_attributes: bool,
+    parent_attributes: &mut DocumentAttributes,
+) -> Result<Vec<InlineNode>, Error> {
+    let processed = if with_attributes {
+        let mut preprocessor = InlinePreprocessor::new(parent_attributes);
+        let processed = preprocessor.process(line, location.absolute_start)?;
+        if processed.text.is_empty() {
+            return Ok(Vec::new());
+        }
+        Some(processed)
+    } else {
+        None
+    };
+    let text = processed.as_ref().map_or(line, |processed| &processed.text);
+    let mut pairs =
+        InnerPestParser::parse(Rule::inlines, text).map_err(|e| Error::Parse(e.to_string()))?;
+    parse_inlines(
+        pairs
+            .next()
+            .ok_or_else(|| Error::Parse("error parsing verbatim block content".to_string()))?,
+        processed.as_ref(),
+        Some(location),
+        parent_attributes,
+    )
+}
+
+/// Appends `text` to the plain text the verbatim content ends with, or as a new node.
+fn push_verbatim_text(content: &mut Vec<InlineNode>, text: &str, location: &Location) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(InlineNode::PlainText(plain)) = content.last_mut() {
+        plain.content.push_str(text);
+        plain.location.absolute_end = location.absolute_end + text.len();
+        return;
+    }
+    content.push(InlineNode::PlainText(Plain {
+        location: location.clone(),
+        content: text.to_string(),
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Block, DelimitedBlock, DelimitedBlockType, InlineNode, Substitution};
+
+    fn parse_block(input: &str) -> DelimitedBlock {
+        let doc = parse(input).unwrap();
+        let Some(Block::DelimitedBlock(block)) = doc.blocks.last() else {
+            panic!("expected a delimited block, got {:?}", doc.blocks);
+        };
+        block.clone()
+    }
+
+    fn content(block: &DelimitedBlock) -> &[InlineNode] {
+        match &block.inner {
+            DelimitedBlockType::DelimitedListing(inlines)
+            | DelimitedBlockType::DelimitedLiteral(inlines) => inlines,
+            other => panic!("expected a verbatim block, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_verbatim_content_is_raw_by_default() {
+        let block = parse_block(":version: 1.2.3\n\n----\ngem install acdc -v {version}\n----\n");
+        assert_eq!(block.metadata.substitutions, None);
+        assert_eq!(
+            crate::blocks::inlines_text(content(&block)),
+            "gem install acdc -v {version}"
+        );
+    }
+
+    #[test]
+    fn test_verbatim_subs_attributes() {
+        let block = parse_block(
+            ":version: 1.2.3\n\n[source,sh,subs=\"+attributes\"]\n----\ngem install acdc -v {version}\n----\n",
+        );
+        assert_eq!(
+            block.metadata.substitutions,
+            Some(vec![
+                Substitution::SpecialChars,
+                Substitution::Callouts,
+                Substitution::Attributes,
+            ])
+        );
+        assert_eq!(
+            crate::blocks::inlines_text(content(&block)),
+            "gem install acdc -v 1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_verbatim_subs_quotes() {
+        let block = parse_block("[subs=\"quotes\"]\n....\nsome *bold* text\n....\n");
+        assert_eq!(
+            block.metadata.substitutions,
+            Some(vec![Substitution::Quotes])
+        );
+        assert!(content(&block)
+            .iter()
+            .any(|node| matches!(node, InlineNode::BoldText(_))));
+    }
+
+    #[test]
+    fn test_verbatim_subs_keep_blank_lines() {
+        for subs in ["+macros", "+attributes,+quotes"] {
+            let block = parse_block(&format!(
+                ":name: two\n\n[subs=\"{subs}\"]\n----\nline *one*\n\nline {{name}}\n----\n"
+            ));
+            let expected = if subs.contains("attributes") {
+                "line one\n\nline two"
+            } else {
+                "line one\n\nline {name}"
+            };
+            assert_eq!(crate::blocks::inlines_text(content(&block)), expected);
+        }
+    }
+
+    #[test]
+    fn test_verbatim_subs_none() {
+        let block = parse_block(":version: 1.2.3\n\n[subs=none]\n----\n{version}\n----\n");
+        assert_eq!(block.metadata.substitutions, Some(vec![]));
+        assert_eq!(crate::blocks::inlines_text(content(&block)), "{version}");
+    }
 }
@@ -1,8 +1,12 @@
-use pest::iterators::Pairs;
+use pest::{
+    iterators::{Pair, Pairs},
+    Parser as _,
+};
 
 use crate::{
-    blocks, Anchor, Block, BlockMetadata, DescriptionList, DescriptionListDescription,
-    DescriptionListItem, DocumentAttributes, Error, InlineNode, Location, Rule,
+    inlines::parse_inlines, Anchor, Block, BlockMetadata, DescriptionList,
+    DescriptionListDescription, DescriptionListItem, DocumentAttributes, Error, InlineNode,
+    InlinePreprocessor, InnerPestParser, Location, Paragraph, Rule,
 };
 
 impl DescriptionList {
@@ -13,95 +17,243 @@ impl DescriptionList {
         _parent_location: Option<&Location>,
         parent_attributes: &mut DocumentAttributes,
     ) -> Result<Block, Error> {
-        let mut location = Location::default();
         // TODO(nlopes): handle parent_location
         let mut items = Vec::new();
 
         for pair in pairs {
             let location = Location::from_pair(&pair);
-            let mut blocks = Vec::new();
             match pair.as_rule() {
                 Rule::description_list_item => {
                     let mut anchors = Vec::new();
-                    let mut term = String::new();
+                    let mut term = Vec::new();
                     let mut delimiter = "";
-                    for inner_pair in pair.clone().into_inner() {
-                        let location = location.clone();
+                    let mut inline = None;
+                    let mut blocks = Vec::new();
+                    for inner_pair in pair.into_inner() {
                         match inner_pair.as_rule() {
                             Rule::description_list_term => {
-                                term = inner_pair.as_str().to_string();
+                                term = parse_text(&inner_pair, parent_attributes)?;
                             }
                             Rule::description_list_term_anchor => {
-                                anchors.push(Anchor::parse(inner_pair.into_inner()));
+                                if !inner_pair.as_str().is_empty() {
+                                    anchors.push(Anchor::parse(inner_pair.into_inner()));
+                                }
                             }
                             Rule::description_list_delimiter => {
                                 delimiter = inner_pair.as_str();
                             }
-                            Rule::blocks => {
-                                let description = blocks::parse(
-                                    inner_pair.into_inner(),
-                                    Some(&location),
-                                    parent_attributes,
-                                )?;
-                                items.push(DescriptionListItem {
-                                    anchors: anchors.clone(),
-                                    term: term.to_string(),
-                                    delimiter: delimiter.to_string(),
-                                    description: DescriptionListDescription::Blocks(description),
-                                    location,
-                                });
-                            }
                             Rule::description_list_inline => {
-                                let description = inner_pair.as_str();
-                                items.push(DescriptionListItem {
-                                    anchors: anchors.clone(),
-                                    term: term.to_string(),
-                                    delimiter: delimiter.to_string(),
-                                    description: DescriptionListDescription::Inline(
-                                        description.to_string(),
-                                    ),
-                                    location,
-                                });
+                                inline = Some(parse_text(&inner_pair, parent_attributes)?);
                             }
-                            Rule::EOI | Rule::comment => {}
-                            _ => {
-                                // If we get here, it means we have a block that is not a
-                                // description list
-                                blocks.push(Block::parse(
+                            Rule::block => {
+                                blocks.extend(Block::parse(
                                     inner_pair.into_inner(),
                                     Some(&location),
                                     parent_attributes,
                                 )?);
                             }
+                            Rule::EOI | Rule::comment => {}
+                            unknown => unreachable!("{unknown:?}"),
                         }
                     }
-                    if !blocks.is_empty() {
-                        items.push(DescriptionListItem {
-                            anchors,
-                            term: term.to_string(),
-                            delimiter: delimiter.to_string(),
-                            description: DescriptionListDescription::Blocks(blocks),
-                            location,
-                        });
-                    }
+                    items.push(DescriptionListItem {
+                        anchors,
+                        terms: vec![term],
+                        delimiter: delimiter.to_string(),
+                        description: inline.map_or(
+                            DescriptionListDescription::Blocks(blocks),
+                            DescriptionListDescription::Inline,
+                        ),
+                        location,
+                    });
                 }
                 Rule::EOI | Rule::comment => {}
                 unknown => unreachable!("{unknown:?}"),
             }
         }
 
-        location.start = items
-            .first()
-            .map_or(location.start, |item| item.location.start.clone());
-        location.end = items
-            .last()
-            .map_or(location.end, |item| item.location.end.clone());
-
+        let items = group_items(items);
         Ok(Block::DescriptionList(Self {
             title,
             metadata,
+            location: items_location(&items),
             items,
-            location,
         }))
     }
 }
+
+/// Parses the inlines of a term or of an inline description, leaving out the
+/// whitespace around it.
+fn parse_text(
+    pair: &Pair<Rule>,
+    parent_attributes: &mut DocumentAttributes,
+) -> Result<Vec<InlineNode>, Error> {
+    let text = pair.as_str().trim_end();
+    let trimmed = text.trim_start();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+    let start = pair.as_span().start() + text.len() - trimmed.len();
+    let mut preprocessor = InlinePreprocessor::new(parent_attributes);
+    let processed = preprocessor.process(trimmed, start)?;
+    if processed.text.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut pairs = InnerPestParser::parse(Rule::inlines, &processed.text)
+        .map_err(|e| Error::Parse(e.to_string()))?;
+    parse_inlines(
+        pairs.next().ok_or_else(|| {
+            tracing::error!("error parsing description list text");
+            Error::Parse("error parsing description list text".to_string())
+        })?,
+        Some(&processed),
+        Some(&Location::from_pair(pair)),
+        parent_attributes,
+    )
+}
+
+/// Builds the items of a list out of the items in the order they were found.
+///
+/// The delimiter of the first item is the list's: an item using another one starts a
+/// list nested in the description of the item before it, like in asciidoctor. A term
+/// without a description shares the description of the item that follows it.
+fn group_items(items: Vec<DescriptionListItem>) -> Vec<DescriptionListItem> {
+    let Some(delimiter) = items.first().map(|item| item.delimiter.clone()) else {
+        return items;
+    };
+    let mut grouped: Vec<DescriptionListItem> = Vec::new();
+    let mut nested = Vec::new();
+    for item in items {
+        if item.delimiter != delimiter {
+            nested.push(item);
+            continue;
+        }
+        if let Some(last) = grouped.last_mut() {
+            nest(last, std::mem::take(&mut nested));
+            if matches!(&last.description, DescriptionListDescription::Blocks(blocks) if blocks.is_empty())
+            {
+                last.anchors.extend(item.anchors);
+                last.terms.extend(item.terms);
+                last.description = item.description;
+                last.location.end = item.location.end;
+                last.location.absolute_end = item.location.absolute_end;
+                continue;
+            }
+        }
+        grouped.push(item);
+    }
+    if let Some(last) = grouped.last_mut() {
+        nest(last, nested);
+    }
+    grouped
+}
+
+/// Adds the list made of `items` at the end of the description of `item`.
+fn nest(item: &mut DescriptionListItem, items: Vec<DescriptionListItem>) {
+    if items.is_empty() {
+        return;
+    }
+    let items = group_items(items);
+    let location = items_location(&items);
+    let list = Block::DescriptionList(DescriptionList {
+        title: Vec::new(),
+        metadata: BlockMetadata::default(),
+        items,
+        location: location.clone(),
+    });
+    match &mut item.description {
+        DescriptionListDescription::Blocks(blocks) => blocks.push(list),
+        DescriptionListDescription::Inline(text) => {
+            // The text becomes a paragraph for the nested list to follow it.
+            let paragraph = Block::Paragraph(Paragraph {
+                metadata: BlockMetadata::default(),
+                title: Vec::new(),
+                content: std::mem::take(text),
+                location: item.location.clone(),
+            });
+            item.description = DescriptionListDescription::Blocks(vec![paragraph, list]);
+        }
+    }
+    item.location.end = location.end;
+    item.location.absolute_end = location.absolute_end;
+}
+
+fn items_location(items: &[DescriptionListItem]) -> Location {
+    let mut location = Location::default();
+    if let (Some(first), Some(last)) = (items.first(), items.last()) {
+        location.absolute_start = first.location.absolute_start;
+        location.start = first.location.start.clone();
+        location.absolute_end = last.location.absolute_end;
+        location.end = last.location.end.clone();
+    }
+    location
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        blocks::inlines_text, parse, Block, DescriptionList, DescriptionListDescription,
+        DescriptionListItem, InlineNode,
+    };
+
+    fn description_list(input: &str) -> DescriptionList {
+        let doc = parse(input).unwrap();
+        let Block::DescriptionList(list) = &doc.blocks[0] else {
+            panic!("expected a description list, got {:?}", doc.blocks[0]);
+        };
+        list.clone()
+    }
+
+    fn terms(item: &DescriptionListItem) -> Vec<String> {
+        item.terms.iter().map(|term| inlines_text(term)).collect()
+    }
+
+    #[test]
+    fn test_parse_terms_sharing_a_description() {
+        let list = description_list("CPU:: The brain\nRAM::\nMemory:: Storage\n");
+        let terms = list.items.iter().map(terms).collect::<Vec<_>>();
+        assert_eq!(terms, vec![vec!["CPU"], vec!["RAM", "Memory"]]);
+        let DescriptionListDescription::Inline(description) = &list.items[1].description else {
+            panic!(
+                "expected an inline description, got {:?}",
+                list.items[1].description
+            );
+        };
+        assert_eq!(inlines_text(description), "Storage");
+    }
+
+    #[test]
+    fn test_parse_nested_description_list() {
+        let list = description_list("Outer::\n  Inner::: Nested\n  Other::: More\nNext:: Item\n");
+        assert_eq!(list.items.len(), 2);
+        let DescriptionListDescription::Blocks(blocks) = &list.items[0].description else {
+            panic!("expected blocks, got {:?}", list.items[0].description);
+        };
+        let [Block::DescriptionList(nested)] = &blocks[..] else {
+            panic!("expected a nested description list, got {blocks:?}");
+        };
+        assert_eq!(nested.items.len(), 2);
+        assert_eq!(terms(&nested.items[0]), vec!["Inner"]);
+        assert_eq!(nested.items[1].delimiter, ":::");
+        assert_eq!(terms(&list.items[1]), vec!["Next"]);
+    }
+
+    #[test]
+    fn test_parse_inlines_in_terms_and_descriptions() {
+        let list = description_list("*CPU*:: The _brain_\n");
+        assert!(matches!(
+            &list.items[0].terms[0][..],
+            [InlineNode::BoldText(_)]
+        ));
+        let DescriptionListDescription::Inline(description) = &list.items[0].description else {
+            panic!(
+                "expected an inline description, got {:?}",
+                list.items[0].description
+            );
+        };
+        assert!(matches!(
+            &description[..],
+            [InlineNode::PlainText(_), InlineNode::ItalicText(_)]
+        ));
+    }
+}
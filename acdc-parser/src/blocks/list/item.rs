@@ -32,9 +32,11 @@ impl ListItem {
                     let start_pos = pair.as_span().start_pos().pos();
 
                     // Run inline preprocessor before parsing inlines
-                    let mut preprocessor =
-                        crate::InlinePreprocessor::new(parent_attributes.clone());
+                    let mut preprocessor = crate::InlinePreprocessor::new(parent_attributes);
                     let processed = preprocessor.process(text, start_pos)?;
+                    if processed.text.is_empty() {
+                        continue;
+                    }
 
                     // Now parse the processed text
                     let mut pairs = crate::InnerPestParser::parse(Rule::inlines, &processed.text)
@@ -58,6 +60,10 @@ impl ListItem {
                         Error::Parse(format!("error with list level depth: {e}"))
                     })?;
                 }
+                Rule::callout_level => {
+                    marker = pair.as_str().to_string();
+                    level = 1;
+                }
                 Rule::checklist_item_checked => checked = Some(true),
                 Rule::checklist_item_unchecked => checked = Some(false),
                 Rule::EOI | Rule::comment => {}
@@ -38,7 +38,7 @@ pub(crate) fn parse_list(
                 location.shift(parent_location);
 
                 // Run inline preprocessor before parsing inlines
-                let mut preprocessor = InlinePreprocessor::new(parent_attributes.clone());
+                let mut preprocessor = InlinePreprocessor::new(parent_attributes);
                 let processed = preprocessor.process(text, start_pos)?;
 
                 let mut pairs = InnerPestParser::parse(Rule::inlines, &processed.text)
@@ -54,7 +54,7 @@ pub(crate) fn parse_list(
                     parent_attributes,
                 )?;
             }
-            Rule::unordered_list | Rule::ordered_list => {
+            Rule::unordered_list | Rule::ordered_list | Rule::callout_list => {
                 block = Block::parse_simple_list(
                     pair.into_inner(),
                     title.clone(),
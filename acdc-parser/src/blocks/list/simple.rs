@@ -2,10 +2,29 @@ use pest::iterators::Pairs;
 use tracing::instrument;
 
 use crate::{
-    Block, BlockMetadata, DocumentAttributes, ElementAttributes, Error, InlineNode, ListItem,
-    Location, OrderedList, Rule, UnorderedList,
+    AttributeValue, Block, BlockMetadata, CalloutList, DocumentAttributes, ElementAttributes,
+    Error, InlineNode, ListItem, Location, OrderedList, Rule, UnorderedList,
 };
 
+impl OrderedList {
+    /// Reads the number of the first item from the `start` attribute, warning when it
+    /// isn't a number.
+    pub(crate) fn start(attributes: &ElementAttributes) -> Option<i32> {
+        let Some(AttributeValue::String(start)) = attributes.get("start") else {
+            return None;
+        };
+        start
+            .trim()
+            .parse()
+            .inspect_err(|_| tracing::warn!(start, "ordered list start is not a number, ignoring"))
+            .ok()
+    }
+
+    pub(crate) fn reversed(metadata: &BlockMetadata) -> bool {
+        metadata.options.iter().any(|option| option == "reversed")
+    }
+}
+
 impl Block {
     #[instrument(level = "trace")]
     pub(crate) fn parse_simple_list(
@@ -37,8 +56,12 @@ impl Block {
                         parent_attributes,
                     )?);
                 }
-                Rule::ordered_list_item => {
-                    kind = "ordered";
+                Rule::ordered_list_item | Rule::callout_list_item => {
+                    kind = if pair.as_rule() == Rule::callout_list_item {
+                        "callout"
+                    } else {
+                        "ordered"
+                    };
                     items.push(ListItem::parse(
                         pair.into_inner(),
                         parent_location,
@@ -55,6 +78,15 @@ impl Block {
         location.shift(parent_location);
         Ok(match kind {
             "ordered" => Block::OrderedList(OrderedList {
+                title,
+                start: OrderedList::start(&attributes),
+                reversed: OrderedList::reversed(&metadata),
+                metadata,
+                items,
+                marker,
+                location,
+            }),
+            "callout" => Block::CalloutList(CalloutList {
                 title,
                 metadata,
                 items,
@@ -10,6 +10,8 @@ mod video;
 
 use pest::iterators::Pairs;
 
+pub(crate) use section::{generate_id, inlines_text};
+
 use crate::{Block, DocumentAttribute, DocumentAttributes, Error, Location, Rule, Section};
 
 pub(crate) fn parse(
@@ -36,7 +38,7 @@ pub(crate) fn parse(
                 )?);
             }
             Rule::block => {
-                blocks.push(Block::parse(
+                blocks.extend(Block::parse(
                     pair.into_inner(),
                     parent_location,
                     parent_attributes,
@@ -20,7 +20,7 @@ impl Paragraph {
         attributes: &mut ElementAttributes,
         parent_location: Option<&Location>,
         parent_attributes: &mut DocumentAttributes,
-    ) -> Result<Block, Error> {
+    ) -> Result<Option<Block>, Error> {
         let mut outer_location = Location::from_pair(&pair);
         let pairs = pair.into_inner();
 
@@ -40,8 +40,13 @@ impl Paragraph {
                     let start_pos = pair.as_span().start_pos().pos();
 
                     // Run inline preprocessor before parsing inlines
-                    let mut preprocessor = InlinePreprocessor::new(parent_attributes.clone());
+                    let mut preprocessor = InlinePreprocessor::new(parent_attributes);
                     let processed = preprocessor.process(text, start_pos)?;
+                    // Every line may have been dropped for referencing a missing
+                    // attribute, which leaves nothing to parse.
+                    if processed.text.is_empty() {
+                        continue;
+                    }
 
                     // Now parse the processed text
                     let mut pairs = InnerPestParser::parse(Rule::inlines, &processed.text)
@@ -89,8 +94,11 @@ impl Paragraph {
                     location.shift(parent_location);
 
                     // Run inline preprocessor before parsing inlines
-                    let mut preprocessor = InlinePreprocessor::new(parent_attributes.clone());
+                    let mut preprocessor = InlinePreprocessor::new(parent_attributes);
                     let processed = preprocessor.process(text, start_pos)?;
+                    if processed.text.is_empty() {
+                        continue;
+                    }
 
                     let mut pairs = InnerPestParser::parse(Rule::inlines, &processed.text)
                         .map_err(|e| Error::Parse(e.to_string()))?;
@@ -111,9 +119,13 @@ impl Paragraph {
                 }
             }
         }
+        // A paragraph whose lines were all dropped is not a block at all.
+        if content.is_empty() {
+            return Ok(None);
+        }
         outer_location.shift(parent_location);
         if let Some(admonition) = admonition {
-            Ok(Block::Admonition(Admonition {
+            Ok(Some(Block::Admonition(Admonition {
                 metadata: metadata.clone(),
                 title,
                 blocks: vec![Block::Paragraph(Self {
@@ -124,14 +136,14 @@ impl Paragraph {
                 })],
                 location: outer_location.clone(),
                 variant: AdmonitionVariant::from_str(admonition)?,
-            }))
+            })))
         } else {
-            Ok(Block::Paragraph(Self {
+            Ok(Some(Block::Paragraph(Self {
                 metadata: metadata.clone(),
                 title,
                 content,
                 location: outer_location.clone(),
-            }))
+            })))
         }
     }
 
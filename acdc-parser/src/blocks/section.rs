@@ -2,8 +2,8 @@ use pest::{iterators::Pair, Parser as _};
 
 use crate::{
     inlines::parse_inlines, model::DiscreteHeaderSection, Anchor, AttributeValue, Block,
-    BlockMetadata, DocumentAttributes, ElementAttributes, Error, InlinePreprocessor,
-    InnerPestParser, Location, Rule, Section,
+    BlockMetadata, DocumentAttributes, ElementAttributes, Error, InlineMacro, InlineNode,
+    InlinePreprocessor, InnerPestParser, Location, Rule, Section,
 };
 
 // TODO(nlopes): this might be parser as part of the inner content of a delimited block
@@ -35,7 +35,7 @@ impl Section {
                 Rule::section_title => {
                     let mut inner_location = Location::from_pair(&inner_pair);
                     inner_location.shift(parent_location);
-                    let mut preprocessor = InlinePreprocessor::new(parent_attributes.clone());
+                    let mut preprocessor = InlinePreprocessor::new(parent_attributes);
                     let processed =
                         preprocessor.process(inner_pair.as_str(), inner_pair.as_span().start())?;
 
@@ -73,7 +73,7 @@ impl Section {
                                 )?);
                             }
                             Rule::block => {
-                                content.push(Block::parse(
+                                content.extend(Block::parse(
                                     pair.into_inner(),
                                     parent_location,
                                     parent_attributes,
@@ -102,12 +102,14 @@ impl Section {
                 }
                 Rule::named_attribute => {
                     Block::parse_named_attribute(
-                        pair.clone().into_inner(),
+                        inner_pair.clone().into_inner(),
                         &mut attributes,
                         &mut metadata,
                     );
                 }
-                Rule::anchor => anchors.push(Anchor::parse(pair.clone().into_inner())),
+                Rule::anchor => anchors.push(Anchor::parse(inner_pair.clone().into_inner())),
+                Rule::option => metadata.options.push(inner_pair.as_str().to_string()),
+                Rule::role => metadata.roles.push(inner_pair.as_str().to_string()),
 
                 Rule::EOI | Rule::comment | Rule::open_sb | Rule::close_sb => {}
                 unknown => unreachable!("{:?}", unknown),
@@ -124,12 +126,95 @@ impl Section {
                 content,
             }));
         }
+        metadata.anchors = anchors;
         Ok(Block::Section(Self {
             metadata,
             title,
             level,
             content,
             location,
+            generated_id: None,
         }))
     }
 }
+
+impl Section {
+    /// Returns the id of the section.
+    ///
+    /// That is its explicit id if it has one or, otherwise, the one generated from its
+    /// title while parsing. Sections without either (e.g. with `sectids` unset) get one
+    /// generated the same way asciidoctor does by default (`_` prefix and separator).
+    #[must_use]
+    pub fn id(&self) -> String {
+        if let Some(anchor) = &self.metadata.id {
+            return anchor.id.clone();
+        }
+        if let Some(anchor) = self.metadata.anchors.first() {
+            return anchor.id.clone();
+        }
+        if let Some(id) = &self.generated_id {
+            return id.clone();
+        }
+        generate_id(&self.title, "_", "_")
+    }
+
+    /// Whether the section has an id of its own, rather than one generated from its title.
+    pub(crate) fn has_explicit_id(&self) -> bool {
+        self.metadata.id.is_some() || !self.metadata.anchors.is_empty()
+    }
+}
+
+/// Generates an id from a section title, e.g. `_getting_started` for "Getting Started".
+///
+/// The title is lowercased and every run of characters other than letters, digits, `-`
+/// and `.` becomes a single `separator`, then `prefix` is put in front.
+pub(crate) fn generate_id(title: &[InlineNode], prefix: &str, separator: &str) -> String {
+    let mut id = String::from(prefix);
+    let mut last_was_separator = true;
+    for c in inlines_text(title).to_lowercase().chars() {
+        if c.is_alphanumeric() || c == '-' || c == '.' {
+            id.push(c);
+            last_was_separator = false;
+        } else if !last_was_separator {
+            id.push_str(separator);
+            last_was_separator = true;
+        }
+    }
+    if id.len() > prefix.len() && last_was_separator {
+        id.truncate(id.len() - separator.len());
+    }
+    id
+}
+
+pub(crate) fn inlines_text(inlines: &[InlineNode]) -> String {
+    let mut text = String::new();
+    for inline in inlines {
+        match inline {
+            InlineNode::PlainText(plain) => text.push_str(&plain.content),
+            InlineNode::RawText(raw) => text.push_str(&raw.content),
+            InlineNode::BoldText(bold) => text.push_str(&inlines_text(&bold.content)),
+            InlineNode::ItalicText(italic) => text.push_str(&inlines_text(&italic.content)),
+            InlineNode::MonospaceText(monospace) => {
+                text.push_str(&inlines_text(&monospace.content));
+            }
+            InlineNode::HighlightText(highlight) => {
+                text.push_str(&inlines_text(&highlight.content));
+            }
+            InlineNode::SubscriptText(subscript) => {
+                text.push_str(&inlines_text(&subscript.content));
+            }
+            InlineNode::SuperscriptText(superscript) => {
+                text.push_str(&inlines_text(&superscript.content));
+            }
+            InlineNode::Macro(InlineMacro::Link(link)) => {
+                text.push_str(link.text.as_ref().unwrap_or(&link.target));
+            }
+            InlineNode::Macro(InlineMacro::Stem(stem)) => text.push_str(&stem.content),
+            InlineNode::Macro(InlineMacro::CrossReference(xref)) => {
+                text.push_str(xref.text.as_ref().unwrap_or(&xref.target));
+            }
+            _ => {}
+        }
+    }
+    text
+}
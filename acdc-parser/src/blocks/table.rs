@@ -1,25 +1,40 @@
 use pest::iterators::Pair;
 
 use crate::{
-    AttributeValue, BlockMetadata, DocumentAttributes, ElementAttributes, Error, Location, Rule,
-    Table, TableColumn, TableRow,
+    AttributeValue, BlockMetadata, ColumnFormat, ColumnStyle, ColumnWidth, DocumentAttributes,
+    ElementAttributes, Error, HorizontalAlignment, Location, Rule, Table, TableColumn, TableRow,
+    VerticalAlignment,
 };
 
 impl Table {
     pub(crate) fn parse(
         pair: &Pair<Rule>,
+        delimiter: &str,
         metadata: &BlockMetadata,
         attributes: &ElementAttributes,
         parent_attributes: &mut DocumentAttributes,
     ) -> Result<Self, Error> {
-        let mut separator = "|".to_string();
+        // The delimiter itself tells us the default format: `,===` is csv, `:===` is
+        // dsv and both `|===` and `!===` (used for nested tables) are psv.
+        let mut separator = match delimiter.chars().next() {
+            Some(',') => ",",
+            Some(':') => ":",
+            Some('!') => "!",
+            _ => "|",
+        }
+        .to_string();
+        let mut psv = separator == "|" || separator == "!";
+        let mut csv = separator == ",";
         if let Some(AttributeValue::String(format)) = attributes.get("format") {
             separator = match format.as_str() {
+                "psv" => separator,
                 "csv" => ",".to_string(),
                 "dsv" => ":".to_string(),
                 "tsv" => "\t".to_string(),
                 format => unimplemented!("unkown table format: {format}"),
             };
+            psv = format == "psv";
+            csv = format == "csv";
         }
         // override the separator if it is provided in the document
         separator = attributes
@@ -27,16 +42,23 @@ impl Table {
             .unwrap_or(&AttributeValue::String(separator.clone()))
             .to_string();
 
-        let ncols = if let Some(AttributeValue::String(cols)) = attributes.get("cols") {
-            Some(cols.split(',').count())
+        let columns = if let Some(AttributeValue::String(cols)) = attributes.get("cols") {
+            ColumnFormat::parse_all(cols)
         } else {
-            None
+            Vec::new()
         };
+        let ncols = (!columns.is_empty()).then_some(columns.len());
 
         // Set this to true if the user mandates it!
         let mut has_header = metadata.options.contains(&String::from("header"));
 
-        let raw_rows = Self::parse_rows(pair.as_str(), &separator, &mut has_header);
+        let (cells, first_line_cells) = if psv {
+            Self::parse_psv_cells(pair.as_str(), &separator, &mut has_header)
+        } else if csv {
+            Self::parse_csv_cells(pair.as_str(), &separator, &mut has_header)
+        } else {
+            Self::parse_dsv_cells(pair.as_str(), &separator, &mut has_header)
+        };
 
         // If the user forces a noheader, we should not have a header, so after we've
         // tried to figure out if there are any headers, we should set it to false one
@@ -46,114 +68,672 @@ impl Table {
         }
         let has_footer = metadata.options.contains(&String::from("footer"));
 
+        // Without an explicit `cols` attribute, the number of columns taken by the cells
+        // on the first line determines the number of columns.
+        let ncols = ncols.or(first_line_cells).unwrap_or(cells.len()).max(1);
+        let raw_rows = build_rows(cells, ncols)?;
+
         let mut header = None;
         let mut footer = None;
         let mut rows = Vec::new();
 
         for (i, row) in raw_rows.iter().enumerate() {
-            let columns = row
+            // Cells without a style of their own take the one of their column, except in
+            // the header.
+            let cells = row
                 .iter()
-                .filter(|cell| !cell.is_empty())
-                .map(|cell| parse_table_cell(cell, parent_attributes))
+                .map(|cell| {
+                    let style = cell.style.clone().or_else(|| {
+                        let format = columns.get(cell.column).filter(|_| !has_header)?;
+                        format.style.letter().map(String::from)
+                    });
+                    parse_table_cell(cell, style, parent_attributes)
+                })
                 .collect::<Result<Vec<_>, _>>()?;
 
-            // validate that if we have ncols we have the same number of columns in each row
-            if let Some(ncols) = ncols {
-                if columns.len() != ncols {
-                    return Err(Error::Parse(format!(
-                        "expected table row with {ncols} columns, found {} columns",
-                        columns.len()
-                    )));
-                }
-            }
-
             // if we have a header, we need to add the columns we have to the header
             if has_header {
-                header = Some(TableRow { columns });
+                header = Some(TableRow { columns: cells });
                 has_header = false;
                 continue;
             }
 
             // if we have a footer, we need to add the columns we have to the footer
             if has_footer && i == raw_rows.len() - 1 {
-                footer = Some(TableRow { columns });
+                footer = Some(TableRow { columns: cells });
                 continue;
             }
 
             // if we get here, these columns are a row
-            rows.push(TableRow { columns });
+            rows.push(TableRow { columns: cells });
         }
 
         Ok(Self {
             header,
             footer,
             rows,
+            columns,
             location: Location::default(),
         })
     }
 
-    fn parse_rows(text: &str, separator: &str, has_header: &mut bool) -> Vec<Vec<String>> {
-        let mut location = Location::default();
+    /// Detects an implicit header row: a first line that isn't empty, followed by an
+    /// empty line.
+    fn detect_header(i: usize, line: &str, has_header: &mut bool) {
+        // If we are in the first row and it is empty, we should not have a header,
+        // set it to false and move on.
+        if i == 0 && line.is_empty() {
+            *has_header = false;
+        }
+
+        // If we're in the first row and it is empty, and we've already added
+        // something to the rows, then we should have a header
+        if i == 1 && line.is_empty() {
+            *has_header = true;
+        }
+    }
+
+    /// Splits prefix-separated (psv) table content into cells.
+    ///
+    /// Each cell starts at a separator and may be preceded by a cell specifier (e.g.
+    /// `a|` or `2+h|`). Cell content keeps its line breaks so that `a`-style cells can
+    /// hold arbitrary blocks, including nested tables. Also returns the number of
+    /// columns taken by the cells on the first line.
+    fn parse_psv_cells(
+        text: &str,
+        separator: &str,
+        has_header: &mut bool,
+    ) -> (Vec<RawCell>, Option<usize>) {
+        let mut cells = Vec::new();
+        let mut current: Option<CellSpec> = None;
+        let mut buffer = String::new();
+        let mut first_line_cells = None;
+        for (i, line) in text.lines().enumerate() {
+            Self::detect_header(i, line.trim(), has_header);
 
-        let mut rows = Vec::new();
-        let mut row_string = String::new();
-        for (i, row) in text.lines().enumerate() {
-            let row = row.trim();
-            // If we are in the first row and it is empty, we should not have a header,
-            // set it to false and move on.
-            if i == 0 && row.is_empty() {
-                *has_header = false;
+            let mut rest = line;
+            let mut line_cells = 0;
+            loop {
+                match rest.find(separator) {
+                    Some(idx) if rest[..idx].ends_with('\\') => {
+                        buffer.push_str(&rest[..idx - 1]);
+                        buffer.push_str(separator);
+                        rest = &rest[idx + separator.len()..];
+                    }
+                    Some(idx) => {
+                        buffer.push_str(&rest[..idx]);
+                        let spec = take_cell_spec(&mut buffer);
+                        // Anything before the very first separator is not a cell.
+                        if let Some(spec) = current.take() {
+                            RawCell::push(&mut cells, spec, &buffer);
+                        }
+                        buffer.clear();
+                        line_cells += spec.repeat * spec.colspan;
+                        current = Some(spec);
+                        rest = &rest[idx + separator.len()..];
+                    }
+                    None => {
+                        buffer.push_str(rest);
+                        buffer.push('\n');
+                        break;
+                    }
+                }
+            }
+            if first_line_cells.is_none() && line_cells > 0 {
+                first_line_cells = Some(line_cells);
+            }
+        }
+        if let Some(spec) = current {
+            RawCell::push(&mut cells, spec, &buffer);
+        }
+        (cells, first_line_cells)
+    }
+
+    /// Splits comma-separated table content into cells.
+    ///
+    /// Fields may be enclosed in double quotes, in which case they can hold the
+    /// separator, line breaks and (doubled) double quotes. Also returns the number of
+    /// fields in the first record. Like in other tables, a first record followed by an
+    /// empty line is the header.
+    fn parse_csv_cells(
+        text: &str,
+        separator: &str,
+        has_header: &mut bool,
+    ) -> (Vec<RawCell>, Option<usize>) {
+        let mut cells = Vec::new();
+        let mut first_line_cells = None;
+        let mut records = 0;
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut record_start = 0;
+        for (i, line) in text.lines().enumerate() {
+            if quoted {
+                field.push('\n');
+            } else {
+                if line.trim().is_empty() {
+                    if i == 0 {
+                        *has_header = false;
+                    } else if records == 1 && first_line_cells.is_some() {
+                        *has_header = true;
+                    }
+                    continue;
+                }
+                record_start = cells.len();
+            }
+            let mut rest = line;
+            while let Some(c) = rest.chars().next() {
+                if quoted {
+                    if let Some(tail) = rest.strip_prefix("\"\"") {
+                        field.push('"');
+                        rest = tail;
+                        continue;
+                    }
+                    if c == '"' {
+                        quoted = false;
+                    } else {
+                        field.push(c);
+                    }
+                } else if let Some(tail) = rest.strip_prefix(separator) {
+                    RawCell::push(&mut cells, CellSpec::default(), &field);
+                    field.clear();
+                    rest = tail;
+                    continue;
+                } else if c == '"' && field.trim().is_empty() {
+                    field.clear();
+                    quoted = true;
+                } else {
+                    field.push(c);
+                }
+                rest = &rest[c.len_utf8()..];
+            }
+            if !quoted {
+                RawCell::push(&mut cells, CellSpec::default(), &field);
+                field.clear();
+                records += 1;
+                if records == 1 {
+                    first_line_cells = Some(cells.len() - record_start);
+                }
+            }
+        }
+        if quoted {
+            RawCell::push(&mut cells, CellSpec::default(), &field);
+        }
+        (cells, first_line_cells)
+    }
+
+    /// Splits delimiter-separated (dsv, tsv) table content into cells, one row per
+    /// line. Also returns the number of cells found on the first line.
+    fn parse_dsv_cells(
+        text: &str,
+        separator: &str,
+        has_header: &mut bool,
+    ) -> (Vec<RawCell>, Option<usize>) {
+        let mut cells = Vec::new();
+        let mut first_line_cells = None;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            Self::detect_header(i, line, has_header);
+            if line.is_empty() {
                 continue;
             }
+            let before = cells.len();
+            for cell in line.split(separator) {
+                RawCell::push(&mut cells, CellSpec::default(), cell);
+            }
+            first_line_cells.get_or_insert(cells.len() - before);
+        }
+        (cells, first_line_cells)
+    }
+}
 
-            // If we're in the first row and it is empty, and we've already added
-            // something to the rows, then we should have a header
-            if i == 1 && row.is_empty() {
-                *has_header = true;
+impl ColumnFormat {
+    /// Parses a `cols` attribute: either a plain number of columns (`cols="3"`) or a
+    /// list of column specs separated by commas or semicolons, where `<n>*` repeats a
+    /// spec `n` times (`cols="3*,2"`).
+    fn parse_all(cols: &str) -> Vec<Self> {
+        if let Ok(count) = cols.trim().parse::<usize>() {
+            return vec![Self::default(); count];
+        }
+        let mut columns = Vec::new();
+        for spec in cols.split([',', ';']) {
+            let mut spec = spec.trim();
+            let mut attempt = spec;
+            let mut repeat = 1;
+            if let Some(n) = take_number(&mut attempt) {
+                if let Some(tail) = attempt.strip_prefix('*') {
+                    repeat = n;
+                    spec = tail;
+                }
             }
+            let format = Self::parse(spec).unwrap_or_else(|| {
+                tracing::warn!(spec, "invalid column spec, using the default one");
+                Self::default()
+            });
+            columns.extend(std::iter::repeat_n(format, repeat));
+        }
+        columns
+    }
+
+    /// Parses a column spec, i.e. `[<halign>][.<valign>][<width>][<style>]` where the
+    /// width is a number, a percentage or `~` for autowidth.
+    fn parse(spec: &str) -> Option<Self> {
+        let mut format = Self::default();
+        let mut rest = spec;
 
-            if row.is_empty() && !row_string.is_empty() {
-                let columns = row_string
-                    .split(separator)
-                    .map(str::trim)
-                    .map(str::to_string)
-                    .collect();
-                row_string.clear();
-                rows.push(columns);
+        if let Some(halign) = rest.chars().next().and_then(horizontal_alignment) {
+            format.halign = halign;
+            rest = &rest[1..];
+        }
+        if let Some(tail) = rest.strip_prefix('.') {
+            format.valign = tail.chars().next().and_then(vertical_alignment)?;
+            rest = &tail[1..];
+        }
+
+        if let Some(tail) = rest.strip_prefix('~') {
+            format.width = ColumnWidth::Auto;
+            rest = tail;
+        } else if let Some(width) = take_number(&mut rest) {
+            format.width = match rest.strip_prefix('%') {
+                Some(tail) => {
+                    rest = tail;
+                    ColumnWidth::Percentage(width)
+                }
+                None => ColumnWidth::Proportional(width),
+            };
+        }
+
+        if let Some(c) = rest.chars().next() {
+            format.style = column_style(c)?;
+            rest = &rest[1..];
+        }
+
+        rest.is_empty().then_some(format)
+    }
+}
+
+fn horizontal_alignment(c: char) -> Option<HorizontalAlignment> {
+    match c {
+        '<' => Some(HorizontalAlignment::Left),
+        '^' => Some(HorizontalAlignment::Center),
+        '>' => Some(HorizontalAlignment::Right),
+        _ => None,
+    }
+}
+
+fn vertical_alignment(c: char) -> Option<VerticalAlignment> {
+    match c {
+        '<' => Some(VerticalAlignment::Top),
+        '^' => Some(VerticalAlignment::Middle),
+        '>' => Some(VerticalAlignment::Bottom),
+        _ => None,
+    }
+}
+
+fn column_style(c: char) -> Option<ColumnStyle> {
+    match c {
+        'a' => Some(ColumnStyle::AsciiDoc),
+        'd' => Some(ColumnStyle::Default),
+        'e' => Some(ColumnStyle::Emphasis),
+        'h' => Some(ColumnStyle::Header),
+        'l' => Some(ColumnStyle::Literal),
+        'm' => Some(ColumnStyle::Monospace),
+        's' => Some(ColumnStyle::Strong),
+        _ => None,
+    }
+}
+
+/// Groups cells into rows of `ncols` columns, taking into account the columns taken by
+/// cells spanning several columns, or by cells from previous rows spanning several rows.
+fn build_rows(cells: Vec<RawCell>, ncols: usize) -> Result<Vec<Vec<RawCell>>, Error> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    // Rows still taken in each column by cells spanning down from earlier rows, and the
+    // rows that the cells of the current row take in the following ones.
+    let mut spanned = vec![0; ncols];
+    let mut started = vec![0; ncols];
+    let mut column = skip_spanned(0, &spanned);
+    for mut cell in cells {
+        cell.column = column;
+        if cell.rowspan > 1 {
+            for rows in started.iter_mut().skip(column).take(cell.colspan) {
+                *rows = cell.rowspan - 1;
             }
+        }
+        column = skip_spanned(column + cell.colspan, &spanned);
+        row.push(cell);
+        if column < ncols {
+            continue;
+        }
+        // validate that we have the same number of columns in each row
+        if column > ncols {
+            return Err(Error::Parse(format!(
+                "expected table row with {ncols} columns, found {column} columns"
+            )));
+        }
+        rows.push(std::mem::take(&mut row));
+        for (rows, started) in spanned.iter_mut().zip(&mut started) {
+            *rows = rows.saturating_sub(1).max(std::mem::take(started));
+        }
+        column = skip_spanned(0, &spanned);
+    }
+    // Like asciidoctor, cells fill the rows in order whatever line they're on, so a
+    // number of cells that isn't a multiple of the number of columns leaves the last row
+    // short. It's padded with empty cells so that every row has all its columns.
+    if !row.is_empty() {
+        let missing = (column..ncols).filter(|&i| spanned[i] == 0).count();
+        tracing::warn!(
+            row = rows.len() + 1,
+            columns = ncols,
+            cells = ncols - missing,
+            "table cells don't fill the last row, padding it with empty cells"
+        );
+        row.extend(std::iter::repeat_n(RawCell::empty(), missing));
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// The first column from `column` on that isn't taken by a cell spanning down from an
+/// earlier row.
+fn skip_spanned(mut column: usize, spanned: &[usize]) -> usize {
+    while spanned.get(column).is_some_and(|rows| *rows > 0) {
+        column += 1;
+    }
+    column
+}
 
-            // Adjust the location
-            if row_string.is_empty() {
-                location.start.line = i + 1;
-                location.start.column = 1;
+/// A table cell as found in the source, before its content is parsed.
+#[derive(Clone, Debug)]
+struct RawCell {
+    style: Option<String>,
+    colspan: usize,
+    rowspan: usize,
+    /// The column the cell starts in, once `build_rows` has laid it out.
+    column: usize,
+    text: String,
+}
+
+impl RawCell {
+    fn empty() -> Self {
+        RawCell {
+            style: None,
+            colspan: 1,
+            rowspan: 1,
+            column: 0,
+            text: String::new(),
+        }
+    }
+
+    fn push(cells: &mut Vec<RawCell>, spec: CellSpec, text: &str) {
+        let cell = RawCell {
+            style: spec.style,
+            colspan: spec.colspan,
+            rowspan: spec.rowspan,
+            column: 0,
+            text: text.trim().to_string(),
+        };
+        cells.extend(std::iter::repeat_n(cell, spec.repeat));
+    }
+}
+
+/// A cell specifier, i.e. `[<n>*][<colspan>][.<rowspan>+][<halign>][.<valign>][<style>]`.
+#[derive(Debug)]
+struct CellSpec {
+    repeat: usize,
+    colspan: usize,
+    rowspan: usize,
+    style: Option<String>,
+}
+
+impl Default for CellSpec {
+    fn default() -> Self {
+        Self {
+            repeat: 1,
+            colspan: 1,
+            rowspan: 1,
+            style: None,
+        }
+    }
+}
+
+impl CellSpec {
+    fn parse(spec: &str) -> Option<Self> {
+        if spec.is_empty() {
+            return None;
+        }
+        let mut result = Self::default();
+        let mut rest = spec;
+
+        // duplication factor
+        let mut attempt = rest;
+        if let Some(repeat) = take_number(&mut attempt) {
+            if let Some(tail) = attempt.strip_prefix('*') {
+                result.repeat = repeat;
+                rest = tail;
             }
-            location.end.line = i + 1;
-            location.end.column = row.len() + 1;
+        }
 
-            // Add the row to the row string
-            row_string.push_str(row);
+        // span factor
+        let mut attempt = rest;
+        let colspan = take_number(&mut attempt);
+        let rowspan = match attempt.strip_prefix('.') {
+            Some(tail) => {
+                attempt = tail;
+                take_number(&mut attempt)
+            }
+            None => None,
+        };
+        if colspan.is_some() || rowspan.is_some() {
+            rest = attempt.strip_prefix('+')?;
+            result.colspan = colspan.unwrap_or(1).max(1);
+            result.rowspan = rowspan.unwrap_or(1).max(1);
         }
-        if !row_string.is_empty() {
-            let columns = row_string
-                .split(separator)
-                .map(str::trim)
-                .map(str::to_string)
-                .collect();
-            rows.push(columns);
+
+        // horizontal and vertical alignment
+        rest = rest.strip_prefix(['<', '^', '>']).unwrap_or(rest);
+        if let Some(tail) = rest.strip_prefix('.') {
+            rest = tail.strip_prefix(['<', '^', '>'])?;
+        }
+
+        // style
+        if let Some(style) = rest.chars().next().filter(|c| "adehlmsv".contains(*c)) {
+            result.style = Some(style.to_string());
+            rest = &rest[1..];
         }
-        rows
+
+        rest.is_empty().then_some(result)
+    }
+}
+
+fn take_number(text: &mut &str) -> Option<usize> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let number = text[..end].parse().ok()?;
+    *text = &text[end..];
+    Some(number)
+}
+
+/// Removes a trailing cell specifier from `buffer` (the text right before a separator)
+/// and returns it, or returns the default specifier if there isn't one.
+fn take_cell_spec(buffer: &mut String) -> CellSpec {
+    let start = buffer.rfind(char::is_whitespace).map_or(0, |idx| idx + 1);
+    match CellSpec::parse(&buffer[start..]) {
+        Some(spec) => {
+            buffer.truncate(start);
+            spec
+        }
+        None => CellSpec::default(),
     }
 }
 
 fn parse_table_cell(
-    text: &str,
+    cell: &RawCell,
+    style: Option<String>,
     parent_attributes: &mut DocumentAttributes,
 ) -> Result<TableColumn, Error> {
     use pest::Parser as _;
 
-    let parse = crate::InnerPestParser::parse(Rule::block, text)
+    if cell.text.is_empty() {
+        return Ok(TableColumn {
+            content: Vec::new(),
+            style,
+            colspan: cell.colspan,
+            rowspan: cell.rowspan,
+        });
+    }
+
+    // AsciiDoc cells can hold any number of blocks, the rest hold a single one.
+    let rule = if style.as_deref() == Some("a") {
+        Rule::blocks
+    } else {
+        Rule::block
+    };
+    let parse = crate::InnerPestParser::parse(rule, &cell.text)
         .map_err(|e| Error::Parse(format!("error parsing table cell: {e}")))?;
     let content = crate::blocks::parse(parse, Some(&Location::default()), parent_attributes)?;
 
-    Ok(TableColumn { content })
+    Ok(TableColumn {
+        content,
+        style,
+        colspan: cell.colspan,
+        rowspan: cell.rowspan,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parse, Block, ColumnFormat, ColumnStyle, ColumnWidth, DelimitedBlockType,
+        HorizontalAlignment, Table, VerticalAlignment,
+    };
+
+    fn parse_table(input: &str) -> Table {
+        let doc = parse(input).unwrap();
+        let Block::DelimitedBlock(block) = &doc.blocks[0] else {
+            panic!("expected a delimited block, got {:?}", doc.blocks[0]);
+        };
+        let DelimitedBlockType::DelimitedTable(table) = &block.inner else {
+            panic!("expected a table, got {:?}", block.inner);
+        };
+        table.clone()
+    }
+
+    fn text(table: &Table) -> Vec<Vec<String>> {
+        table
+            .rows
+            .iter()
+            .map(|row| {
+                row.columns
+                    .iter()
+                    .map(|column| match column.content.first() {
+                        Some(Block::Paragraph(paragraph)) => {
+                            crate::blocks::inlines_text(&paragraph.content)
+                        }
+                        _ => String::new(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_csv_quoted_fields() {
+        let table = parse_table(
+            "[%noheader,format=csv]\n|===\nName,Notes\n\n\"Smith, J\",\"Spans\ntwo lines\"\nDoe,\"Says \"\"hi\"\"\"\n|===\n",
+        );
+        assert!(table.header.is_none());
+        assert_eq!(
+            text(&table),
+            vec![
+                vec!["Name", "Notes"],
+                vec!["Smith, J", "Spans\ntwo lines"],
+                vec!["Doe", "Says \"hi\""],
+            ]
+        );
+
+        let table = parse_table("[format=csv]\n|===\nName,Notes\n\nDoe,\"a\nb\"\n|===\n");
+        assert!(table.header.is_some());
+        assert_eq!(text(&table), vec![vec!["Doe", "a\nb"]]);
+    }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_cells_reflow_into_columns() {
+        let table = parse_table("[cols=\"2\"]\n|===\n|a |b |c\n|d\n|e\n|===\n");
+        assert_eq!(
+            text(&table),
+            vec![vec!["a", "b"], vec!["c", "d"], vec!["e", ""]]
+        );
+        assert!(logs_contain(
+            "table cells don't fill the last row, padding it with empty cells"
+        ));
+    }
+
+    #[test]
+    fn test_column_specs() {
+        assert_eq!(
+            ColumnFormat::parse_all("1,2,>3,^2e,.^4a"),
+            vec![
+                ColumnFormat::default(),
+                ColumnFormat {
+                    width: ColumnWidth::Proportional(2),
+                    ..ColumnFormat::default()
+                },
+                ColumnFormat {
+                    width: ColumnWidth::Proportional(3),
+                    halign: HorizontalAlignment::Right,
+                    ..ColumnFormat::default()
+                },
+                ColumnFormat {
+                    width: ColumnWidth::Proportional(2),
+                    halign: HorizontalAlignment::Center,
+                    style: ColumnStyle::Emphasis,
+                    ..ColumnFormat::default()
+                },
+                ColumnFormat {
+                    width: ColumnWidth::Proportional(4),
+                    valign: VerticalAlignment::Middle,
+                    style: ColumnStyle::AsciiDoc,
+                    ..ColumnFormat::default()
+                },
+            ]
+        );
+
+        let columns = ColumnFormat::parse_all("3*>.>m;25%;~");
+        assert_eq!(columns.len(), 5);
+        assert_eq!(columns[2].halign, HorizontalAlignment::Right);
+        assert_eq!(columns[2].valign, VerticalAlignment::Bottom);
+        assert_eq!(columns[2].style, ColumnStyle::Monospace);
+        assert_eq!(columns[3].width, ColumnWidth::Percentage(25));
+        assert_eq!(columns[4].width, ColumnWidth::Auto);
+
+        assert_eq!(
+            ColumnFormat::parse_all("3"),
+            vec![ColumnFormat::default(); 3]
+        );
+    }
+
+    #[test]
+    fn test_column_style_applies_to_cells() {
+        let table = parse_table(
+            "[%header,cols=\"1,a\"]\n|===\n|Name |Notes\n\n|one\n|* first\n* second\n|two\nd|plain\n|===\n",
+        );
+        let header = table.header.as_ref().unwrap();
+        assert_eq!(header.columns[1].style, None);
+        let cells = &table.rows[0].columns;
+        assert_eq!(cells[0].style, None);
+        assert_eq!(cells[1].style.as_deref(), Some("a"));
+        assert!(matches!(cells[1].content[0], Block::UnorderedList(_)));
+        assert_eq!(table.rows[1].columns[1].style.as_deref(), Some("d"));
+    }
+
+    #[test]
+    fn test_column_style_below_spanned_cell() {
+        let table = parse_table("[cols=\"1,m,e\"]\n|===\n.2+|tall |b |c\n|d |e\n|===\n");
+        let cells = &table.rows[1].columns;
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].style.as_deref(), Some("m"));
+        assert_eq!(cells[1].style.as_deref(), Some("e"));
+    }
 }
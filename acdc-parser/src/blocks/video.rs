@@ -53,7 +53,13 @@ impl Video {
                                 }
                                 attribute_idx += 1;
                             }
-                            Rule::EOI | Rule::comment => {}
+                            Rule::role => metadata.roles.push(pair.as_str().to_string()),
+                            Rule::option => metadata.options.push(pair.as_str().to_string()),
+                            Rule::EOI
+                            | Rule::comment
+                            | Rule::open_sb
+                            | Rule::close_sb
+                            | Rule::empty_style => {}
                             unknown => unreachable!("{unknown:?}"),
                         }
                     }
@@ -2,6 +2,11 @@ use pest::iterators::Pairs;
 
 use crate::{AttributeName, AttributeValue, DocumentAttribute, DocumentAttributes, Rule};
 
+use super::wordcount::WordCount;
+
+/// Attributes whose value is a number of section levels.
+const LEVEL_ATTRIBUTES: [&str; 2] = ["toclevels", "sectnumlevels"];
+
 impl DocumentAttribute {
     pub(crate) fn parse(
         pairs: Pairs<Rule>,
@@ -20,7 +25,10 @@ impl DocumentAttribute {
                     unset = true;
                 }
                 Rule::document_attribute_value => {
-                    value = Some(AttributeValue::String(pair.as_str().to_string()));
+                    value = Some(AttributeValue::String(WordCount::reserve_references(
+                        pair.as_str(),
+                        parent_attributes,
+                    )));
                 }
                 unknown => {
                     tracing::warn!(?unknown, "unknown rule in header attribute");
@@ -34,7 +42,24 @@ impl DocumentAttribute {
         } else {
             (name.to_string(), AttributeValue::Bool(true))
         };
+        if let Some(value) = invalid_level(&name, &value) {
+            tracing::warn!(name, value, "invalid number of levels, using the default");
+        }
         parent_attributes.insert(name.clone(), value.clone());
         (name, value)
     }
 }
+
+/// Returns the value of `name` when it's an attribute holding a number of section
+/// levels and its value isn't a non-negative number, in which case converters fall back
+/// to the default.
+pub(crate) fn invalid_level<'a>(name: &str, value: &'a AttributeValue) -> Option<&'a str> {
+    match value {
+        AttributeValue::String(levels)
+            if LEVEL_ATTRIBUTES.contains(&name) && levels.trim().parse::<u8>().is_err() =>
+        {
+            Some(levels)
+        }
+        _ => None,
+    }
+}
@@ -28,7 +28,7 @@ impl Author {
                     initials.push(last_name.chars().next().unwrap_or_default());
                 }
                 Rule::author_email => {
-                    email = Some(pair.as_str().to_string()).map(|s| s.to_string());
+                    email = Some(pair.as_str().to_string());
                 }
                 unknown => unreachable!("{unknown:?}"),
             }
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use crate::{Block, Footnote, Header, InlineMacro, InlineNode};
+
+use super::validate::{walk_blocks_inlines_mut, walk_inlines_mut};
+
+/// Numbers the footnotes in `header` and `blocks` in the order they appear, and returns
+/// each numbered footnote.
+///
+/// A named footnote gets its number the first time it appears with some text, later
+/// ones with that name refer back to it and share its number.
+pub(crate) fn number(header: Option<&mut Header>, blocks: &mut [Block]) -> Vec<Footnote> {
+    let mut footnotes: Vec<Footnote> = Vec::new();
+    let mut named = HashMap::new();
+    let mut assign = |inline: &mut InlineNode| {
+        let InlineNode::Macro(InlineMacro::Footnote(footnote)) = inline else {
+            return;
+        };
+        if let Some(id) = &footnote.id {
+            if let Some(&number) = named.get(id) {
+                footnote.number = number;
+                return;
+            }
+            if footnote.text.is_none() {
+                tracing::warn!(id, "reference to a footnote that isn't defined before it");
+                return;
+            }
+            named.insert(id.clone(), footnotes.len() + 1);
+        }
+        footnote.number = footnotes.len() + 1;
+        footnotes.push(footnote.clone());
+    };
+    if let Some(header) = header {
+        walk_inlines_mut(&mut header.title, &mut assign);
+    }
+    walk_blocks_inlines_mut(blocks, &mut assign);
+    footnotes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    #[test]
+    fn test_number_footnotes() {
+        let doc = parse(
+            "First.footnote:[One] Second.footnote:disclaimer[Two]\n\n== Section\n\nThird.footnote:[Three] Again.footnote:disclaimer[] Missing.footnote:nope[]\n",
+        )
+        .unwrap();
+        let footnotes = doc
+            .footnotes()
+            .iter()
+            .map(|footnote| {
+                (
+                    footnote.number,
+                    footnote.id.as_deref(),
+                    footnote.text.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            footnotes,
+            vec![
+                (1, None, Some("One")),
+                (2, Some("disclaimer"), Some("Two")),
+                (3, None, Some("Three")),
+            ]
+        );
+    }
+}
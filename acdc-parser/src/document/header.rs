@@ -54,7 +54,7 @@ impl Header {
 
                                     // Run inline preprocessor before parsing inlines
                                     let mut preprocessor =
-                                        InlinePreprocessor::new(parent_attributes.clone());
+                                        InlinePreprocessor::new(parent_attributes);
                                     let processed = preprocessor.process(text, start_pos)?;
 
                                     let mut pairs =
@@ -1,22 +1,41 @@
 mod attribute;
 mod author;
+mod footnotes;
 mod header;
+mod plain_text;
+mod sectids;
+mod sections;
+mod toc;
 mod tree_builder;
 mod validate;
+mod wordcount;
 
 use pest::iterators::Pairs;
 use tracing::instrument;
 
-use crate::{blocks, Document, DocumentAttributes, Error, Header, Location, Rule};
+use crate::{
+    blocks, timings::measure, Document, DocumentAttributes, Error, Header, Location, ParseTimings,
+    Rule,
+};
+
+pub use validate::{validate_project, walk_blocks};
+use wordcount::WordCount;
 
 impl Document {
-    #[instrument(level = "trace")]
-    pub(crate) fn parse(pairs: Pairs<Rule>) -> Result<Self, Error> {
+    #[instrument(level = "trace", skip(timings))]
+    pub(crate) fn parse(pairs: Pairs<Rule>, timings: &mut ParseTimings) -> Result<Self, Error> {
+        // Attribute references are resolved while parsing, before the words can be
+        // counted, so references to the word count are parsed as placeholders that are
+        // filled in once it's known.
+        let source = pairs.as_str();
+        let counted = source.contains("{wordcount}") || source.contains("{reading-time}");
         let mut document_header = None;
         let mut attributes = DocumentAttributes::default();
+        let mut header_attributes = DocumentAttributes::default();
         let mut blocks = Vec::new();
         let mut location = Location::default();
 
+        let start = std::time::Instant::now();
         let len = pairs.clone().count();
         for (i, pair) in pairs.enumerate() {
             if i == 0 {
@@ -28,8 +47,16 @@ impl Document {
             match pair.as_rule() {
                 Rule::document_header => {
                     document_header = Header::parse(pair.into_inner(), &mut attributes)?;
+                    if counted {
+                        WordCount::reserve(&mut attributes);
+                    }
+                    header_attributes = attributes.clone();
                 }
                 Rule::blocks => {
+                    if counted {
+                        WordCount::reserve(&mut attributes);
+                    }
+                    header_attributes = attributes.clone();
                     blocks.extend(blocks::parse(pair.into_inner(), None, &mut attributes)?);
                 }
                 Rule::comment | Rule::EOI => {}
@@ -37,8 +64,27 @@ impl Document {
             }
         }
 
-        tree_builder::build_section_tree(&mut blocks)?;
-        validate::section_block_level(&blocks, None)?;
+        timings.blocks += start.elapsed();
+
+        measure(&mut timings.section_tree, || {
+            tree_builder::build_section_tree(&mut blocks)?;
+            validate::section_block_level(&blocks, None)
+        })?;
+        if counted || WordCount::requested(&attributes) {
+            measure(&mut timings.word_count, || {
+                let word_count = WordCount::from_blocks(&blocks);
+                word_count.apply(&mut attributes);
+                word_count.apply(&mut header_attributes);
+                word_count.fill_in(&mut blocks);
+            });
+        }
+        measure(&mut timings.section_ids, || {
+            sectids::assign(&mut blocks, &attributes);
+        });
+        let footnotes = measure(&mut timings.footnotes, || {
+            footnotes::number(document_header.as_mut(), &mut blocks)
+        });
+        let toc_entries = measure(&mut timings.toc, || toc::collect_entries(&blocks));
 
         Ok(Self {
             name: "document".to_string(),
@@ -47,6 +93,9 @@ impl Document {
             attributes,
             blocks,
             location,
+            toc_entries,
+            footnotes,
+            header_attributes,
         })
     }
 }
@@ -0,0 +1,154 @@
+use crate::{
+    blocks::inlines_text, Block, DelimitedBlockType, DescriptionListDescription, Document,
+    InlineNode, ListItem, Table,
+};
+
+impl Document {
+    /// Returns the text a reader would see in the document, without any markup, e.g. for
+    /// indexing it for search or showing a preview.
+    ///
+    /// Titles, paragraphs and code blocks each end up in a chunk of their own, separated by
+    /// a blank line. List items and table rows go on a line each, with the cells of a row
+    /// separated by a tab. Comments and passthrough blocks are left out.
+    #[must_use]
+    pub fn plain_text(&self) -> String {
+        let mut chunks = Vec::new();
+        if let Some(header) = &self.header {
+            push_inlines(&header.title, &mut chunks);
+        }
+        push_blocks(&self.blocks, &mut chunks);
+        chunks.join("\n\n")
+    }
+}
+
+fn push_inlines(inlines: &[InlineNode], chunks: &mut Vec<String>) {
+    let text = inlines_text(inlines);
+    let text = text.trim();
+    if !text.is_empty() {
+        chunks.push(text.to_string());
+    }
+}
+
+/// Like [`push_inlines`], but leaving out comment lines, which the parser keeps in the
+/// text of paragraphs.
+fn push_paragraph(inlines: &[InlineNode], chunks: &mut Vec<String>) {
+    let text = inlines_text(inlines);
+    let text = text
+        .lines()
+        .filter(|line| !line.starts_with("//") || line.starts_with("///"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+    if !text.is_empty() {
+        chunks.push(text.to_string());
+    }
+}
+
+fn push_items(items: &[ListItem], chunks: &mut Vec<String>) {
+    let lines = items
+        .iter()
+        .map(|item| inlines_text(&item.content).trim().to_string())
+        .collect::<Vec<_>>();
+    chunks.push(lines.join("\n"));
+}
+
+fn push_table(table: &Table, chunks: &mut Vec<String>) {
+    let lines = table
+        .header
+        .iter()
+        .chain(&table.rows)
+        .chain(&table.footer)
+        .map(|row| {
+            row.columns
+                .iter()
+                .map(|column| {
+                    let mut cell = Vec::new();
+                    push_blocks(&column.content, &mut cell);
+                    cell.join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("\t")
+        })
+        .collect::<Vec<_>>();
+    chunks.push(lines.join("\n"));
+}
+
+fn push_blocks(blocks: &[Block], chunks: &mut Vec<String>) {
+    for block in blocks {
+        match block {
+            Block::Paragraph(paragraph) => {
+                push_inlines(&paragraph.title, chunks);
+                push_paragraph(&paragraph.content, chunks);
+            }
+            Block::Section(section) => {
+                push_inlines(&section.title, chunks);
+                push_blocks(&section.content, chunks);
+            }
+            Block::DiscreteHeader(header) => push_inlines(&header.title, chunks),
+            Block::Admonition(admonition) => {
+                push_inlines(&admonition.title, chunks);
+                push_blocks(&admonition.blocks, chunks);
+            }
+            Block::UnorderedList(list) => {
+                push_inlines(&list.title, chunks);
+                push_items(&list.items, chunks);
+            }
+            Block::OrderedList(list) => {
+                push_inlines(&list.title, chunks);
+                push_items(&list.items, chunks);
+            }
+            Block::CalloutList(list) => {
+                push_inlines(&list.title, chunks);
+                push_items(&list.items, chunks);
+            }
+            Block::DescriptionList(list) => {
+                for item in &list.items {
+                    for term in &item.terms {
+                        push_inlines(term, chunks);
+                    }
+                    match &item.description {
+                        DescriptionListDescription::Inline(inlines) => {
+                            push_inlines(inlines, chunks);
+                        }
+                        DescriptionListDescription::Blocks(blocks) => push_blocks(blocks, chunks),
+                    }
+                }
+            }
+            Block::DelimitedBlock(delimited) => {
+                push_inlines(&delimited.title, chunks);
+                match &delimited.inner {
+                    DelimitedBlockType::DelimitedExample(blocks)
+                    | DelimitedBlockType::DelimitedOpen(blocks)
+                    | DelimitedBlockType::DelimitedSidebar(blocks)
+                    | DelimitedBlockType::DelimitedQuote(blocks) => push_blocks(blocks, chunks),
+                    DelimitedBlockType::DelimitedListing(inlines)
+                    | DelimitedBlockType::DelimitedLiteral(inlines)
+                    | DelimitedBlockType::DelimitedVerse(inlines) => {
+                        push_inlines(inlines, chunks);
+                    }
+                    DelimitedBlockType::DelimitedTable(table) => push_table(table, chunks),
+                    DelimitedBlockType::DelimitedComment(_)
+                    | DelimitedBlockType::DelimitedPass(_) => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    #[test]
+    fn test_plain_text() {
+        let doc = parse(
+            "= Guide\n\n== Install\n\nRun *the* _installer_ with `--force`.\n\n// not shown\n\n[source,sh]\n----\nmake install\n----\n\n|===\n|Name |Value\n\n|a |1\n|===\n\n* first\n* second\n",
+        )
+        .unwrap();
+        assert_eq!(
+            doc.plain_text(),
+            "Guide\n\nInstall\n\nRun the installer with --force.\n\nmake install\n\nName\tValue\na\t1\n\nfirst\nsecond"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+
+use crate::{blocks::generate_id, AttributeValue, Block, DocumentAttributes};
+
+/// Gives every section in `blocks` without an id of its own one generated from its
+/// title, unless `sectids` is unset.
+///
+/// Generated ids start with `idprefix` and separate words with `idseparator` (both `_`
+/// by default, and empty when set without a value). An id that is already taken gets
+/// a numeric suffix, e.g. `_usage_2` for the second "Usage" section.
+pub(crate) fn assign(blocks: &mut [Block], attributes: &DocumentAttributes) {
+    if matches!(attributes.get("sectids"), Some(AttributeValue::Bool(false))) {
+        return;
+    }
+    let setting = |name: &str| match attributes.get(name) {
+        Some(AttributeValue::String(value)) => value.clone(),
+        Some(_) => String::new(),
+        None => String::from("_"),
+    };
+    let prefix = setting("idprefix");
+    let separator = setting("idseparator");
+
+    let mut taken = HashSet::new();
+    collect_explicit(blocks, &mut taken);
+    generate(blocks, &prefix, &separator, &mut taken);
+}
+
+fn collect_explicit(blocks: &[Block], taken: &mut HashSet<String>) {
+    for block in blocks {
+        if let Block::Section(section) = block {
+            if section.has_explicit_id() {
+                taken.insert(section.id());
+            }
+            collect_explicit(&section.content, taken);
+        }
+    }
+}
+
+fn generate(blocks: &mut [Block], prefix: &str, separator: &str, taken: &mut HashSet<String>) {
+    for block in blocks {
+        if let Block::Section(section) = block {
+            if !section.has_explicit_id() {
+                let base = generate_id(&section.title, prefix, separator);
+                let suffix_separator = if separator.is_empty() { "_" } else { separator };
+                let mut id = base.clone();
+                let mut n = 2;
+                while taken.contains(&id) {
+                    id = format!("{base}{suffix_separator}{n}");
+                    n += 1;
+                }
+                taken.insert(id.clone());
+                section.generated_id = Some(id);
+            }
+            generate(&mut section.content, prefix, separator, taken);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    fn ids(input: &str) -> Vec<String> {
+        parse(input)
+            .unwrap()
+            .toc()
+            .iter()
+            .map(|e| e.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_section_ids() {
+        assert_eq!(
+            ids("= Doc\n\n== Usage\n\ntext\n\n[[usage_2]]\n== Other\n\ntext\n\n== Usage\n\ntext\n\n== Usage\n\ntext\n"),
+            vec!["_usage", "usage_2", "_usage_2", "_usage_3"]
+        );
+    }
+
+    #[test]
+    fn test_section_id_prefix_and_separator() {
+        assert_eq!(
+            ids("= Doc\n:idprefix:\n:idseparator: -\n\n== Getting *Started* Now\n\ntext\n"),
+            vec!["getting-started-now"]
+        );
+        assert_eq!(
+            ids("= Doc\n:idprefix: sec_\n\n== Getting Started\n\ntext\n"),
+            vec!["sec_getting_started"]
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{blocks::inlines_text, Block, Document, SectionRef};
+
+impl Document {
+    /// Returns every section of the document, nested ones included, in document order.
+    ///
+    /// Unlike [`Document::toc`], sections with the `notoc` option are listed too, and
+    /// their titles come as plain text.
+    #[must_use]
+    pub fn sections(&self) -> Vec<SectionRef> {
+        let mut sections = Vec::new();
+        collect(&self.blocks, &mut sections);
+        sections
+    }
+}
+
+fn collect(blocks: &[Block], sections: &mut Vec<SectionRef>) {
+    for block in blocks {
+        if let Block::Section(section) = block {
+            sections.push(SectionRef {
+                id: section.id(),
+                level: section.level,
+                title: inlines_text(&section.title),
+                location: section.location.clone(),
+            });
+            collect(&section.content, sections);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    #[test]
+    fn test_sections_in_document_order() {
+        let doc = parse(
+            "= Document\n\n== First *Section*\n\ncontent\n\n=== Nested One\n\ncontent\n\n==== Deeper\n\ncontent\n\n[[custom]]\n[%notoc]\n== Second\n\ncontent\n",
+        )
+        .unwrap();
+        let sections = doc
+            .sections()
+            .into_iter()
+            .map(|section| (section.id, section.level, section.title))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sections,
+            vec![
+                ("_first_section".to_string(), 1, "First Section".to_string()),
+                ("_nested_one".to_string(), 2, "Nested One".to_string()),
+                ("_deeper".to_string(), 3, "Deeper".to_string()),
+                ("custom".to_string(), 1, "Second".to_string()),
+            ]
+        );
+        assert_eq!(doc.sections()[1].location.start.line, 7);
+    }
+}
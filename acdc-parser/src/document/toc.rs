@@ -0,0 +1,62 @@
+use crate::{Block, TocEntry};
+
+/// Collects a flat list of `TocEntry` for every section in `blocks`, in document order.
+///
+/// Sections with the `notoc` option are left out, along with their subsections.
+pub(crate) fn collect_entries(blocks: &[Block]) -> Vec<TocEntry> {
+    let mut entries = Vec::new();
+    collect(blocks, &mut entries);
+    entries
+}
+
+fn collect(blocks: &[Block], entries: &mut Vec<TocEntry>) {
+    for block in blocks {
+        if let Block::Section(section) = block {
+            if section.metadata.options.iter().any(|o| o == "notoc") {
+                continue;
+            }
+            entries.push(TocEntry {
+                id: section.id(),
+                title: section.title.clone(),
+                level: section.level,
+                children: Vec::new(),
+            });
+            collect(&section.content, entries);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse;
+
+    #[test]
+    fn test_toc_tree_nests_sections() {
+        let doc = parse(
+            "= Document\n\n== First Section\n\ncontent\n\n=== Nested One\n\ncontent\n\n[[custom]]\n== Second\n\ncontent\n",
+        )
+        .unwrap();
+        let ids = doc.toc().iter().map(|e| e.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["_first_section", "_nested_one", "custom"]);
+
+        let tree = doc.toc_tree();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].id, "_first_section");
+        assert_eq!(tree[0].level, 1);
+        assert_eq!(tree[0].children.len(), 1);
+        assert_eq!(tree[0].children[0].id, "_nested_one");
+        assert_eq!(tree[0].children[0].level, 2);
+        assert_eq!(tree[1].id, "custom");
+        assert_eq!(tree[1].children, Vec::new());
+    }
+
+    #[test]
+    fn test_toc_skips_notoc_sections() {
+        let doc = parse(
+            "= Document\n\n== Shown\n\ncontent\n\n[%unnumbered%notoc]\n== Hidden\n\ncontent\n\n=== Hidden Child\n\ncontent\n\n== Also Shown\n\ncontent\n",
+        )
+        .unwrap();
+        let ids = doc.toc().iter().map(|e| e.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["_shown", "_also_shown"]);
+    }
+}
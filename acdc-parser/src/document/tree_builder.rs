@@ -102,6 +102,7 @@ pub(crate) fn build_section_tree(document: &mut Vec<Block>) -> Result<(), Error>
                         }
                         Block::Paragraph(paragraph) => paragraph.location.end.clone(),
                         Block::OrderedList(ordered_list) => ordered_list.location.end.clone(),
+                        Block::CalloutList(callout_list) => callout_list.location.end.clone(),
                         Block::UnorderedList(unordered_list) => unordered_list.location.end.clone(),
                         Block::DocumentAttribute(attribute) => attribute.location.end.clone(),
                         Block::Admonition(admonition) => admonition.location.end.clone(),
@@ -189,7 +190,7 @@ fn section_tree_move(kept_layers: &mut Vec<Block>, i: usize) -> Result<(), Error
                     delimited_block.location.end.clone()
                 }
                 Some(Block::Paragraph(paragraph)) => paragraph.location.end.clone(),
-                _ => todo!(),
+                _ => current_section.location.end.clone(),
             };
             parent_section.content.push(Block::Section(current_section));
             kept_layers.remove(i);
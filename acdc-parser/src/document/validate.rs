@@ -1,6 +1,468 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
 use tracing::instrument;
 
-use crate::{Block, Error, ErrorDetail};
+use crate::{
+    document::attribute::invalid_level, AttributeValue, Block, DelimitedBlockType,
+    DescriptionListDescription, Diagnostic, DiscreteHeader, Document, Error, ErrorDetail, Image,
+    InlineMacro, InlineNode, Severity, ThematicBreak,
+};
+
+impl Document {
+    /// Checks the document for problems that don't stop it from being converted, and
+    /// returns a diagnostic for each one found.
+    ///
+    /// Currently this flags images without alt text, unless they're marked as decorative
+    /// with `role=decorative`, and level attributes like `toclevels` that aren't a
+    /// number.
+    #[must_use]
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (name, value) in self.attributes.iter() {
+            if let Some(value) = invalid_level(name, value) {
+                // Attributes don't keep where they were set, so these point at the
+                // header, where they usually are.
+                let location = self
+                    .header
+                    .as_ref()
+                    .map_or(&self.location, |header| &header.location);
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    message: format!(
+                        "invalid value '{value}' for '{name}', expected a number of levels"
+                    ),
+                    location: location.clone(),
+                    file: None,
+                });
+            }
+        }
+        if let Some(header) = &self.header {
+            walk_inlines(&header.title, &mut |inline| {
+                check_inline_image(inline, &mut diagnostics);
+            });
+        }
+        walk_blocks(&self.blocks, &mut |block| {
+            if let Block::Image(image) = block {
+                check_image(image, &mut diagnostics);
+            }
+            block_inlines(block, &mut |inline| {
+                check_inline_image(inline, &mut diagnostics);
+            });
+        });
+        diagnostics
+    }
+
+    /// Fills in the text of each cross reference that doesn't have its own with the
+    /// label of its target: the anchor's `xreflabel` if it has one, or else the title of
+    /// the section it points to. Returns a diagnostic for each cross reference whose
+    /// target isn't an id in the document.
+    ///
+    /// References to other documents, like `xref:other.adoc#install[]`, are left alone,
+    /// see [`validate_project`] for those.
+    pub fn resolve_references(&mut self) -> Vec<Diagnostic> {
+        let references = self.references();
+        let mut diagnostics = Vec::new();
+        let mut resolve = |inline: &mut InlineNode| {
+            let InlineNode::Macro(InlineMacro::CrossReference(xref)) = inline else {
+                return;
+            };
+            let id = match xref.target.split_once('#') {
+                Some(("", id)) => id,
+                Some(_) => return,
+                None => xref.target.as_str(),
+            };
+            match references.get(id) {
+                Some(label) => {
+                    if xref.text.is_none() {
+                        xref.text.clone_from(label);
+                    }
+                }
+                None => diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    message: format!("xref target '{id}' not found"),
+                    location: xref.location.clone(),
+                    file: None,
+                }),
+            }
+        };
+        if let Some(header) = &mut self.header {
+            walk_inlines_mut(&mut header.title, &mut resolve);
+        }
+        walk_blocks_inlines_mut(&mut self.blocks, &mut resolve);
+        diagnostics
+    }
+
+    /// Returns every id an xref can point to in the document, those of sections, of
+    /// anchored blocks and of inline anchors, along with the text an xref to it shows
+    /// when it doesn't have its own.
+    #[must_use]
+    pub fn references(&self) -> HashMap<String, Option<String>> {
+        let mut references = HashMap::new();
+        walk_blocks(&self.blocks, &mut |block| {
+            let metadata = match block {
+                Block::Section(section) => {
+                    let label = section
+                        .metadata
+                        .anchors
+                        .first()
+                        .and_then(|anchor| anchor.xreflabel.clone())
+                        .unwrap_or_else(|| crate::blocks::inlines_text(&section.title));
+                    references.insert(section.id(), Some(label));
+                    return;
+                }
+                Block::Paragraph(paragraph) => &paragraph.metadata,
+                Block::DelimitedBlock(delimited) => &delimited.metadata,
+                Block::Image(image) => &image.metadata,
+                Block::Admonition(admonition) => &admonition.metadata,
+                Block::UnorderedList(list) => &list.metadata,
+                Block::OrderedList(list) => &list.metadata,
+                Block::CalloutList(list) => &list.metadata,
+                Block::DescriptionList(list) => &list.metadata,
+                Block::Audio(audio) => &audio.metadata,
+                Block::Video(video) => &video.metadata,
+                Block::PageBreak(page_break) => &page_break.metadata,
+                Block::DiscreteHeader(DiscreteHeader { anchors, .. })
+                | Block::ThematicBreak(ThematicBreak { anchors, .. }) => {
+                    for anchor in anchors {
+                        references
+                            .entry(anchor.id.clone())
+                            .or_insert_with(|| anchor.xreflabel.clone());
+                    }
+                    return;
+                }
+                _ => return,
+            };
+            for anchor in metadata.id.iter().chain(&metadata.anchors) {
+                references
+                    .entry(anchor.id.clone())
+                    .or_insert_with(|| anchor.xreflabel.clone());
+            }
+        });
+        walk_blocks(&self.blocks, &mut |block| {
+            block_inlines(block, &mut |inline| {
+                if let InlineNode::InlineAnchor(anchor) = inline {
+                    references
+                        .entry(anchor.id.clone())
+                        .or_insert_with(|| anchor.xreflabel.clone());
+                }
+            });
+        });
+        references
+    }
+}
+
+/// Parses every file in `files` and checks the cross references between them, e.g.
+/// `xref:other.adoc#install[]`, returning a diagnostic for each one pointing to an id
+/// that doesn't exist in the other document.
+///
+/// References to documents outside of `files` can't be checked and are left alone.
+///
+/// # Errors
+///
+/// Returns an error if any of the files fails to parse.
+pub fn validate_project<P: AsRef<Path>>(files: &[P]) -> Result<Vec<Diagnostic>, Error> {
+    let mut documents = Vec::new();
+    for file in files {
+        let path = file.as_ref();
+        let document = crate::parse_file(path)?;
+        documents.push((normalize(path), document));
+    }
+    let ids = documents
+        .iter()
+        .map(|(path, document)| (path.clone(), document.references()))
+        .collect::<HashMap<_, _>>();
+
+    let mut diagnostics = Vec::new();
+    for (path, document) in &documents {
+        let mut check_inline = |inline: &InlineNode| {
+            let InlineNode::Macro(InlineMacro::CrossReference(xref)) = inline else {
+                return;
+            };
+            let Some((target_file, id)) = xref.target.split_once('#') else {
+                return;
+            };
+            if target_file.is_empty() {
+                return;
+            }
+            let mut target_path = path.with_file_name(target_file);
+            if target_path.extension().is_none() {
+                target_path.set_extension("adoc");
+            }
+            let target_path = normalize(&target_path);
+            if ids
+                .get(&target_path)
+                .is_some_and(|target_ids| !target_ids.contains_key(id))
+            {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    message: format!("xref target '{id}' not found in '{target_file}'"),
+                    location: xref.location.clone(),
+                    file: Some(path.clone()),
+                });
+            }
+        };
+        walk_blocks(&document.blocks, &mut |block| {
+            block_inlines(block, &mut check_inline);
+        });
+    }
+    Ok(diagnostics)
+}
+
+/// Resolves `.` and `..` components of `path` without touching the filesystem, so the
+/// same file reached through different relative paths compares equal.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn check_inline_image(inline: &InlineNode, diagnostics: &mut Vec<Diagnostic>) {
+    if let InlineNode::Macro(InlineMacro::Image(image)) = inline {
+        check_image(image, diagnostics);
+    }
+}
+
+fn check_image(image: &Image, diagnostics: &mut Vec<Diagnostic>) {
+    if image.is_decorative() {
+        return;
+    }
+    let has_alt = matches!(
+        image.metadata.attributes.get("alt"),
+        Some(AttributeValue::String(alt)) if !alt.trim().is_empty()
+    );
+    if !has_alt {
+        diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            message: format!("image '{}' has no alt text", image.source),
+            location: image.location.clone(),
+            file: None,
+        });
+    }
+}
+
+/// Calls `f` on every block in `blocks`, nested ones included, in document order.
+///
+/// A block is visited before the blocks nested in it, and the cells of a table are
+/// visited row by row, from the header to the footer.
+pub fn walk_blocks<'a>(blocks: &'a [Block], f: &mut impl FnMut(&'a Block)) {
+    for block in blocks {
+        f(block);
+        match block {
+            Block::Section(section) => walk_blocks(&section.content, f),
+            Block::Admonition(admonition) => walk_blocks(&admonition.blocks, f),
+            Block::DescriptionList(list) => {
+                for item in &list.items {
+                    if let DescriptionListDescription::Blocks(blocks) = &item.description {
+                        walk_blocks(blocks, f);
+                    }
+                }
+            }
+            Block::DelimitedBlock(delimited) => match &delimited.inner {
+                DelimitedBlockType::DelimitedExample(blocks)
+                | DelimitedBlockType::DelimitedOpen(blocks)
+                | DelimitedBlockType::DelimitedSidebar(blocks)
+                | DelimitedBlockType::DelimitedQuote(blocks) => walk_blocks(blocks, f),
+                DelimitedBlockType::DelimitedTable(table) => {
+                    for row in table.header.iter().chain(&table.rows).chain(&table.footer) {
+                        for column in &row.columns {
+                            walk_blocks(&column.content, f);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Calls `f` on every inline of the title and content of `block` itself, leaving out
+/// those of the blocks nested in it.
+fn block_inlines<'a>(block: &'a Block, f: &mut impl FnMut(&'a InlineNode)) {
+    match block {
+        Block::Paragraph(paragraph) => {
+            walk_inlines(&paragraph.title, f);
+            walk_inlines(&paragraph.content, f);
+        }
+        Block::Section(section) => walk_inlines(&section.title, f),
+        Block::Admonition(admonition) => walk_inlines(&admonition.title, f),
+        Block::UnorderedList(list) => {
+            walk_inlines(&list.title, f);
+            list.items
+                .iter()
+                .for_each(|item| walk_inlines(&item.content, f));
+        }
+        Block::OrderedList(list) => {
+            walk_inlines(&list.title, f);
+            list.items
+                .iter()
+                .for_each(|item| walk_inlines(&item.content, f));
+        }
+        Block::CalloutList(list) => {
+            walk_inlines(&list.title, f);
+            list.items
+                .iter()
+                .for_each(|item| walk_inlines(&item.content, f));
+        }
+        Block::DescriptionList(list) => {
+            walk_inlines(&list.title, f);
+            for item in &list.items {
+                item.terms.iter().for_each(|term| walk_inlines(term, f));
+                if let DescriptionListDescription::Inline(inlines) = &item.description {
+                    walk_inlines(inlines, f);
+                }
+            }
+        }
+        Block::DelimitedBlock(delimited) => {
+            walk_inlines(&delimited.title, f);
+            if let DelimitedBlockType::DelimitedVerse(inlines)
+            | DelimitedBlockType::DelimitedListing(inlines)
+            | DelimitedBlockType::DelimitedLiteral(inlines) = &delimited.inner
+            {
+                walk_inlines(inlines, f);
+            }
+        }
+        Block::DiscreteHeader(header) => walk_inlines(&header.title, f),
+        Block::Image(image) => walk_inlines(&image.title, f),
+        Block::Audio(audio) => walk_inlines(&audio.title, f),
+        Block::Video(video) => walk_inlines(&video.title, f),
+        _ => {}
+    }
+}
+
+/// Calls `f` on every inline of every block in `blocks`, nested ones included, allowing
+/// it to change them. This covers the same blocks and inlines as [`walk_blocks`] and
+/// [`block_inlines`] put together.
+pub(super) fn walk_blocks_inlines_mut(blocks: &mut [Block], f: &mut impl FnMut(&mut InlineNode)) {
+    for block in blocks {
+        match block {
+            Block::Paragraph(paragraph) => {
+                walk_inlines_mut(&mut paragraph.title, f);
+                walk_inlines_mut(&mut paragraph.content, f);
+            }
+            Block::Section(section) => {
+                walk_inlines_mut(&mut section.title, f);
+                walk_blocks_inlines_mut(&mut section.content, f);
+            }
+            Block::Admonition(admonition) => {
+                walk_inlines_mut(&mut admonition.title, f);
+                walk_blocks_inlines_mut(&mut admonition.blocks, f);
+            }
+            Block::UnorderedList(list) => {
+                walk_inlines_mut(&mut list.title, f);
+                for item in &mut list.items {
+                    walk_inlines_mut(&mut item.content, f);
+                }
+            }
+            Block::OrderedList(list) => {
+                walk_inlines_mut(&mut list.title, f);
+                for item in &mut list.items {
+                    walk_inlines_mut(&mut item.content, f);
+                }
+            }
+            Block::CalloutList(list) => {
+                walk_inlines_mut(&mut list.title, f);
+                for item in &mut list.items {
+                    walk_inlines_mut(&mut item.content, f);
+                }
+            }
+            Block::DescriptionList(list) => {
+                walk_inlines_mut(&mut list.title, f);
+                for item in &mut list.items {
+                    for term in &mut item.terms {
+                        walk_inlines_mut(term, f);
+                    }
+                    match &mut item.description {
+                        DescriptionListDescription::Inline(inlines) => walk_inlines_mut(inlines, f),
+                        DescriptionListDescription::Blocks(blocks) => {
+                            walk_blocks_inlines_mut(blocks, f);
+                        }
+                    }
+                }
+            }
+            Block::DiscreteHeader(header) => walk_inlines_mut(&mut header.title, f),
+            Block::Image(image) => walk_inlines_mut(&mut image.title, f),
+            Block::Audio(audio) => walk_inlines_mut(&mut audio.title, f),
+            Block::Video(video) => walk_inlines_mut(&mut video.title, f),
+            Block::DelimitedBlock(delimited) => {
+                walk_inlines_mut(&mut delimited.title, f);
+                match &mut delimited.inner {
+                    DelimitedBlockType::DelimitedExample(blocks)
+                    | DelimitedBlockType::DelimitedOpen(blocks)
+                    | DelimitedBlockType::DelimitedSidebar(blocks)
+                    | DelimitedBlockType::DelimitedQuote(blocks) => {
+                        walk_blocks_inlines_mut(blocks, f);
+                    }
+                    DelimitedBlockType::DelimitedVerse(inlines)
+                    | DelimitedBlockType::DelimitedListing(inlines)
+                    | DelimitedBlockType::DelimitedLiteral(inlines) => walk_inlines_mut(inlines, f),
+                    DelimitedBlockType::DelimitedTable(table) => {
+                        for row in table
+                            .header
+                            .iter_mut()
+                            .chain(&mut table.rows)
+                            .chain(&mut table.footer)
+                        {
+                            for column in &mut row.columns {
+                                walk_blocks_inlines_mut(&mut column.content, f);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Calls `f` on every inline in `inlines`, including those inside formatted text,
+/// allowing it to change them.
+pub(super) fn walk_inlines_mut(inlines: &mut [InlineNode], f: &mut impl FnMut(&mut InlineNode)) {
+    for inline in inlines {
+        f(inline);
+        match inline {
+            InlineNode::BoldText(bold) => walk_inlines_mut(&mut bold.content, f),
+            InlineNode::ItalicText(italic) => walk_inlines_mut(&mut italic.content, f),
+            InlineNode::MonospaceText(monospace) => walk_inlines_mut(&mut monospace.content, f),
+            InlineNode::HighlightText(highlight) => walk_inlines_mut(&mut highlight.content, f),
+            InlineNode::SubscriptText(subscript) => walk_inlines_mut(&mut subscript.content, f),
+            InlineNode::SuperscriptText(superscript) => {
+                walk_inlines_mut(&mut superscript.content, f);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Calls `f` on every inline in `inlines`, including those inside formatted text.
+fn walk_inlines<'a>(inlines: &'a [InlineNode], f: &mut impl FnMut(&'a InlineNode)) {
+    for inline in inlines {
+        f(inline);
+        match inline {
+            InlineNode::BoldText(bold) => walk_inlines(&bold.content, f),
+            InlineNode::ItalicText(italic) => walk_inlines(&italic.content, f),
+            InlineNode::MonospaceText(monospace) => walk_inlines(&monospace.content, f),
+            InlineNode::HighlightText(highlight) => walk_inlines(&highlight.content, f),
+            InlineNode::SubscriptText(subscript) => walk_inlines(&subscript.content, f),
+            InlineNode::SuperscriptText(superscript) => walk_inlines(&superscript.content, f),
+            _ => {}
+        }
+    }
+}
 
 // Validate that the block level is correct for the section level.
 //
@@ -42,3 +504,131 @@ pub(crate) fn section_block_level(content: &[Block], prior_level: Option<u8>) ->
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Severity};
+
+    #[test]
+    fn test_validate_images_without_alt_text() {
+        let doc = parse(
+            "image::chart.png[]\n\nimage::divider.png[role=decorative]\n\nSee image:icon.png[Icon] and image:logo.png[].\n",
+        )
+        .unwrap();
+        let diagnostics = doc.validate();
+        let messages = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.message.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                (Severity::Warning, "image 'chart.png' has no alt text"),
+                (Severity::Warning, "image 'logo.png' has no alt text"),
+            ]
+        );
+        assert_eq!(diagnostics[0].location.start.line, 1);
+    }
+
+    #[test]
+    fn test_validate_level_attributes() {
+        let doc = parse("= Document\n:toclevels: abc\n:sectnumlevels: -1\n\ncontent\n").unwrap();
+        let messages = doc
+            .validate()
+            .into_iter()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    Severity::Warning,
+                    "invalid value '-1' for 'sectnumlevels', expected a number of levels"
+                        .to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "invalid value 'abc' for 'toclevels', expected a number of levels".to_string()
+                ),
+            ]
+        );
+
+        let doc = parse("= Document\n:toclevels: 3\n\ncontent\n").unwrap();
+        assert_eq!(doc.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        let mut doc = parse(
+            "== Getting Started\n\n[[setup,Setup Steps]]\nRun it.\n\nSee <<_getting_started>>, <<setup>>, <<setup,the steps>>, [[spot]]here, <<spot>> and <<missing>>.\n",
+        )
+        .unwrap();
+        let diagnostics = doc.resolve_references();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "xref target 'missing' not found");
+        assert_eq!(diagnostics[0].location.start.line, 6);
+
+        let mut texts = Vec::new();
+        super::walk_blocks(&doc.blocks, &mut |block| {
+            super::block_inlines(block, &mut |inline| {
+                if let crate::InlineNode::Macro(crate::InlineMacro::CrossReference(xref)) = inline {
+                    texts.push(xref.text.clone());
+                }
+            });
+        });
+        assert_eq!(
+            texts,
+            vec![
+                Some("Getting Started".to_string()),
+                Some("Setup Steps".to_string()),
+                Some("the steps".to_string()),
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_references_in_description_lists() {
+        let mut doc = parse(
+            "[[cpu]]\n== CPU\n\n<<cpu>>:: See <<cpu>> and <<nowhere>>.\nNote:: A footnote.footnote:[In a list.]\n",
+        )
+        .unwrap();
+        let diagnostics = doc.resolve_references();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "xref target 'nowhere' not found");
+        let mut texts = Vec::new();
+        super::walk_blocks(&doc.blocks, &mut |block| {
+            super::block_inlines(block, &mut |inline| {
+                if let crate::InlineNode::Macro(crate::InlineMacro::CrossReference(xref)) = inline {
+                    texts.push(xref.text.clone());
+                }
+            });
+        });
+        let cpu = Some("CPU".to_string());
+        assert_eq!(texts, vec![cpu.clone(), cpu, None]);
+        assert_eq!(doc.footnotes().len(), 1);
+        assert_eq!(doc.footnotes()[0].number, 1);
+    }
+
+    #[test]
+    fn test_validate_project_reports_broken_xrefs() {
+        let diagnostics = crate::validate_project(&[
+            "fixtures/samples/xref-project/guide.adoc",
+            "fixtures/samples/xref-project/reference.adoc",
+        ])
+        .unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "xref target 'flags' not found in 'reference.adoc'"
+        );
+        assert_eq!(
+            diagnostics[0].file.as_deref(),
+            Some(std::path::Path::new(
+                "fixtures/samples/xref-project/guide.adoc"
+            ))
+        );
+        assert_eq!(diagnostics[0].location.start.line, 7);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    blocks::inlines_text, AttributeValue, Block, DelimitedBlockType, DescriptionListDescription,
+    DocumentAttributes, InlineMacro, InlineNode, ListItem,
+};
+
+use super::validate::{walk_blocks, walk_blocks_inlines_mut};
+
+/// Words per minute assumed when deriving `reading-time` from `wordcount`.
+const WORDS_PER_MINUTE: usize = 200;
+
+/// What `{wordcount}` stands for until the words are counted.
+const WORDCOUNT_PLACEHOLDER: &str = "\u{E000}wordcount\u{E000}";
+
+/// What `{reading-time}` stands for until the words are counted.
+const READING_TIME_PLACEHOLDER: &str = "\u{E000}reading-time\u{E000}";
+
+/// Word count of the plain-text content of a document.
+///
+/// Listing, literal, passthrough and comment blocks are not prose, so they don't count. The
+/// count is only computed for documents that refer to `{wordcount}` or `{reading-time}`, or
+/// that set `reading-time`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct WordCount(usize);
+
+impl WordCount {
+    pub(crate) fn from_blocks(blocks: &[Block]) -> Self {
+        Self(count_blocks(blocks))
+    }
+
+    /// Whether the document opted into `reading-time` by setting it without a value.
+    pub(crate) fn requested(attributes: &DocumentAttributes) -> bool {
+        matches!(
+            attributes.get("reading-time"),
+            Some(AttributeValue::Bool(true))
+        )
+    }
+
+    /// Estimated reading time in minutes, never less than one.
+    fn reading_time(self) -> usize {
+        self.0.div_ceil(WORDS_PER_MINUTE).max(1)
+    }
+
+    /// Sets the `wordcount` attribute, unless the document sets it, and `reading-time`,
+    /// when the document sets it without a value, to placeholders that [`Self::apply`]
+    /// and [`Self::fill_in`] later replace with the count.
+    pub(crate) fn reserve(attributes: &mut DocumentAttributes) {
+        if attributes.get("wordcount").is_none() {
+            attributes.insert(
+                "wordcount".to_string(),
+                AttributeValue::String(WORDCOUNT_PLACEHOLDER.to_string()),
+            );
+        }
+        if Self::requested(attributes) {
+            attributes.insert(
+                "reading-time".to_string(),
+                AttributeValue::String(READING_TIME_PLACEHOLDER.to_string()),
+            );
+        }
+    }
+
+    /// Parses the references to `wordcount` and `reading-time` in the value of an
+    /// attribute entry as placeholders, when they stand for the count, so that
+    /// [`Self::apply`] fills them in too, e.g. in `:page-reading-time: {reading-time}`.
+    pub(crate) fn reserve_references(value: &str, attributes: &DocumentAttributes) -> String {
+        let mut value = value.to_string();
+        if attributes.get("wordcount").is_none()
+            || is_placeholder(attributes, "wordcount", WORDCOUNT_PLACEHOLDER)
+        {
+            value = value.replace("{wordcount}", WORDCOUNT_PLACEHOLDER);
+        }
+        if Self::requested(attributes)
+            || is_placeholder(attributes, "reading-time", READING_TIME_PLACEHOLDER)
+        {
+            value = value.replace("{reading-time}", READING_TIME_PLACEHOLDER);
+        }
+        value
+    }
+
+    /// Exposes the count as the `wordcount` attribute, unless the document sets it, and
+    /// fills it in wherever an attribute value refers to it.
+    ///
+    /// `reading-time` is opt-in: when the document sets it without a value, the value is
+    /// replaced by the estimated number of minutes.
+    pub(crate) fn apply(self, attributes: &mut DocumentAttributes) {
+        Self::reserve(attributes);
+        let filled = attributes
+            .iter()
+            .filter_map(|(name, value)| match value {
+                AttributeValue::String(value) if has_placeholders(value) => {
+                    Some((name.clone(), AttributeValue::String(self.replace(value))))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for (name, value) in filled {
+            attributes.insert(name, value);
+        }
+    }
+
+    /// Replaces the placeholders that references to `wordcount` and `reading-time` were
+    /// parsed as in the text of `blocks`.
+    pub(crate) fn fill_in(self, blocks: &mut [Block]) {
+        let mut fill_in = |inline: &mut InlineNode| {
+            let text = match inline {
+                InlineNode::PlainText(plain) => &mut plain.content,
+                InlineNode::Macro(InlineMacro::Footnote(footnote)) => match &mut footnote.text {
+                    Some(text) => text,
+                    None => return,
+                },
+                InlineNode::Macro(InlineMacro::CrossReference(xref)) => match &mut xref.text {
+                    Some(text) => text,
+                    None => return,
+                },
+                _ => return,
+            };
+            if has_placeholders(text) {
+                *text = self.replace(text);
+            }
+        };
+        walk_blocks_inlines_mut(blocks, &mut fill_in);
+    }
+
+    fn replace(self, text: &str) -> String {
+        text.replace(WORDCOUNT_PLACEHOLDER, &self.0.to_string())
+            .replace(READING_TIME_PLACEHOLDER, &self.reading_time().to_string())
+    }
+}
+
+fn is_placeholder(attributes: &DocumentAttributes, name: &str, placeholder: &str) -> bool {
+    matches!(attributes.get(name), Some(AttributeValue::String(value)) if value == placeholder)
+}
+
+fn has_placeholders(text: &str) -> bool {
+    text.contains(WORDCOUNT_PLACEHOLDER) || text.contains(READING_TIME_PLACEHOLDER)
+}
+
+fn count_inlines(inlines: &[InlineNode]) -> usize {
+    inlines_text(inlines).split_whitespace().count()
+}
+
+fn count_items(items: &[ListItem]) -> usize {
+    items.iter().map(|item| count_inlines(&item.content)).sum()
+}
+
+/// Counts the words of `block` itself, leaving out those of the blocks nested in it.
+fn count_block(block: &Block) -> usize {
+    match block {
+        Block::Paragraph(paragraph) => count_inlines(&paragraph.content),
+        Block::Section(section) => count_inlines(&section.title),
+        Block::DiscreteHeader(header) => count_inlines(&header.title),
+        Block::UnorderedList(list) => count_items(&list.items),
+        Block::OrderedList(list) => count_items(&list.items),
+        Block::CalloutList(list) => count_items(&list.items),
+        Block::DescriptionList(list) => list
+            .items
+            .iter()
+            .map(|item| {
+                item.terms
+                    .iter()
+                    .map(|term| count_inlines(term))
+                    .sum::<usize>()
+                    + match &item.description {
+                        DescriptionListDescription::Inline(inlines) => count_inlines(inlines),
+                        DescriptionListDescription::Blocks(_) => 0,
+                    }
+            })
+            .sum(),
+        Block::DelimitedBlock(delimited) => match &delimited.inner {
+            DelimitedBlockType::DelimitedVerse(inlines) => count_inlines(inlines),
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+fn count_blocks(blocks: &[Block]) -> usize {
+    let mut count = 0;
+    walk_blocks(blocks, &mut |block| count += count_block(block));
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, AttributeValue};
+
+    #[test]
+    fn test_wordcount_skips_code_blocks() {
+        let doc = parse(
+            "= Title\n:reading-time:\n\nThis document has {wordcount} words.\n\n----\nnot counted here\n----\n\n* one more\n",
+        )
+        .unwrap();
+        assert_eq!(
+            doc.attributes.get("wordcount"),
+            Some(&AttributeValue::String("7".to_string()))
+        );
+        assert_eq!(
+            doc.attributes.get("reading-time"),
+            Some(&AttributeValue::String("1".to_string()))
+        );
+        let crate::Block::Paragraph(paragraph) = &doc.blocks[0] else {
+            panic!("expected a paragraph, got {:?}", doc.blocks[0]);
+        };
+        assert_eq!(
+            crate::blocks::inlines_text(&paragraph.content),
+            "This document has 7 words."
+        );
+    }
+
+    #[test]
+    fn test_wordcount_fills_in_titles_and_footnotes() {
+        let doc =
+            parse("== About {wordcount}\n\nSome words.footnote:[All {wordcount}.]\n").unwrap();
+        let crate::Block::Section(section) = &doc.blocks[0] else {
+            panic!("expected a section, got {:?}", doc.blocks[0]);
+        };
+        assert_eq!(crate::blocks::inlines_text(&section.title), "About 4");
+        assert_eq!(section.id(), "_about_4");
+        assert_eq!(doc.footnotes[0].text.as_deref(), Some("All 4."));
+    }
+
+    #[test]
+    fn test_wordcount_fills_in_attribute_values() {
+        let doc = parse(
+            "= Title\n:reading-time:\n:page-reading-time: {reading-time}\n\nTakes {page-reading-time} min.\n\n:words: {wordcount} words\n\nHas {words}.\n",
+        )
+        .unwrap();
+        for attributes in [&doc.attributes, doc.header_attributes()] {
+            assert_eq!(
+                attributes.get("page-reading-time"),
+                Some(&AttributeValue::String("1".to_string()))
+            );
+        }
+        assert_eq!(
+            doc.attributes.get("words"),
+            Some(&AttributeValue::String("6 words".to_string()))
+        );
+        let crate::Block::Paragraph(paragraph) = &doc.blocks[0] else {
+            panic!("expected a paragraph, got {:?}", doc.blocks[0]);
+        };
+        assert_eq!(
+            crate::blocks::inlines_text(&paragraph.content),
+            "Takes 1 min."
+        );
+    }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_wordcount_logs_warnings_once() {
+        parse("= Title\n\nThere are {wordcount} words.\n\n[cols=\"2\"]\n|===\n|a |b |c\n|===\n")
+            .unwrap();
+        logs_assert(|lines: &[&str]| {
+            match lines
+                .iter()
+                .filter(|line| line.contains("padding it with empty cells"))
+                .count()
+            {
+                1 => Ok(()),
+                n => Err(format!("expected the warning to be logged once, got {n}")),
+            }
+        });
+    }
+}
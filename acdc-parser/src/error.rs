@@ -12,8 +12,8 @@ pub enum Error {
     #[error("section level mismatch: {1} (expected '{2}'), position: {0}")]
     NestedSectionLevelMismatch(Detail, SectionLevel, SectionLevel),
 
-    #[error("Invalid conditional directive")]
-    InvalidConditionalDirective,
+    #[error("Invalid conditional directive, position: {0}")]
+    InvalidConditionalDirective(Detail),
 
     #[error("Invalid include directive")]
     InvalidIncludeDirective,
@@ -27,6 +27,12 @@ pub enum Error {
     #[error("Invalid level offset: {0}")]
     InvalidLevelOffset(String),
 
+    #[error("unclosed tag '{1}' in included file, position: {0}")]
+    UnclosedTag(Detail, String),
+
+    #[error("line {1} is out of range of the {2} lines of the included file, position: {0}")]
+    IncludeLineOutOfRange(Detail, usize, usize),
+
     #[error("I/O error: {0}")]
     #[serde(skip_deserializing)]
     Io(#[from] std::io::Error),
@@ -43,6 +49,30 @@ pub enum Error {
     UnexpectedBlock(String),
 }
 
+impl Error {
+    /// Returns where in the document the error is, for the errors that know it.
+    #[must_use]
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::NestedSectionLevelMismatch(detail, ..)
+            | Error::InvalidConditionalDirective(detail)
+            | Error::UnclosedTag(detail, _)
+            | Error::IncludeLineOutOfRange(detail, ..) => Some(&detail.location),
+            _ => None,
+        }
+    }
+
+    /// Returns the offset of the error from the start of the document in bytes, for the
+    /// errors that know it. Errors from preprocessor directives only know their line.
+    #[must_use]
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Error::NestedSectionLevelMismatch(detail, ..) => Some(detail.location.offset()),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct Detail {
     pub location: Location,
@@ -109,5 +139,9 @@ mod tests {
             format!("{error}"),
             "section level mismatch: 1 (expected '2'), position: start(line: 1, column: 2), end(line: 3, column: 4)"
         );
+        let location = error.location().unwrap();
+        assert_eq!(location.start, Position { line: 1, column: 2 });
+        assert_eq!(location.offset(), 2);
+        assert!(Error::InvalidIncludeDirective.location().is_none());
     }
 }
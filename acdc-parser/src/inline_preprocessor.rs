@@ -1,21 +1,25 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 use pest::Parser;
 use pest_derive::Parser;
 use tracing::instrument;
 
 use crate::{
-    AttributeValue, DocumentAttributes, Error, Location, Pass, PassthroughKind, Position,
-    Substitution,
+    model::AttributeMissing, AttributeValue, DocumentAttributes, Error, Location, Pass,
+    PassthroughKind, Position, Substitution,
 };
 
 #[derive(Parser)]
 #[grammar = "../grammar/inline_preprocessor.pest"]
 struct InlinePreprocessorParser;
 
+/// Substitutes attribute references and counters in inline text and pulls out its
+/// passthroughs before it's parsed.
+///
+/// Counters are kept in the document attributes, which is why it needs to change them.
 #[derive(Debug)]
-pub(crate) struct InlinePreprocessor {
-    attributes: DocumentAttributes,
+pub(crate) struct InlinePreprocessor<'a> {
+    attributes: &'a mut DocumentAttributes,
     source_map: SourceMap,
 }
 
@@ -60,42 +64,6 @@ impl SourceMap {
         self.offsets = merged;
     }
 
-    fn add_offset_orig(&mut self, position: usize, offset: i32, kind: ProcessedKind) {
-        // Sort and merge overlapping/adjacent offsets
-        self.offsets.push((position, offset, kind.clone()));
-        self.offsets.sort_by_key(|k| k.0);
-
-        let mut merged = Vec::new();
-        let mut current_pos = 0;
-        let mut current_offset = 0;
-        let mut current_kind = kind;
-
-        for (pos, offs, offset_kind) in self.offsets.drain(..) {
-            match pos.cmp(&current_pos) {
-                std::cmp::Ordering::Equal => {
-                    current_offset += offs;
-                }
-                std::cmp::Ordering::Greater => {
-                    if current_offset != 0 {
-                        merged.push((current_pos, current_offset, current_kind));
-                    }
-                    current_pos = pos;
-                    current_offset = offs;
-                    current_kind = offset_kind;
-                }
-                std::cmp::Ordering::Less => {
-                    // Skip overlapping offsets
-                    continue;
-                }
-            }
-        }
-
-        if current_offset != 0 {
-            merged.push((current_pos, current_offset, current_kind));
-        }
-        self.offsets = merged;
-    }
-
     /// Map a source position back to its original location
     pub(crate) fn map_position(&self, pos: usize) -> usize {
         let mut offset = 0;
@@ -120,12 +88,11 @@ impl SourceMap {
 pub(crate) struct ProcessedContent {
     pub text: String,
     pub passthroughs: Vec<Pass>,
-    pub attributes: HashMap<usize, Location>,
     pub(crate) source_map: SourceMap,
 }
 
-impl InlinePreprocessor {
-    pub(crate) fn new(attributes: DocumentAttributes) -> Self {
+impl<'a> InlinePreprocessor<'a> {
+    pub(crate) fn new(attributes: &'a mut DocumentAttributes) -> Self {
         Self {
             attributes,
             source_map: SourceMap::default(),
@@ -141,42 +108,109 @@ impl InlinePreprocessor {
     ) -> Result<ProcessedContent, Error> {
         let mut result = String::with_capacity(text.len());
         let mut passthroughs = Vec::new();
-        let mut attributes = HashMap::new();
 
         let pairs = InlinePreprocessorParser::parse(Rule::preprocessed_text, text)
             .map_err(|e| Error::Parse(format!("Invalid inline text: {e}")))?;
+        let missing = AttributeMissing::missing(self.attributes);
         let mut pass_found_count = 0;
+        // Where the current line starts, in `result` and in `text`.
+        let mut line_start = (0, 0);
+        // The text before this is on a line that was dropped.
+        let mut skip_to = 0;
         for pair in pairs.flatten() {
+            let span = pair.as_span();
+            if span.start() < skip_to {
+                if matches!(
+                    pair.as_rule(),
+                    Rule::unprocessed_text | Rule::kbd_open | Rule::kbd_text | Rule::kbd_close
+                ) && span.end() > skip_to
+                {
+                    push_text(
+                        &text[skip_to..span.end()],
+                        skip_to,
+                        &mut result,
+                        &mut line_start,
+                    );
+                }
+                continue;
+            }
             match pair.as_rule() {
+                Rule::counter_ref => {
+                    let mut inner = pair.clone().into_inner();
+                    let kind = inner.next().map(|p| p.as_str()).unwrap_or_default();
+                    let name = inner.next().map(|p| p.as_str()).unwrap_or_default();
+                    let start = inner.next().map(|p| p.as_str());
+                    let value = self.next_counter_value(name, start);
+                    // `counter2` steps the counter without showing it.
+                    let value = if kind == "counter2" { "" } else { &value };
+                    self.substitute(&pair, value, start_position, &mut result);
+                }
                 Rule::attr_ref => {
                     let attr_name = pair.clone().into_inner().next().unwrap().as_str();
-                    if let Some(value) = self.attributes.get(attr_name) {
-                        match value {
-                            AttributeValue::String(s) => {
-                                let attr_span = pair.as_span();
-                                self.source_map.add_offset(
-                                    start_position + attr_span.start(),
-                                    i32::try_from(s.len()).unwrap_or_default()
-                                        - i32::try_from(attr_span.as_str().len())
-                                            .unwrap_or_default(),
-                                    ProcessedKind::Attribute,
-                                );
-                                result.push_str(s);
-                                attributes.insert(
-                                    self.source_map.offsets.len(),
-                                    Location::from_pair(&pair),
+                    match self.attributes.get(attr_name) {
+                        Some(AttributeValue::String(s)) => {
+                            let s = s.clone();
+                            self.substitute(&pair, &s, start_position, &mut result);
+                        }
+                        Some(AttributeValue::Bool(true)) => {
+                            self.substitute(&pair, "", start_position, &mut result);
+                        }
+                        Some(AttributeValue::None | AttributeValue::Inlines(_)) => {
+                            // TODO(nlopes): do we need to handle other types?
+                            // For these, keep original text
+                            result.push_str(pair.as_str());
+                        }
+                        Some(AttributeValue::Bool(false)) | None => match missing {
+                            AttributeMissing::Skip => result.push_str(pair.as_str()),
+                            AttributeMissing::Warn => {
+                                tracing::warn!(
+                                    name = attr_name,
+                                    "skipping reference to missing attribute"
                                 );
-                            }
-                            _ => {
-                                // TODO(nlopes): do we need to handle other types?
-                                // For non-string attributes, keep original text
                                 result.push_str(pair.as_str());
                             }
+                            AttributeMissing::Drop => {
+                                self.substitute(&pair, "", start_position, &mut result);
+                            }
+                            AttributeMissing::DropLine => {
+                                skip_to = self.drop_line(
+                                    text,
+                                    &pair,
+                                    start_position,
+                                    &mut result,
+                                    &mut line_start,
+                                );
+                            }
+                        },
+                    }
+                }
+                Rule::attr_set => {
+                    let mut inner = pair.clone().into_inner();
+                    let name = inner.next().map(|p| p.as_str()).unwrap_or_default();
+                    let value = inner.next();
+                    if value.as_ref().map(pest::iterators::Pair::as_rule) == Some(Rule::attr_unset)
+                    {
+                        self.attributes
+                            .insert(name.to_string(), AttributeValue::Bool(false));
+                        if AttributeMissing::undefined(self.attributes)
+                            == AttributeMissing::DropLine
+                        {
+                            skip_to = self.drop_line(
+                                text,
+                                &pair,
+                                start_position,
+                                &mut result,
+                                &mut line_start,
+                            );
+                            continue;
                         }
                     } else {
-                        // Keep unresolved references as-is
-                        result.push_str(pair.as_str());
+                        let value = value.map_or(AttributeValue::Bool(true), |value| {
+                            AttributeValue::String(value.as_str().to_string())
+                        });
+                        self.attributes.insert(name.to_string(), value);
                     }
+                    self.substitute(&pair, "", start_position, &mut result);
                 }
                 Rule::single_plus_passthrough
                 | Rule::double_plus_passthrough
@@ -187,9 +221,9 @@ impl InlinePreprocessor {
                     let len = span.end_pos().line_col().1 - span.start_pos().line_col().1;
 
                     // Insert placeholder
-                    result.push_str(&format!(
-                        "\u{FFFD}\u{FFFD}\u{FFFD}{pass_found_count}\u{FFFD}\u{FFFD}\u{FFFD}"
-                    ));
+                    result.push_str("\u{FFFD}\u{FFFD}\u{FFFD}");
+                    result.push_str(&pass_found_count.to_string());
+                    result.push_str("\u{FFFD}\u{FFFD}\u{FFFD}");
 
                     self.source_map.add_offset(
                         start_position + span.start(),
@@ -200,8 +234,8 @@ impl InlinePreprocessor {
                     pass_found_count += 1;
                     passthroughs.push(pass);
                 }
-                Rule::unprocessed_text => {
-                    result.push_str(pair.as_str());
+                Rule::unprocessed_text | Rule::kbd_open | Rule::kbd_text | Rule::kbd_close => {
+                    push_text(pair.as_str(), span.start(), &mut result, &mut line_start);
                 }
                 _ => {}
             }
@@ -210,11 +244,77 @@ impl InlinePreprocessor {
         Ok(ProcessedContent {
             text: result,
             passthroughs,
-            attributes,
             source_map: self.source_map.clone(),
         })
     }
 
+    /// Writes `value` in place of the text matched by `pair`, keeping track of how much
+    /// longer or shorter that makes the text.
+    fn substitute(
+        &mut self,
+        pair: &pest::iterators::Pair<Rule>,
+        value: &str,
+        start_position: usize,
+        result: &mut String,
+    ) {
+        let span = pair.as_span();
+        self.source_map.add_offset(
+            start_position + span.start(),
+            i32::try_from(value.len()).unwrap_or_default()
+                - i32::try_from(span.as_str().len()).unwrap_or_default(),
+            ProcessedKind::Attribute,
+        );
+        result.push_str(value);
+    }
+
+    /// Removes the line with the text matched by `pair` from `result`, returning where
+    /// that line ends in `text` so that the rest of it is skipped.
+    fn drop_line(
+        &mut self,
+        text: &str,
+        pair: &pest::iterators::Pair<Rule>,
+        start_position: usize,
+        result: &mut String,
+        line_start: &mut (usize, usize),
+    ) -> usize {
+        let span = pair.as_span();
+        let line_end = text[span.end()..]
+            .find('\n')
+            .map_or(text.len(), |i| span.end() + i + 1);
+        let mut removed = result.len() - line_start.0 + line_end - span.start();
+        result.truncate(line_start.0);
+        // Without a line after it, the newline before the dropped line goes too.
+        if !text[..line_end].ends_with('\n') && result.ends_with('\n') {
+            result.pop();
+            removed += 1;
+        }
+        self.source_map.add_offset(
+            start_position + line_start.1,
+            -i32::try_from(removed).unwrap_or_default(),
+            ProcessedKind::Attribute,
+        );
+        *line_start = (result.len(), line_end);
+        line_end
+    }
+
+    /// Steps the counter `name` and returns its new value.
+    ///
+    /// Like in asciidoctor, the counter is the attribute of the same name. When it isn't
+    /// set yet, it starts at `start`, or at 1 without one. Otherwise a number goes up by
+    /// one and letters move on to the next ones (`A`, `B`, ..., `Z`, `AA`).
+    fn next_counter_value(&mut self, name: &str, start: Option<&str>) -> String {
+        let value = match self.attributes.get(name) {
+            Some(AttributeValue::String(current)) => match current.parse::<i64>() {
+                Ok(number) => (number + 1).to_string(),
+                Err(_) => next_letters(current),
+            },
+            _ => start.unwrap_or("1").to_string(),
+        };
+        self.attributes
+            .insert(name.to_string(), AttributeValue::String(value.clone()));
+        value
+    }
+
     /// Create a Pass instance from a passthrough rule match
     fn create_passthrough(
         &mut self,
@@ -327,10 +427,43 @@ impl InlinePreprocessor {
     }
 }
 
+/// Appends `text`, which starts at `offset` in the text being processed, to `result`,
+/// keeping track of where the last line in it starts.
+fn push_text(text: &str, offset: usize, result: &mut String, line_start: &mut (usize, usize)) {
+    if let Some(i) = text.rfind('\n') {
+        *line_start = (result.len() + i + 1, offset + i + 1);
+    }
+    result.push_str(text);
+}
+
+/// Returns the letters that come after `letters`, carrying over like digits do: `a`
+/// becomes `b` and `Az` becomes `Ba`, while `Z` becomes `AA`.
+fn next_letters(letters: &str) -> String {
+    let mut chars = letters.chars().collect::<Vec<_>>();
+    for c in chars.iter_mut().rev() {
+        match *c {
+            'z' => *c = 'a',
+            'Z' => *c = 'A',
+            c2 if c2.is_ascii_alphabetic() => {
+                *c = char::from(u8::try_from(u32::from(c2) + 1).unwrap_or_default());
+                return chars.into_iter().collect();
+            }
+            _ => return letters.to_string(),
+        }
+    }
+    // Every letter carried over, so there's one more in front, of the same case.
+    let first = if letters.starts_with(|c: char| c.is_ascii_lowercase()) {
+        'a'
+    } else {
+        'A'
+    };
+    std::iter::once(first).chain(chars).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::DocumentAttributes;
+    use crate::{Block, DocumentAttributes, InlineNode};
 
     fn setup_attributes() -> DocumentAttributes {
         let mut attrs = DocumentAttributes::default();
@@ -342,8 +475,8 @@ mod tests {
 
     #[test]
     fn test_preprocess_attribute_in_link() {
-        let attrs = setup_attributes();
-        let mut preprocessor = InlinePreprocessor::new(attrs);
+        let mut attrs = setup_attributes();
+        let mut preprocessor = InlinePreprocessor::new(&mut attrs);
 
         let input = "The {s}[syntax page] provides complete stuff.";
         let start_pos = 0;
@@ -365,8 +498,8 @@ mod tests {
 
     #[test]
     fn test_preprocess_inline_passthrough() {
-        let attrs = setup_attributes();
-        let mut preprocessor = InlinePreprocessor::new(attrs);
+        let mut attrs = setup_attributes();
+        let mut preprocessor = InlinePreprocessor::new(&mut attrs);
 
         let input = "Something\n\nHere is some +*bold*+ text and ++**more bold**++ text.";
         //                 0123456789012345678901234567890123456789012345678901234567890123456
@@ -401,8 +534,8 @@ mod tests {
 
     #[test]
     fn test_preprocess_inline_in_attributes() {
-        let attrs = setup_attributes();
-        let mut preprocessor = InlinePreprocessor::new(attrs);
+        let mut attrs = setup_attributes();
+        let mut preprocessor = InlinePreprocessor::new(&mut attrs);
 
         // Test block title with attribute reference
         let input = "Version {version} of {title}";
@@ -436,8 +569,8 @@ mod tests {
 
     #[test]
     fn test_preprocess_complex_example() {
-        let attrs = setup_attributes();
-        let mut preprocessor = InlinePreprocessor::new(attrs.clone());
+        let mut attrs = setup_attributes();
+        let mut preprocessor = InlinePreprocessor::new(&mut attrs);
 
         // Complex example with attribute in link and passthrough
         let input = "Check the {s}[syntax page] and +this {s} won't expand+ for details.";
@@ -470,7 +603,7 @@ mod tests {
         attrs.insert("nested1".into(), AttributeValue::String("{version}".into()));
         attrs.insert("nested2".into(), AttributeValue::String("{nested1}".into()));
 
-        let mut preprocessor = InlinePreprocessor::new(attrs);
+        let mut preprocessor = InlinePreprocessor::new(&mut attrs);
 
         // Test passthrough containing attribute that references another attribute
         let input = "Here is a +special {nested2} value+ to test.";
@@ -502,7 +635,7 @@ mod tests {
         // Add docname attribute
         attrs.insert("docname".into(), AttributeValue::String("test-doc".into()));
 
-        let mut preprocessor = InlinePreprocessor::new(attrs);
+        let mut preprocessor = InlinePreprocessor::new(&mut attrs);
         let input = "The text pass:q,a[<u>underline _{docname}_</u>] is underlined.";
         //                 01234567890123456789012345678901234567890123456789012345678901
         //                          ^start of pass        ^docname
@@ -540,8 +673,8 @@ mod tests {
 
     #[test]
     fn test_line_breaks() {
-        let attrs = setup_attributes();
-        let mut preprocessor = InlinePreprocessor::new(attrs);
+        let mut attrs = setup_attributes();
+        let mut preprocessor = InlinePreprocessor::new(&mut attrs);
 
         let input = "This is a test +\nwith a line break.";
         //                 012345678901234567890123456789012345678
@@ -558,8 +691,8 @@ mod tests {
 
     #[test]
     fn test_section_with_passthrough() {
-        let attrs = setup_attributes();
-        let mut preprocessor = InlinePreprocessor::new(attrs);
+        let mut attrs = setup_attributes();
+        let mut preprocessor = InlinePreprocessor::new(&mut attrs);
 
         let input = "= Document Title\nHello +<h1>+World+</h1>+ of +<u>+Gemini+</u>+";
         //                 012345678901234567890123456789012345678901234567890123456789012
@@ -570,18 +703,21 @@ mod tests {
 
         assert_eq!(
             result.text,
-            "= Document Title\nHello \u{FFFD}\u{FFFD}\u{FFFD}0\u{FFFD}\u{FFFD}\u{FFFD}World\u{FFFD}\u{FFFD}\u{FFFD}1\u{FFFD}\u{FFFD}\u{FFFD} of \u{FFFD}\u{FFFD}\u{FFFD}2\u{FFFD}\u{FFFD}\u{FFFD}Gemini\u{FFFD}\u{FFFD}\u{FFFD}3\u{FFFD}\u{FFFD}\u{FFFD}"
+            "= Document Title\nHello \u{FFFD}\u{FFFD}\u{FFFD}0\u{FFFD}\u{FFFD}\u{FFFD}World+</h1>+ of \u{FFFD}\u{FFFD}\u{FFFD}1\u{FFFD}\u{FFFD}\u{FFFD}Gemini+</u>+"
         );
 
+        // A single plus passthrough can't start right after a word character, so
+        // `World+</h1>+` and `Gemini+</u>+` are left as text.
+
         // Verify passthrough was captured
-        assert_eq!(result.passthroughs.len(), 4);
+        assert_eq!(result.passthroughs.len(), 2);
 
         let first_pass = &result.passthroughs[0];
         let second_pass = &result.passthroughs[1];
 
         // Check passthrough content preserved original text
         assert_eq!(first_pass.text.as_ref().unwrap(), "<h1>");
-        assert_eq!(second_pass.text.as_ref().unwrap(), "</h1>");
+        assert_eq!(second_pass.text.as_ref().unwrap(), "<u>");
 
         // Verify substitutions were captured
         assert!(first_pass
@@ -598,7 +734,126 @@ mod tests {
             .contains(&Substitution::SpecialChars));
 
         // Check positions
-        assert_eq!(second_pass.location.absolute_start, 34); // Start of pass macro
-        assert_eq!(second_pass.location.absolute_end, 41); // End of pass macro content including brackets
+        assert_eq!(second_pass.location.absolute_start, 45); // Start of pass macro
+        assert_eq!(second_pass.location.absolute_end, 50); // End of pass macro content including brackets
+    }
+
+    #[test]
+    fn test_preprocess_counters() {
+        let mut attrs = DocumentAttributes::default();
+        let mut preprocessor = InlinePreprocessor::new(&mut attrs);
+        let result = preprocessor
+            .process(
+                "{counter:step} {counter:step}{counter2:step} {counter:step} {counter:seq:A} {counter:seq} {counter:from:5}",
+                0,
+            )
+            .unwrap();
+        assert_eq!(result.text, "1 2 4 A B 5");
+        assert_eq!(attrs.get("step"), Some(&AttributeValue::String("4".into())));
+    }
+
+    #[test]
+    fn test_counters_carry_across_blocks() {
+        let doc = crate::parse("Step {counter:s}.\n\nStep {counter:s}.\n").unwrap();
+        let texts = doc
+            .blocks
+            .iter()
+            .map(|block| match block {
+                crate::Block::Paragraph(paragraph) => {
+                    crate::blocks::inlines_text(&paragraph.content)
+                }
+                other => panic!("expected a paragraph, got {other:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec!["Step 1.", "Step 2."]);
+        assert_eq!(
+            doc.attributes.get("s"),
+            Some(&AttributeValue::String("2".into()))
+        );
+    }
+
+    #[test]
+    fn test_preprocess_attribute_missing() {
+        let text = "one {missing} ++{missing}++\ntwo {missing}\nthree";
+        let process = |mode: &str| {
+            let mut attrs = DocumentAttributes::default();
+            attrs.insert(
+                "attribute-missing".to_string(),
+                AttributeValue::String(mode.to_string()),
+            );
+            InlinePreprocessor::new(&mut attrs)
+                .process(text, 0)
+                .unwrap()
+                .text
+        };
+        assert_eq!(
+            process("skip"),
+            "one {missing} \u{FFFD}\u{FFFD}\u{FFFD}0\u{FFFD}\u{FFFD}\u{FFFD}\ntwo {missing}\nthree"
+        );
+        assert_eq!(
+            process("drop"),
+            "one  \u{FFFD}\u{FFFD}\u{FFFD}0\u{FFFD}\u{FFFD}\u{FFFD}\ntwo \nthree"
+        );
+        assert_eq!(process("drop-line"), "three");
+    }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_preprocess_attribute_missing_warn() {
+        let mut attrs = DocumentAttributes::default();
+        attrs.insert(
+            "attribute-missing".to_string(),
+            AttributeValue::String("warn".to_string()),
+        );
+        let result = InlinePreprocessor::new(&mut attrs)
+            .process("a {missing} reference", 0)
+            .unwrap();
+        assert_eq!(result.text, "a {missing} reference");
+        assert!(logs_contain("skipping reference to missing attribute"));
+    }
+
+    #[test]
+    fn test_preprocess_attribute_undefined() {
+        let text = "{set:name:value}{name}\n{set:name!}dropped\n{name} stays";
+        let mut attrs = DocumentAttributes::default();
+        let result = InlinePreprocessor::new(&mut attrs)
+            .process(text, 0)
+            .unwrap();
+        assert_eq!(result.text, "value\n{name} stays");
+        assert_eq!(attrs.get("name"), Some(&AttributeValue::Bool(false)));
+
+        let mut attrs = DocumentAttributes::default();
+        attrs.insert(
+            "attribute-undefined".to_string(),
+            AttributeValue::String("drop".to_string()),
+        );
+        let result = InlinePreprocessor::new(&mut attrs)
+            .process(text, 0)
+            .unwrap();
+        assert_eq!(result.text, "value\ndropped\n{name} stays");
+    }
+
+    #[test]
+    fn test_parse_paragraph_with_every_line_dropped() {
+        let doc = crate::parse(":foo: bar\n\nText {set:foo!} more.\n\nAfter.\n").unwrap();
+        assert_eq!(doc.blocks.len(), 1);
+        let Block::Paragraph(paragraph) = &doc.blocks[0] else {
+            panic!("expected a paragraph, got {:?}", doc.blocks[0]);
+        };
+        let [InlineNode::PlainText(text)] = paragraph.content.as_slice() else {
+            panic!("expected plain text, got {:?}", paragraph.content);
+        };
+        assert_eq!(text.content, "After.");
+
+        let doc = crate::parse(":attribute-missing: drop-line\n\nOnly {undefined}\n").unwrap();
+        assert_eq!(doc.blocks, Vec::new());
+    }
+
+    #[test]
+    fn test_next_letters() {
+        assert_eq!(next_letters("A"), "B");
+        assert_eq!(next_letters("z"), "aa");
+        assert_eq!(next_letters("Az"), "Ba");
+        assert_eq!(next_letters("ZZ"), "AAA");
     }
 }
@@ -0,0 +1,28 @@
+use pest::iterators::Pairs;
+
+use crate::{Footnote, Location, Rule};
+
+impl Footnote {
+    /// Parses a footnote, in either its `footnote:[text]` or `footnote:name[text]` form.
+    ///
+    /// Footnotes are numbered once the whole document is parsed, see
+    /// [`crate::Document::footnotes`].
+    pub(crate) fn parse_inline(pairs: Pairs<Rule>, location: Location) -> Self {
+        let mut id = None;
+        let mut text = None;
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::footnote_id => id = Some(pair.as_str().to_string()),
+                Rule::footnote_text => text = Some(pair.as_str().trim().to_string()),
+                Rule::EOI | Rule::comment => {}
+                unknown => unreachable!("{unknown:?}"),
+            }
+        }
+        Self {
+            id,
+            text,
+            number: 0,
+            location,
+        }
+    }
+}
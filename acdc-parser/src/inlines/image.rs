@@ -7,6 +7,7 @@ impl Image {
         let mut metadata = BlockMetadata::default();
         let mut source = ImageSource::Path(String::new());
         let mut attributes = ElementAttributes::default();
+        let mut positional = ["alt", "width", "height"].into_iter();
         for pair in pairs {
             match pair.as_rule() {
                 Rule::path => source = ImageSource::Path(pair.as_str().to_string()),
@@ -14,9 +15,17 @@ impl Image {
                 Rule::named_attribute => {
                     super::parse_named_attribute(pair.into_inner(), &mut attributes);
                 }
-                Rule::positional_attribute_value => {
-                    attributes.insert(pair.as_str().to_string(), AttributeValue::None);
-                }
+                Rule::positional_attribute_value => match positional.next() {
+                    Some(name) => {
+                        attributes.insert(
+                            name.to_string(),
+                            AttributeValue::String(pair.as_str().to_string()),
+                        );
+                    }
+                    None => {
+                        attributes.insert(pair.as_str().to_string(), AttributeValue::None);
+                    }
+                },
                 Rule::EOI | Rule::comment | Rule::open_sb | Rule::close_sb => {}
                 unknown => unreachable!("{unknown:?}"),
             }
@@ -7,7 +7,7 @@ impl Keyboard {
         let mut keys = Vec::new();
         for pair in pairs {
             match pair.as_rule() {
-                Rule::key => keys.push(pair.as_str().to_string()),
+                Rule::key => keys.extend(split_keys(pair.as_str())),
                 Rule::EOI | Rule::comment => {}
                 unknown => unreachable!("{unknown:?}"),
             }
@@ -15,3 +15,103 @@ impl Keyboard {
         Self { keys, location }
     }
 }
+
+/// Splits the content of a `kbd:[]` macro into its keys.
+///
+/// Keys are separated by `,` when there is one, `+` otherwise. A separator with no
+/// key before it is the key itself (`kbd:[Ctrl++]`, `kbd:[++]`), and `\+` is always a
+/// literal `+`.
+fn split_keys(text: &str) -> Vec<String> {
+    let delimiter = if text.trim() != "," && text.contains(',') {
+        ','
+    } else {
+        '+'
+    };
+    let mut keys = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.peek() == Some(&'+') {
+            current.push('+');
+            chars.next();
+        } else if c == delimiter && !current.trim().is_empty() {
+            keys.push(current.trim().to_string());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.trim().is_empty() {
+        keys.push(current.trim().to_string());
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Block, InlineMacro, InlineNode};
+
+    fn keys(input: &str) -> Vec<String> {
+        let doc = parse(&format!(":experimental:\n\n{input}\n")).unwrap();
+        let Block::Paragraph(paragraph) = &doc.blocks[0] else {
+            panic!("expected a paragraph, got {:?}", doc.blocks[0]);
+        };
+        match &paragraph.content[..] {
+            [InlineNode::Macro(InlineMacro::Keyboard(keyboard))] => keyboard.keys.clone(),
+            other => panic!("expected a keyboard macro, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_keyboard_keys() {
+        assert_eq!(keys("kbd:[Ctrl+T]"), vec!["Ctrl", "T"]);
+        assert_eq!(keys("kbd:[Ctrl,Shift,T]"), vec!["Ctrl", "Shift", "T"]);
+        assert_eq!(keys("kbd:[Ctrl+Shift+T]"), vec!["Ctrl", "Shift", "T"]);
+        assert_eq!(keys("kbd:[F11]"), vec!["F11"]);
+    }
+
+    #[test]
+    fn test_parse_keyboard_plus_key() {
+        assert_eq!(keys("kbd:[Ctrl++]"), vec!["Ctrl", "+"]);
+        assert_eq!(keys("kbd:[++]"), vec!["+"]);
+        assert_eq!(keys("kbd:[Ctrl+\\+]"), vec!["Ctrl", "+"]);
+    }
+
+    #[test]
+    fn test_parse_keyboard_comma_separated() {
+        assert_eq!(keys("kbd:[Ctrl,+]"), vec!["Ctrl", "+"]);
+        assert_eq!(keys("kbd:[Ctrl,,]"), vec!["Ctrl", ","]);
+    }
+
+    #[test]
+    fn test_parse_several_keyboard_macros_with_plus_keys() {
+        let all_keys = |input: &str| {
+            let doc = parse(&format!(":experimental:\n:key: F5\n\n{input}\n")).unwrap();
+            let Block::Paragraph(paragraph) = &doc.blocks[0] else {
+                panic!("expected a paragraph, got {:?}", doc.blocks[0]);
+            };
+            paragraph
+                .content
+                .iter()
+                .filter_map(|node| match node {
+                    InlineNode::Macro(InlineMacro::Keyboard(keyboard)) => {
+                        Some(keyboard.keys.clone())
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            all_keys("Press kbd:[Ctrl++] to zoom in and kbd:[Ctrl+-] to zoom out."),
+            vec![vec!["Ctrl", "+"], vec!["Ctrl", "-"]]
+        );
+        assert_eq!(
+            all_keys("kbd:[Ctrl++] then kbd:[Alt+F4]"),
+            vec![vec!["Ctrl", "+"], vec!["Alt", "F4"]]
+        );
+        assert_eq!(
+            all_keys("kbd:[Ctrl+{key}] and kbd:[++]"),
+            vec![vec!["Ctrl", "F5"], vec!["+"]]
+        );
+    }
+}
@@ -33,4 +33,54 @@ impl Link {
             location,
         }
     }
+
+    /// Parses a `mailto:` macro into a link to the email address, adding the subject and
+    /// body (if given) to the query string of the target.
+    pub(crate) fn parse_mailto(pairs: Pairs<Rule>, location: Location) -> Self {
+        let mut text = None;
+        let mut target = String::from("mailto:");
+        let mut query = Vec::new();
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::email_address => target.push_str(pair.as_str()),
+                Rule::link_title => {
+                    text = Some(pair.as_str().trim_matches('"').to_string());
+                }
+                Rule::mailto_subject => {
+                    query.push(format!("subject={}", percent_encode(pair.as_str().trim())));
+                }
+                Rule::mailto_body => {
+                    query.push(format!("body={}", percent_encode(pair.as_str().trim())));
+                }
+                Rule::EOI | Rule::comment => {}
+                unknown => unreachable!("{unknown:?}"),
+            }
+        }
+        if !query.is_empty() {
+            target.push('?');
+            target.push_str(&query.join("&"));
+        }
+        Self {
+            text,
+            target,
+            attributes: ElementAttributes::default(),
+            location,
+        }
+    }
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986.
+fn percent_encode(text: &str) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push('%');
+            encoded.push(char::from(HEX[usize::from(byte >> 4)]));
+            encoded.push(char::from(HEX[usize::from(byte & 0xf)]));
+        }
+    }
+    encoded
 }
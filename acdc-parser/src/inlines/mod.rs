@@ -1,12 +1,15 @@
 mod autolink;
 mod button;
+mod footnote;
 mod icon;
 mod image;
 mod keyboard;
 mod link;
 mod menu;
 mod pass;
+mod stem;
 mod url;
+mod xref;
 
 use pest::{
     iterators::{Pair, Pairs},
@@ -15,10 +18,11 @@ use pest::{
 use tracing::instrument;
 
 use crate::{
-    error::Error, inline_preprocessor::ProcessedKind, AttributeValue, Autolink, Bold, Button,
-    DocumentAttributes, ElementAttributes, Highlight, Icon, Image, InlineMacro, InlineNode, Italic,
-    Keyboard, LineBreak, Link, Location, Menu, Monospace, Pass, PassthroughKind, Plain, Position,
-    ProcessedContent, Raw, Rule, Subscript, Superscript, Url,
+    error::Error, inline_preprocessor::ProcessedKind, Anchor, AttributeValue, Autolink, Bold,
+    Button, CrossReference, DocumentAttributes, ElementAttributes, Footnote, Highlight, Icon,
+    Image, InlineMacro, InlineNode, Italic, Keyboard, LineBreak, Link, Location, Menu, Monospace,
+    Pass, PassthroughKind, Plain, Position, ProcessedContent, Raw, Rule, Stem, Subscript,
+    Superscript, Url,
 };
 
 impl InlineNode {
@@ -30,7 +34,8 @@ impl InlineNode {
         parent_attributes: &mut DocumentAttributes,
         last_index_seen: &mut Option<usize>,
     ) -> Result<InlineNode, Error> {
-        let mut role = None;
+        let mut roles = Vec::new();
+        let mut id = None;
 
         for pair in pairs {
             let mut location = Location::from_pair(&pair);
@@ -71,7 +76,8 @@ impl InlineNode {
                         parent_attributes,
                     )?;
                     return Ok(InlineNode::HighlightText(Highlight {
-                        role,
+                        role: inline_role(&roles),
+                        id,
                         content,
                         location,
                     }));
@@ -87,7 +93,8 @@ impl InlineNode {
                         parent_attributes,
                     )?;
                     return Ok(InlineNode::ItalicText(Italic {
-                        role,
+                        role: inline_role(&roles),
+                        id,
                         content,
                         location,
                     }));
@@ -103,7 +110,8 @@ impl InlineNode {
                         parent_attributes,
                     )?;
                     return Ok(InlineNode::BoldText(Bold {
-                        role,
+                        role: inline_role(&roles),
+                        id,
                         content,
                         location: mapped_location.1,
                     }));
@@ -119,7 +127,8 @@ impl InlineNode {
                         parent_attributes,
                     )?;
                     return Ok(InlineNode::MonospaceText(Monospace {
-                        role,
+                        role: inline_role(&roles),
+                        id,
                         content,
                         location,
                     }));
@@ -134,7 +143,8 @@ impl InlineNode {
                         parent_attributes,
                     )?;
                     return Ok(InlineNode::SubscriptText(Subscript {
-                        role,
+                        role: inline_role(&roles),
+                        id,
                         content,
                         location,
                     }));
@@ -149,7 +159,8 @@ impl InlineNode {
                         parent_attributes,
                     )?;
                     return Ok(InlineNode::SuperscriptText(Superscript {
-                        role,
+                        role: inline_role(&roles),
+                        id,
                         content,
                         location,
                     }));
@@ -161,8 +172,32 @@ impl InlineNode {
                 | Rule::menu_inline
                 | Rule::url_macro
                 | Rule::link_macro
+                | Rule::mailto_macro
                 | Rule::autolink
                 | Rule::pass_inline => return Self::parse_macro(pair),
+                Rule::stem_inline => {
+                    return Ok(InlineNode::Macro(InlineMacro::Stem(Stem::parse_inline(
+                        pair.into_inner(),
+                        location,
+                        parent_attributes,
+                    ))));
+                }
+                Rule::xref_inline => {
+                    return Ok(InlineNode::Macro(InlineMacro::CrossReference(
+                        CrossReference::parse_inline(pair.into_inner(), location),
+                    )));
+                }
+                Rule::anchor_inline => {
+                    return Ok(InlineNode::InlineAnchor(Anchor {
+                        location,
+                        ..Anchor::parse(pair.into_inner())
+                    }));
+                }
+                Rule::footnote => {
+                    return Ok(InlineNode::Macro(InlineMacro::Footnote(
+                        Footnote::parse_inline(pair.into_inner(), location),
+                    )));
+                }
                 Rule::placeholder => {
                     let kind = processed
                         .unwrap()
@@ -182,11 +217,30 @@ impl InlineNode {
                         location: mapped_location.1,
                     }));
                 }
-                Rule::role => role = Some(pair.as_str().to_string()),
+                Rule::role | Rule::positional_attribute_value => {
+                    roles.push(pair.as_str().to_string());
+                }
+                Rule::block_style_id => id = Some(pair.as_str().to_string()),
+                Rule::named_attribute => {
+                    for inner in pair.into_inner() {
+                        match inner.as_rule() {
+                            Rule::id => id = Some(inner.as_str().to_string()),
+                            Rule::role => roles.push(inner.as_str().to_string()),
+                            _ => {}
+                        }
+                    }
+                }
                 Rule::inline_line_break | Rule::hard_wrap => {
                     return Ok(InlineNode::LineBreak(LineBreak { location }));
                 }
-                Rule::EOI | Rule::comment => {}
+                // Asciidoctor only keeps the id and roles of quoted text, options have no
+                // effect on it.
+                Rule::EOI
+                | Rule::comment
+                | Rule::open_sb
+                | Rule::close_sb
+                | Rule::option
+                | Rule::empty_style => {}
                 unknown => unreachable!("{unknown:?}"),
             }
         }
@@ -225,6 +279,10 @@ impl InlineNode {
                 pair.into_inner(),
                 location,
             )))),
+            Rule::mailto_macro => Ok(InlineNode::Macro(InlineMacro::Link(Link::parse_mailto(
+                pair.into_inner(),
+                location,
+            )))),
             Rule::link_macro => Ok(InlineNode::Macro(InlineMacro::Link(Link::parse_inline(
                 pair.into_inner(),
                 location,
@@ -241,6 +299,15 @@ impl InlineNode {
     }
 }
 
+/// Joins the roles given to quoted text (`[.role1.role2]*text*`) into a single class list.
+fn inline_role(roles: &[String]) -> Option<String> {
+    if roles.is_empty() {
+        None
+    } else {
+        Some(roles.join(" "))
+    }
+}
+
 fn parse_named_attribute(pairs: Pairs<Rule>, attributes: &mut ElementAttributes) {
     let mut name = String::new();
 
@@ -358,115 +425,6 @@ pub(crate) fn get_content(
     Ok(content)
 }
 
-fn map_inline_location2(
-    location: &Location,
-    processed: Option<&ProcessedContent>,
-    index: Option<usize>,
-) -> Option<(Option<String>, Location)> {
-    if let Some(processed) = processed {
-        let effective_start = location.absolute_start;
-        let (matching_pass, passthroughs_before_count) = processed.passthroughs.iter().fold(
-            (None, 0),
-            |(matching_pass, passthroughs_before_count), pass| {
-                if pass.location.absolute_start < effective_start {
-                    (None, passthroughs_before_count + 1)
-                }
-                // We adjust this by multiplying the passthroughs_before_count by 3 because that's the
-                // length of the passthrough according to pest (len_utf8)
-                else if pass.location.absolute_start
-                    == effective_start + 3 * passthroughs_before_count
-                    || pass.location.absolute_start == effective_start + 3
-                    || pass.location.absolute_start
-                        == effective_start
-                            + 3 * (if passthroughs_before_count > 0 {
-                                passthroughs_before_count - 1
-                            } else {
-                                passthroughs_before_count
-                            })
-                {
-                    (Some(pass), passthroughs_before_count)
-                } else {
-                    (matching_pass, passthroughs_before_count)
-                }
-            },
-        );
-        if let Some(pass) = matching_pass {
-            let mapped_start = processed
-                .source_map
-                .map_position(effective_start - 2 * passthroughs_before_count)
-                + 1;
-            let mapped_end = processed.source_map.map_position(location.absolute_end);
-
-            let location = Location {
-                start: Position {
-                    line: location.start.line,
-                    column: mapped_start,
-                },
-                end: Position {
-                    line: location.end.line,
-                    column: mapped_end,
-                },
-                absolute_start: pass.location.absolute_start,
-                absolute_end: pass.location.absolute_end,
-            };
-            return Some((pass.text.clone(), location.clone()));
-        }
-
-        // Add the following code to sort the location of the attributes
-        // if we're here and we're within the location of an attributes (per the source map having an entry for it) then, we need to adjust start and end columns
-        // to the original source location
-        //
-        // This is because the source map only maps the start and end columns of the attributes
-        // and not the content within the attributes
-        if let Some((_, offset, _)) =
-            processed
-                .source_map
-                .offsets
-                .iter()
-                .find(|(absolute_start, _offset, kind)| {
-                    *kind == ProcessedKind::Attribute && *absolute_start == location.absolute_start
-                })
-        {
-            let mut adjusted_location = location.clone();
-            let end = i32::try_from(adjusted_location.end.column).unwrap() - offset;
-            adjusted_location.end.column = usize::try_from(end).unwrap();
-
-            return Some((None, adjusted_location));
-        }
-
-        // If we're here, we're adjusting the location of plain text usually.
-        let mut adjusted_location = location.clone();
-
-        // Map positions back to the original source using the source map
-        adjusted_location.start.column = processed
-            .source_map
-            .map_position(adjusted_location.start.column);
-        adjusted_location.end.column = processed
-            .source_map
-            .map_position(adjusted_location.end.column);
-
-        // This feels like a hack but it makes no sense to have the end column be the same
-        // as the start column, so we adjust it by one. TODO(nlopes): Investigate why this is
-        // happening.
-        if adjusted_location.start.column == adjusted_location.end.column {
-            adjusted_location.end.column += 1;
-        }
-
-        // Adjust the absolute start and end positions to the original source
-        adjusted_location.absolute_start = processed
-            .source_map
-            .map_position(adjusted_location.absolute_start)
-            - 2 * passthroughs_before_count;
-        adjusted_location.absolute_end = processed
-            .source_map
-            .map_position(adjusted_location.absolute_end)
-            - 2 * passthroughs_before_count;
-        Some((None, adjusted_location))
-    } else {
-        None
-    }
-}
-
 #[instrument(level = "trace")]
 #[allow(clippy::too_many_arguments)]
 fn map_inline_location(
@@ -591,3 +549,57 @@ fn map_inline_location(
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Block, InlineNode};
+
+    fn inlines(input: &str) -> Vec<InlineNode> {
+        let doc = parse(input).unwrap();
+        let Block::Paragraph(paragraph) = &doc.blocks[0] else {
+            panic!("expected a paragraph, got {:?}", doc.blocks[0]);
+        };
+        paragraph.content.clone()
+    }
+
+    #[test]
+    fn test_parse_span_roles_and_id() {
+        let [InlineNode::BoldText(bold)] = &inlines("[.role1.role2#id%option]*bold*\n")[..] else {
+            panic!("expected a single bold span");
+        };
+        assert_eq!(bold.role.as_deref(), Some("role1 role2"));
+        assert_eq!(bold.id.as_deref(), Some("id"));
+
+        let [InlineNode::HighlightText(highlight)] = &inlines("[role=big,id=x]#text#\n")[..] else {
+            panic!("expected a single highlight span");
+        };
+        assert_eq!(highlight.role.as_deref(), Some("big"));
+        assert_eq!(highlight.id.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn test_parse_span_role_applies_to_next_span_only() {
+        let found = inlines("[.big]_one_ and _two_\n");
+        let roles = found
+            .iter()
+            .filter_map(|inline| match inline {
+                InlineNode::ItalicText(italic) => Some(italic.role.as_deref()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(roles, vec![Some("big"), None]);
+    }
+
+    #[test]
+    fn test_parse_constrained_marks_inside_words() {
+        for mark in ['*', '_', '`', '#'] {
+            for input in ["Ratio 3*x + y * 2 holds.\n", "Use x*y and 2 * 3.\n"] {
+                let input = input.replace('*', &mark.to_string());
+                assert!(
+                    matches!(&inlines(&input)[..], [InlineNode::PlainText(_)]),
+                    "{input:?} should be plain text"
+                );
+            }
+        }
+    }
+}
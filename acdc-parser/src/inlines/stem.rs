@@ -0,0 +1,37 @@
+use pest::iterators::Pairs;
+
+use crate::{DocumentAttributes, Location, Rule, Stem, StemNotation};
+
+impl Stem {
+    /// Parses an inline stem macro.
+    ///
+    /// `asciimath:[]` and `latexmath:[]` name their notation explicitly, while `stem:[]`
+    /// uses the one set by the `stem` document attribute.
+    pub(crate) fn parse_inline(
+        pairs: Pairs<Rule>,
+        location: Location,
+        parent_attributes: &DocumentAttributes,
+    ) -> Self {
+        let mut content = String::new();
+        let mut notation = StemNotation::Asciimath;
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::stem_notation => {
+                    notation = match pair.as_str() {
+                        "latexmath" => StemNotation::Latexmath,
+                        "stem" => StemNotation::from_document(parent_attributes),
+                        _ => StemNotation::Asciimath,
+                    };
+                }
+                Rule::stem_text => content = pair.as_str().replace("\\]", "]"),
+                Rule::EOI | Rule::comment => {}
+                unknown => unreachable!("{unknown:?}"),
+            }
+        }
+        Self {
+            content,
+            notation,
+            location,
+        }
+    }
+}
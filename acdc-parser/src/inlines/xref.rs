@@ -0,0 +1,62 @@
+use pest::iterators::Pairs;
+
+use crate::{CrossReference, Location, Rule};
+
+impl CrossReference {
+    /// Parses a cross reference, in either its `<<target,text>>` or `xref:target[text]`
+    /// form.
+    pub(crate) fn parse_inline(pairs: Pairs<Rule>, location: Location) -> Self {
+        let mut target = String::new();
+        let mut text = None;
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::xref_target => target = pair.as_str().to_string(),
+                Rule::xref_text => text = Some(pair.as_str().trim().to_string()),
+                Rule::EOI | Rule::comment => {}
+                unknown => unreachable!("{unknown:?}"),
+            }
+        }
+        Self {
+            target,
+            text,
+            location,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Block, CrossReference, InlineMacro, InlineNode};
+
+    fn xrefs(input: &str) -> Vec<CrossReference> {
+        let doc = parse(input).unwrap();
+        let Block::Paragraph(paragraph) = &doc.blocks[0] else {
+            panic!("expected a paragraph, got {:?}", doc.blocks[0]);
+        };
+        paragraph
+            .content
+            .iter()
+            .filter_map(|inline| match inline {
+                InlineNode::Macro(InlineMacro::CrossReference(xref)) => Some(xref.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_xref_forms() {
+        let found = xrefs("See <<intro>>, <<intro, the intro>> and xref:setup[Setup].\n");
+        let found = found
+            .iter()
+            .map(|xref| (xref.target.as_str(), xref.text.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                ("intro", None),
+                ("intro", Some("the intro")),
+                ("setup", Some("Setup"))
+            ]
+        );
+    }
+}
@@ -43,21 +43,26 @@ mod inline_preprocessor;
 mod inlines;
 mod model;
 mod preprocessor;
+mod timings;
 
 pub(crate) use inline_preprocessor::{InlinePreprocessor, ProcessedContent};
 use preprocessor::Preprocessor;
 
+pub use document::{validate_project, walk_blocks};
 pub use error::{Detail as ErrorDetail, Error};
 pub use model::{
     Admonition, AdmonitionVariant, Anchor, AttributeName, AttributeValue, Audio, AudioSource,
-    Author, Autolink, Block, BlockMetadata, Bold, Button, DelimitedBlock, DelimitedBlockType,
-    DescriptionList, DescriptionListDescription, DescriptionListItem, DiscreteHeader, Document,
-    DocumentAttribute, DocumentAttributes, ElementAttributes, Header, Highlight, Icon, Image,
-    ImageSource, InlineMacro, InlineNode, Italic, Keyboard, LineBreak, Link, ListItem, Location,
-    Menu, Monospace, OrderedList, PageBreak, Paragraph, Pass, PassthroughKind, Plain, Position,
-    Raw, Role, Section, Subscript, Substitution, Superscript, Table, TableColumn, TableOfContents,
-    TableRow, ThematicBreak, UnorderedList, Url, Video, VideoSource,
+    Author, Autolink, Block, BlockMetadata, Bold, Button, CalloutList, ColumnFormat, ColumnStyle,
+    ColumnWidth, CrossReference, DelimitedBlock, DelimitedBlockType, DescriptionList,
+    DescriptionListDescription, DescriptionListItem, Diagnostic, DiscreteHeader, Document,
+    DocumentAttribute, DocumentAttributes, ElementAttributes, Footnote, Header, Highlight,
+    HorizontalAlignment, Icon, Image, ImageSource, InlineMacro, InlineNode, Italic, Keyboard,
+    LineBreak, Link, ListItem, Location, Menu, Monospace, OrderedList, PageBreak, Paragraph, Pass,
+    PassthroughKind, Plain, Position, Raw, Role, Section, SectionRef, Severity, Stem, StemNotation,
+    Subscript, Substitution, Superscript, Table, TableColumn, TableOfContents, TableRow,
+    ThematicBreak, TocEntry, UnorderedList, Url, VerticalAlignment, Video, VideoSource,
 };
+pub use timings::ParseTimings;
 
 #[derive(Parser, Debug)]
 #[grammar = "../grammar/inlines.pest"]
@@ -88,7 +93,7 @@ pub(crate) struct InnerPestParser;
 #[instrument(skip(reader))]
 pub fn parse_from_reader<R: std::io::Read>(reader: R) -> Result<Document, Error> {
     let input = Preprocessor.process_reader(reader)?;
-    parse_input(input)
+    parse_input(input, &mut ParseTimings::default())
 }
 
 /// Parse `AsciiDoc` content from a string.
@@ -108,8 +113,29 @@ pub fn parse_from_reader<R: std::io::Read>(reader: R) -> Result<Document, Error>
 /// This function returns an error if the content cannot be parsed.
 #[instrument]
 pub fn parse(input: &str) -> Result<Document, Error> {
-    let input = Preprocessor.process(input)?;
-    parse_input(input)
+    parse_with_timings(input).map(|(document, _)| document)
+}
+
+/// Parse `AsciiDoc` content from a string, like [`parse`], along with how long each
+/// phase of parsing it took.
+///
+/// # Example
+///
+/// ```
+/// use acdc_parser::parse_with_timings;
+///
+/// let (document, timings) = parse_with_timings("= Document Title\n\nA paragraph.").unwrap();
+/// println!("parsed in {:?}", timings.total());
+/// ```
+///
+/// # Errors
+/// This function returns an error if the content cannot be parsed.
+#[instrument]
+pub fn parse_with_timings(input: &str) -> Result<(Document, ParseTimings), Error> {
+    let mut timings = ParseTimings::default();
+    let input = timings::measure(&mut timings.preprocess, || Preprocessor.process(input))?;
+    let document = parse_input(input, &mut timings)?;
+    Ok((document, timings))
 }
 
 /// Parse `AsciiDoc` content from a file.
@@ -130,16 +156,35 @@ pub fn parse(input: &str) -> Result<Document, Error> {
 /// This function returns an error if the content cannot be parsed.
 #[instrument(skip(file_path))]
 pub fn parse_file<P: AsRef<Path>>(file_path: P) -> Result<Document, Error> {
-    let input = Preprocessor.process_file(file_path)?;
-    parse_input(input)
+    parse_file_with_timings(file_path).map(|(document, _)| document)
 }
 
-#[instrument]
-fn parse_input(input: String) -> Result<Document, Error> {
+/// Parse `AsciiDoc` content from a file, like [`parse_file`], along with how long each
+/// phase of parsing it took.
+///
+/// # Errors
+/// This function returns an error if the file cannot be read or its content cannot be
+/// parsed.
+#[instrument(skip(file_path))]
+pub fn parse_file_with_timings<P: AsRef<Path>>(
+    file_path: P,
+) -> Result<(Document, ParseTimings), Error> {
+    let mut timings = ParseTimings::default();
+    let input = timings::measure(&mut timings.preprocess, || {
+        Preprocessor.process_file(file_path)
+    })?;
+    let document = parse_input(input, &mut timings)?;
+    Ok((document, timings))
+}
+
+#[instrument(skip(timings))]
+fn parse_input(input: String, timings: &mut ParseTimings) -> Result<Document, Error> {
     tracing::trace!(?input, "post preprocessor");
+    let start = std::time::Instant::now();
     let pairs = InnerPestParser::parse(Rule::document, &input);
+    timings.grammar += start.elapsed();
     match pairs {
-        Ok(pairs) => Document::parse(pairs),
+        Ok(pairs) => Document::parse(pairs, timings),
         Err(e) => {
             tracing::error!("error parsing document content: {e}");
             Err(Error::Parse(e.to_string()))
@@ -178,6 +223,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_with_timings() {
+        let content = "= Document\n\n== Section\n\nA paragraph of {wordcount} words.\n";
+        let (document, timings) = parse_with_timings(content).unwrap();
+        assert_eq!(document, parse(content).unwrap());
+        assert!(!timings.grammar.is_zero());
+        assert!(!timings.blocks.is_zero());
+        assert!(!timings.word_count.is_zero());
+        assert_eq!(timings.phases().count(), 8);
+        assert_eq!(
+            timings.total(),
+            timings.preprocess
+                + timings.grammar
+                + timings.blocks
+                + timings.section_tree
+                + timings.word_count
+                + timings.section_ids
+                + timings.footnotes
+                + timings.toc
+        );
+    }
+
     //     #[test]
     //     #[tracing_test::traced_test]
     //     fn test_something() {
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{ElementAttributes, Location, Substitution};
+use crate::{AttributeValue, DocumentAttributes, ElementAttributes, Location, Substitution};
 
 /// A `Pass` represents a passthrough macro in a document.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -83,6 +83,74 @@ pub struct Keyboard {
 // TODO(nlopes): this could perhaps be an enum instead with the allowed keys
 pub type Key = String;
 
+/// A `Stem` represents an inline STEM (math) expression in a document.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stem {
+    pub content: String,
+    pub notation: StemNotation,
+    pub location: Location,
+}
+
+/// The notation a `Stem` expression is written in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StemNotation {
+    Asciimath,
+    Latexmath,
+}
+
+impl StemNotation {
+    /// Returns the notation `stem` expressions and blocks default to, as set by the
+    /// `stem` document attribute: latexmath for `latexmath` (or its `latex` and `tex`
+    /// aliases) and asciimath otherwise.
+    #[must_use]
+    pub fn from_document(attributes: &DocumentAttributes) -> Self {
+        match attributes.get("stem") {
+            Some(AttributeValue::String(value))
+                if matches!(value.as_str(), "latexmath" | "latex" | "tex") =>
+            {
+                StemNotation::Latexmath
+            }
+            _ => StemNotation::Asciimath,
+        }
+    }
+}
+
+impl std::fmt::Display for StemNotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StemNotation::Asciimath => write!(f, "asciimath"),
+            StemNotation::Latexmath => write!(f, "latexmath"),
+        }
+    }
+}
+
+/// A `CrossReference` represents an inline reference to an anchor, written as
+/// `<<target,text>>` or `xref:target[text]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CrossReference {
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub location: Location,
+}
+
+/// A `Footnote` represents an inline footnote, written as `footnote:[text]`, or as
+/// `footnote:name[text]` for one that is referred to again later with `footnote:name[]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Footnote {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// The number of the footnote, counting from 1 in document order. Every reference to
+    /// a named footnote has its number, and it's 0 for a reference to a name that isn't
+    /// defined.
+    #[serde(default)]
+    pub number: usize,
+    pub location: Location,
+}
+
 /// An `Autolink` represents an inline autolink in a document.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Autolink {
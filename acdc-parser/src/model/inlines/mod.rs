@@ -10,7 +10,7 @@ mod text;
 pub use macros::*;
 pub use text::*;
 
-use crate::{BlockMetadata, ElementAttributes, Image, ImageSource, Location};
+use crate::{Anchor, BlockMetadata, ElementAttributes, Image, ImageSource, Location};
 
 /// An `InlineNode` represents an inline node in a document.
 ///
@@ -28,6 +28,9 @@ pub enum InlineNode {
     SubscriptText(Subscript),
     SuperscriptText(Superscript),
     LineBreak(LineBreak),
+    /// An anchor placed in the text (`[[id]]` or `[[id,label]]`), which cross references
+    /// can link to.
+    InlineAnchor(Anchor),
     Macro(InlineMacro),
 
     // Internal use only - DO NOT USE unless you're inside the parser
@@ -47,6 +50,7 @@ impl InlineNode {
             InlineNode::SubscriptText(subscript) => subscript.location.clone(),
             InlineNode::SuperscriptText(superscript) => superscript.location.clone(),
             InlineNode::LineBreak(line_break) => line_break.location.clone(),
+            InlineNode::InlineAnchor(anchor) => anchor.location.clone(),
             InlineNode::Macro(macro_node) => match macro_node {
                 InlineMacro::Icon(icon) => icon.location.clone(),
                 InlineMacro::Image(image) => image.location.clone(),
@@ -57,6 +61,9 @@ impl InlineNode {
                 InlineMacro::Link(link) => link.location.clone(),
                 InlineMacro::Autolink(autolink) => autolink.location.clone(),
                 InlineMacro::Pass(pass) => pass.location.clone(),
+                InlineMacro::Stem(stem) => stem.location.clone(),
+                InlineMacro::CrossReference(xref) => xref.location.clone(),
+                InlineMacro::Footnote(footnote) => footnote.location.clone(),
             },
             InlineNode::_PlaceholderContent(placeholder) => placeholder.location.clone(),
         }
@@ -82,9 +89,13 @@ pub enum InlineMacro {
     Link(Link),
     Autolink(Autolink),
     Pass(Pass),
+    Stem(Stem),
+    CrossReference(CrossReference),
+    Footnote(Footnote),
 }
 
 impl Serialize for InlineNode {
+    #[allow(clippy::too_many_lines)]
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -112,6 +123,9 @@ impl Serialize for InlineNode {
                 if let Some(role) = &highlight.role {
                     map.serialize_entry("role", role)?;
                 }
+                if let Some(id) = &highlight.id {
+                    map.serialize_entry("id", id)?;
+                }
                 map.serialize_entry("inlines", &highlight.content)?;
                 map.serialize_entry("location", &highlight.location)?;
             }
@@ -123,6 +137,9 @@ impl Serialize for InlineNode {
                 if let Some(role) = &italic.role {
                     map.serialize_entry("role", role)?;
                 }
+                if let Some(id) = &italic.id {
+                    map.serialize_entry("id", id)?;
+                }
                 map.serialize_entry("inlines", &italic.content)?;
                 map.serialize_entry("location", &italic.location)?;
             }
@@ -134,6 +151,9 @@ impl Serialize for InlineNode {
                 if let Some(role) = &bold.role {
                     map.serialize_entry("role", role)?;
                 }
+                if let Some(id) = &bold.id {
+                    map.serialize_entry("id", id)?;
+                }
                 map.serialize_entry("inlines", &bold.content)?;
                 map.serialize_entry("location", &bold.location)?;
             }
@@ -144,6 +164,9 @@ impl Serialize for InlineNode {
                 if let Some(role) = &monospace.role {
                     map.serialize_entry("role", role)?;
                 }
+                if let Some(id) = &monospace.id {
+                    map.serialize_entry("id", id)?;
+                }
                 map.serialize_entry("inlines", &monospace.content)?;
                 map.serialize_entry("location", &monospace.location)?;
             }
@@ -155,6 +178,9 @@ impl Serialize for InlineNode {
                 if let Some(role) = &subscript.role {
                     map.serialize_entry("role", role)?;
                 }
+                if let Some(id) = &subscript.id {
+                    map.serialize_entry("id", id)?;
+                }
                 map.serialize_entry("inlines", &subscript.content)?;
                 map.serialize_entry("location", &subscript.location)?;
             }
@@ -166,6 +192,9 @@ impl Serialize for InlineNode {
                 if let Some(role) = &superscript.role {
                     map.serialize_entry("role", role)?;
                 }
+                if let Some(id) = &superscript.id {
+                    map.serialize_entry("id", id)?;
+                }
                 map.serialize_entry("inlines", &superscript.content)?;
                 map.serialize_entry("location", &superscript.location)?;
             }
@@ -174,6 +203,15 @@ impl Serialize for InlineNode {
                 map.serialize_entry("type", "inline")?;
                 map.serialize_entry("location", &line_break.location)?;
             }
+            InlineNode::InlineAnchor(anchor) => {
+                map.serialize_entry("name", "anchor")?;
+                map.serialize_entry("type", "inline")?;
+                map.serialize_entry("id", &anchor.id)?;
+                if let Some(xreflabel) = &anchor.xreflabel {
+                    map.serialize_entry("xreflabel", xreflabel)?;
+                }
+                map.serialize_entry("location", &anchor.location)?;
+            }
             InlineNode::Macro(macro_node) => {
                 serialize_inline_macro::<S>(macro_node, &mut map)?;
             }
@@ -247,6 +285,34 @@ where
             map.serialize_entry("target", &autolink.url)?;
             map.serialize_entry("location", &autolink.location)?;
         }
+        InlineMacro::Stem(stem) => {
+            map.serialize_entry("name", "stem")?;
+            map.serialize_entry("type", "inline")?;
+            map.serialize_entry("variant", &stem.notation)?;
+            map.serialize_entry("value", &stem.content)?;
+            map.serialize_entry("location", &stem.location)?;
+        }
+        InlineMacro::CrossReference(xref) => {
+            map.serialize_entry("name", "ref")?;
+            map.serialize_entry("type", "inline")?;
+            map.serialize_entry("variant", "xref")?;
+            map.serialize_entry("target", &xref.target)?;
+            if let Some(text) = &xref.text {
+                map.serialize_entry("value", text)?;
+            }
+            map.serialize_entry("location", &xref.location)?;
+        }
+        InlineMacro::Footnote(footnote) => {
+            map.serialize_entry("name", "footnote")?;
+            map.serialize_entry("type", "inline")?;
+            if let Some(id) = &footnote.id {
+                map.serialize_entry("id", id)?;
+            }
+            if let Some(text) = &footnote.text {
+                map.serialize_entry("value", text)?;
+            }
+            map.serialize_entry("location", &footnote.location)?;
+        }
         InlineMacro::Pass(_) => {
             unimplemented!("passthrough serialization is not implemented because we only serialize to ASG what should be visible to the user")
         }
@@ -281,6 +347,8 @@ impl<'de> Deserialize<'de> for InlineNode {
                 let mut my_inlines = None;
                 let mut my_title = None;
                 let mut my_target = None;
+                let mut my_id = None;
+                let mut my_xreflabel = None;
 
                 // TODO(nlopes): need to deserialize the attributes!
                 while let Some(key) = map.next_key::<String>()? {
@@ -327,6 +395,18 @@ impl<'de> Deserialize<'de> for InlineNode {
                             }
                             my_target = Some(map.next_value()?);
                         }
+                        "id" => {
+                            if my_id.is_some() {
+                                return Err(de::Error::duplicate_field("id"));
+                            }
+                            my_id = Some(map.next_value::<String>()?);
+                        }
+                        "xreflabel" => {
+                            if my_xreflabel.is_some() {
+                                return Err(de::Error::duplicate_field("xreflabel"));
+                            }
+                            my_xreflabel = Some(map.next_value::<String>()?);
+                        }
                         "inlines" => {
                             if my_inlines.is_some() {
                                 return Err(de::Error::duplicate_field("inlines"));
@@ -385,11 +465,37 @@ impl<'de> Deserialize<'de> for InlineNode {
                             location: my_location,
                         }))))
                     }
+                    ("anchor", "inline") => {
+                        let my_id = my_id.ok_or_else(|| de::Error::missing_field("id"))?;
+                        Ok(InlineNode::InlineAnchor(Anchor {
+                            id: my_id,
+                            xreflabel: my_xreflabel,
+                            location: my_location,
+                        }))
+                    }
                     ("keyboard", "inline") => todo!("implement keyboard deserialization"),
                     ("btn" | "button", "inline") => {
                         todo!("implement button deserialization")
                     }
                     ("menu", "inline") => todo!("implement menu deserialization"),
+                    ("stem", "inline") => {
+                        let my_value = my_value.ok_or_else(|| de::Error::missing_field("value"))?;
+                        let my_variant =
+                            my_variant.ok_or_else(|| de::Error::missing_field("variant"))?;
+                        let notation = match my_variant.as_str() {
+                            "asciimath" => StemNotation::Asciimath,
+                            "latexmath" => StemNotation::Latexmath,
+                            _ => {
+                                tracing::error!(variant = %my_variant, "invalid stem notation");
+                                return Err(de::Error::custom("invalid stem notation"));
+                            }
+                        };
+                        Ok(InlineNode::Macro(InlineMacro::Stem(Stem {
+                            content: my_value,
+                            notation,
+                            location: my_location,
+                        })))
+                    }
                     ("ref", "inline") => {
                         let my_variant =
                             my_variant.ok_or_else(|| de::Error::missing_field("variant"))?;
@@ -411,6 +517,13 @@ impl<'de> Deserialize<'de> for InlineNode {
                                 target: my_target,
                                 location: my_location,
                             }))),
+                            "xref" => Ok(InlineNode::Macro(InlineMacro::CrossReference(
+                                CrossReference {
+                                    target: my_target,
+                                    text: my_value,
+                                    location: my_location,
+                                },
+                            ))),
 
                             "autolink" => todo!("implement autolink deserialization"),
                             "pass" => todo!("implement pass deserialization"),
@@ -428,31 +541,37 @@ impl<'de> Deserialize<'de> for InlineNode {
                         match my_variant.as_str() {
                             "strong" => Ok(InlineNode::BoldText(Bold {
                                 role: None,
+                                id: None,
                                 content: my_inlines,
                                 location: my_location,
                             })),
                             "emphasis" => Ok(InlineNode::ItalicText(Italic {
                                 role: None,
+                                id: None,
                                 content: my_inlines,
                                 location: my_location,
                             })),
                             "code" => Ok(InlineNode::MonospaceText(Monospace {
                                 role: None,
+                                id: None,
                                 content: my_inlines,
                                 location: my_location,
                             })),
                             "mark" => Ok(InlineNode::HighlightText(Highlight {
                                 role: None,
+                                id: None,
                                 content: my_inlines,
                                 location: my_location,
                             })),
                             "subscript" => Ok(InlineNode::SubscriptText(Subscript {
                                 role: None,
+                                id: None,
                                 content: my_inlines,
                                 location: my_location,
                             })),
                             "superscript" => Ok(InlineNode::SuperscriptText(Superscript {
                                 role: None,
+                                id: None,
                                 content: my_inlines,
                                 location: my_location,
                             })),
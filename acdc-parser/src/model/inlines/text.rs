@@ -12,6 +12,8 @@ use super::InlineNode;
 pub struct Subscript {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub role: Option<Role>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "inlines")]
     pub content: Vec<InlineNode>,
     pub location: Location,
@@ -22,6 +24,8 @@ pub struct Subscript {
 pub struct Superscript {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub role: Option<Role>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "inlines")]
     pub content: Vec<InlineNode>,
     pub location: Location,
@@ -32,6 +36,8 @@ pub struct Superscript {
 pub struct Monospace {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub role: Option<Role>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "inlines")]
     pub content: Vec<InlineNode>,
     pub location: Location,
@@ -42,6 +48,8 @@ pub struct Monospace {
 pub struct Highlight {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub role: Option<Role>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "inlines")]
     pub content: Vec<InlineNode>,
     pub location: Location,
@@ -52,6 +60,8 @@ pub struct Highlight {
 pub struct Bold {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub role: Option<Role>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "inlines")]
     pub content: Vec<InlineNode>,
     pub location: Location,
@@ -62,6 +72,8 @@ pub struct Bold {
 pub struct Italic {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub role: Option<Role>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "inlines")]
     pub content: Vec<InlineNode>,
     pub location: Location,
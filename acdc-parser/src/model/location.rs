@@ -29,6 +29,12 @@ impl Location {
         location
     }
 
+    /// The offset of the start of the location from the start of the document, in bytes.
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        self.absolute_start
+    }
+
     pub fn set_start_from_pos(&mut self, start: &pest::Position) {
         let (line, column) = start.line_col();
         self.absolute_start = start.pos();
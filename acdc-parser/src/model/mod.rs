@@ -10,6 +10,7 @@ use serde::{
 use crate::Error;
 
 mod attributes;
+mod diagnostic;
 mod inlines;
 mod location;
 mod substitution;
@@ -17,6 +18,7 @@ mod substitution;
 pub use attributes::{
     AttributeName, AttributeValue, Document as DocumentAttributes, Element as ElementAttributes,
 };
+pub use diagnostic::{Diagnostic, Severity};
 pub use inlines::*;
 pub use location::*;
 pub use substitution::*;
@@ -33,6 +35,85 @@ pub struct Document {
     #[serde(default)]
     pub blocks: Vec<Block>,
     pub location: Location,
+    #[serde(skip)]
+    pub(crate) toc_entries: Vec<TocEntry>,
+    #[serde(skip)]
+    pub(crate) footnotes: Vec<Footnote>,
+    #[serde(skip)]
+    pub(crate) header_attributes: DocumentAttributes,
+}
+
+impl Document {
+    /// Returns the flat list of table of contents entries, in document order.
+    ///
+    /// Every section in the document gets an entry, regardless of `toclevels`, so that
+    /// consumers can decide for themselves how deep they want to go. The entries
+    /// returned here never have any `children`, see [`Document::toc_tree`] for that.
+    #[must_use]
+    pub fn toc(&self) -> &[TocEntry] {
+        &self.toc_entries
+    }
+
+    /// Returns the footnotes of the document, in the order they're numbered.
+    ///
+    /// Each footnote is listed once, where it first appears: references to a named
+    /// footnote share its [`Footnote::number`] but aren't listed again.
+    #[must_use]
+    pub fn footnotes(&self) -> &[Footnote] {
+        &self.footnotes
+    }
+
+    /// Returns the document attributes as they were at the end of the header.
+    ///
+    /// Unlike [`Document::attributes`], which holds the value each attribute ended up with,
+    /// this leaves out attribute entries in the body. Those show up as
+    /// [`Block::DocumentAttribute`] blocks, so a consumer walking the blocks in order can
+    /// tell which value applies at any point in the document.
+    #[must_use]
+    pub fn header_attributes(&self) -> &DocumentAttributes {
+        &self.header_attributes
+    }
+
+    /// Returns the table of contents entries nested by section level.
+    #[must_use]
+    pub fn toc_tree(&self) -> Vec<TocEntry> {
+        let mut roots: Vec<TocEntry> = Vec::new();
+        for entry in &self.toc_entries {
+            insert_toc_entry(&mut roots, entry.clone());
+        }
+        roots
+    }
+}
+
+fn insert_toc_entry(siblings: &mut Vec<TocEntry>, entry: TocEntry) {
+    if let Some(last) = siblings.last_mut() {
+        if entry.level > last.level {
+            insert_toc_entry(&mut last.children, entry);
+            return;
+        }
+    }
+    siblings.push(entry);
+}
+
+/// A `TocEntry` represents a section as it appears in the table of contents.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TocEntry {
+    pub id: String,
+    pub title: Vec<InlineNode>,
+    pub level: SectionLevel,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TocEntry>,
+}
+
+/// A `SectionRef` describes a section of a document, as listed by
+/// [`Document::sections`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SectionRef {
+    pub id: String,
+    pub level: SectionLevel,
+    /// The title as plain text, without any formatting.
+    pub title: String,
+    pub location: Location,
 }
 
 type Subtitle = String;
@@ -82,30 +163,6 @@ pub struct Anchor {
 
 pub type Role = String;
 
-// TODO: we could and probably should just use a `AttributeValue` instead
-#[derive(Clone, Debug, Default, PartialEq, Serialize)]
-pub struct OptionalAttributeValue(
-    #[serde(default, skip_serializing_if = "Option::is_none")] pub Option<String>,
-);
-
-impl<'de> Deserialize<'de> for OptionalAttributeValue {
-    fn deserialize<D>(deserializer: D) -> Result<OptionalAttributeValue, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        match Option::<String>::deserialize(deserializer)? {
-            Some(value) => {
-                if value.as_str() == "null" {
-                    Ok(OptionalAttributeValue(None))
-                } else {
-                    Ok(OptionalAttributeValue(Some(value)))
-                }
-            }
-            None => Ok(OptionalAttributeValue(None)),
-        }
-    }
-}
-
 /// A `BlockMetadata` represents the metadata of a block in a document.
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct BlockMetadata {
@@ -120,6 +177,10 @@ pub struct BlockMetadata {
     pub id: Option<Anchor>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub anchors: Vec<Anchor>,
+    /// The substitutions applied to the content of the block, when its `subs` attribute
+    /// overrides the ones it gets by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub substitutions: Option<Vec<Substitution>>,
 }
 
 impl BlockMetadata {
@@ -135,6 +196,7 @@ impl BlockMetadata {
             && self.id.is_none()
             && self.anchors.is_empty()
             && self.attributes.is_empty()
+            && self.substitutions.is_none()
     }
 }
 
@@ -153,6 +215,7 @@ pub enum Block {
     PageBreak(PageBreak),
     UnorderedList(UnorderedList),
     OrderedList(OrderedList),
+    CalloutList(CalloutList),
     DescriptionList(DescriptionList),
     Section(Section),
     DelimitedBlock(DelimitedBlock),
@@ -289,6 +352,28 @@ pub enum ImageSource {
     Url(String),
 }
 
+impl Image {
+    /// Whether the image is marked as purely decorative with `role=decorative`, and so
+    /// doesn't need alt text.
+    #[must_use]
+    pub fn is_decorative(&self) -> bool {
+        self.metadata.roles.iter().any(|role| role == "decorative")
+            || matches!(
+                self.metadata.attributes.get("role"),
+                Some(AttributeValue::String(role)) if role == "decorative"
+            )
+    }
+}
+
+impl std::fmt::Display for ImageSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageSource::Path(path) => write!(f, "{path}"),
+            ImageSource::Url(url) => write!(f, "{url}"),
+        }
+    }
+}
+
 /// A `DescriptionList` represents a description list in a document.
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct DescriptionList {
@@ -302,11 +387,14 @@ pub struct DescriptionList {
 }
 
 /// A `DescriptionListItem` represents a description list item in a document.
+///
+/// An item has several terms when they're written one after the other and share the
+/// description of the last one.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DescriptionListItem {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub anchors: Vec<Anchor>,
-    pub term: String,
+    pub terms: Vec<Vec<InlineNode>>,
     pub delimiter: String,
     pub description: DescriptionListDescription,
     pub location: Location,
@@ -314,7 +402,7 @@ pub struct DescriptionListItem {
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DescriptionListDescription {
-    Inline(String),
+    Inline(Vec<InlineNode>),
     Blocks(Vec<Block>),
 }
 
@@ -331,12 +419,30 @@ pub struct UnorderedList {
 /// An `OrderedList` represents an ordered list in a document.
 #[derive(Clone, Debug, PartialEq)]
 pub struct OrderedList {
+    pub title: Vec<InlineNode>,
+    pub metadata: BlockMetadata,
+    pub items: Vec<ListItem>,
+    pub marker: String,
+    /// The number of the first item, from the `start` attribute.
+    pub start: Option<i32>,
+    /// Whether the list counts down, from the `%reversed` option.
+    pub reversed: bool,
+    pub location: Location,
+}
+
+/// A `CalloutList` represents a callout list in a document.
+///
+/// Each item explains the callout (e.g. `<1>`) with the same number in the preceding
+/// verbatim block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalloutList {
     pub title: Vec<InlineNode>,
     pub metadata: BlockMetadata,
     pub items: Vec<ListItem>,
     pub marker: String,
     pub location: Location,
 }
+
 pub type ListLevel = u8;
 
 /// A `ListItem` represents a list item in a document.
@@ -359,6 +465,15 @@ pub struct Paragraph {
     pub location: Location,
 }
 
+fn single_span() -> usize {
+    1
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_single_span(span: &usize) -> bool {
+    *span == 1
+}
+
 fn is_default_metadata(metadata: &BlockMetadata) -> bool {
     metadata.is_default()
 }
@@ -467,9 +582,92 @@ pub struct Table {
     pub header: Option<TableRow>,
     pub footer: Option<TableRow>,
     pub rows: Vec<TableRow>,
+    /// The format of each column, from the `cols` attribute, empty when there isn't one.
+    /// Column formats aren't part of the ASG, so this isn't serialized.
+    #[serde(skip)]
+    pub columns: Vec<ColumnFormat>,
     pub location: Location,
 }
 
+/// A `ColumnFormat` is the format of a table column, from its entry in the `cols`
+/// attribute (e.g. `>.^2e`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnFormat {
+    pub width: ColumnWidth,
+    pub halign: HorizontalAlignment,
+    pub valign: VerticalAlignment,
+    pub style: ColumnStyle,
+}
+
+/// The width of a table column.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnWidth {
+    /// A share of the table width, relative to the other columns (`2`).
+    Proportional(usize),
+    /// A percentage of the table width (`25%`).
+    Percentage(usize),
+    /// As wide as the content needs (`~`).
+    Auto,
+}
+
+impl Default for ColumnWidth {
+    fn default() -> Self {
+        Self::Proportional(1)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HorizontalAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerticalAlignment {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// The style of the cells of a table column, which the cells can override with their
+/// own specifier.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnStyle {
+    /// `AsciiDoc` content, parsed as blocks (`a`).
+    AsciiDoc,
+    #[default]
+    Default,
+    Emphasis,
+    Header,
+    Literal,
+    Monospace,
+    Strong,
+}
+
+impl ColumnStyle {
+    /// The letter naming this style in a column or cell specifier, or `None` for the
+    /// default style.
+    #[must_use]
+    pub fn letter(self) -> Option<&'static str> {
+        match self {
+            Self::AsciiDoc => Some("a"),
+            Self::Default => None,
+            Self::Emphasis => Some("e"),
+            Self::Header => Some("h"),
+            Self::Literal => Some("l"),
+            Self::Monospace => Some("m"),
+            Self::Strong => Some("s"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TableRow {
     pub columns: Vec<TableColumn>,
@@ -479,6 +677,15 @@ pub struct TableRow {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TableColumn {
     pub content: Vec<Block>,
+    /// The cell style from the cell specifier (e.g. `a` for `a|`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// The number of columns this cell spans.
+    #[serde(default = "single_span", skip_serializing_if = "is_single_span")]
+    pub colspan: usize,
+    /// The number of rows this cell spans.
+    #[serde(default = "single_span", skip_serializing_if = "is_single_span")]
+    pub rowspan: usize,
     //pub location: Location,
 }
 
@@ -490,6 +697,10 @@ pub struct Section {
     pub level: SectionLevel,
     pub content: Vec<Block>,
     pub location: Location,
+    /// The id generated from the title when the section doesn't have one of its own,
+    /// unique in the document. Generated ids aren't part of the ASG, so this isn't
+    /// serialized.
+    pub generated_id: Option<String>,
 }
 
 impl Serialize for Document {
@@ -613,6 +824,28 @@ impl Serialize for OrderedList {
     }
 }
 
+impl Serialize for CalloutList {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(None)?;
+        state.serialize_entry("name", "list")?;
+        state.serialize_entry("type", "block")?;
+        state.serialize_entry("variant", "callout")?;
+        state.serialize_entry("marker", &self.marker)?;
+        if !self.title.is_empty() {
+            state.serialize_entry("title", &self.title)?;
+        }
+        if !is_default_metadata(&self.metadata) {
+            state.serialize_entry("metadata", &self.metadata)?;
+        }
+        state.serialize_entry("items", &self.items)?;
+        state.serialize_entry("location", &self.location)?;
+        state.end()
+    }
+}
+
 impl Serialize for ListItem {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -848,6 +1081,7 @@ impl<'de> Deserialize<'de> for Block {
                             level: my_level,
                             content: my_blocks,
                             location: my_location,
+                            generated_id: None,
                         }))
                     }
                     ("paragraph", "block") => {
@@ -1153,6 +1387,25 @@ impl<'de> Deserialize<'de> for Block {
                                 location: my_location,
                             })),
                             "ordered" => Ok(Block::OrderedList(OrderedList {
+                                title: my_title,
+                                start: OrderedList::start(&my_metadata.attributes),
+                                reversed: OrderedList::reversed(&my_metadata),
+                                metadata: my_metadata,
+                                marker: my_marker,
+                                items: match my_items
+                                    .ok_or_else(|| de::Error::missing_field("items"))?
+                                {
+                                    serde_json::Value::Array(a) => a
+                                        .into_iter()
+                                        .map(|v| {
+                                            serde_json::from_value(v).map_err(de::Error::custom)
+                                        })
+                                        .collect::<Result<Vec<ListItem>, _>>()?,
+                                    _ => return Err(de::Error::custom("items must be an array")),
+                                },
+                                location: my_location,
+                            })),
+                            "callout" => Ok(Block::CalloutList(CalloutList {
                                 title: my_title,
                                 metadata: my_metadata,
                                 marker: my_marker,
@@ -1169,7 +1422,6 @@ impl<'de> Deserialize<'de> for Block {
                                 },
                                 location: my_location,
                             })),
-                            "callout" => todo!("callout list"),
                             _ => Err(de::Error::custom(format!(
                                 "unexpected 'list' variant: {my_variant}",
                             ))),
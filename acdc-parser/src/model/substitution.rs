@@ -34,7 +34,6 @@ impl From<&str> for Substitution {
     }
 }
 
-pub const BASIC: &[Substitution] = &[Substitution::SpecialChars];
 pub const HEADER: &[Substitution] = &[Substitution::SpecialChars, Substitution::Attributes];
 pub const NORMAL: &[Substitution] = &[
     Substitution::SpecialChars,
@@ -44,13 +43,116 @@ pub const NORMAL: &[Substitution] = &[
     Substitution::Macros,
     Substitution::PostReplacements,
 ];
-pub const REFTEXT: &[Substitution] = &[
-    Substitution::SpecialChars,
-    Substitution::Quotes,
-    Substitution::Replacements,
-];
 pub const VERBATIM: &[Substitution] = &[Substitution::SpecialChars, Substitution::Callouts];
 
+/// Resolves the value of a `subs` attribute against the substitutions a block gets by
+/// default.
+///
+/// A plain list (`attributes,quotes`) replaces the defaults, while a list whose first
+/// entry is incremental starts from them: `+x` appends, `x+` prepends and `-x` removes.
+/// `normal` and `verbatim` expand to their groups and `none` to nothing.
+pub(crate) fn resolve_substitutions(value: &str, defaults: &[Substitution]) -> Vec<Substitution> {
+    let entries = value.split(',').map(str::trim).filter(|s| !s.is_empty());
+    let incremental = entries
+        .clone()
+        .next()
+        .is_some_and(|s| s.starts_with(['+', '-']) || s.ends_with('+'));
+    let mut substitutions = if incremental {
+        defaults.to_vec()
+    } else {
+        Vec::new()
+    };
+
+    for entry in entries {
+        let (name, op) = if let Some(name) = entry.strip_prefix('+') {
+            (name, '+')
+        } else if let Some(name) = entry.strip_prefix('-') {
+            (name, '-')
+        } else if let Some(name) = entry.strip_suffix('+') {
+            (name, '<')
+        } else {
+            (entry, '+')
+        };
+        let group = match name {
+            "none" => Vec::new(),
+            "normal" | "n" => NORMAL.to_vec(),
+            "verbatim" | "v" => VERBATIM.to_vec(),
+            "specialchars" | "c" | "attributes" | "a" | "replacements" | "r" | "macros" | "m"
+            | "post_replacements" | "p" | "quotes" | "q" | "callouts" => {
+                vec![Substitution::from(name)]
+            }
+            unknown => {
+                tracing::warn!(substitution = unknown, "unknown substitution, ignoring");
+                continue;
+            }
+        };
+        substitutions.retain(|s| !group.contains(s));
+        match op {
+            '+' => substitutions.extend(group),
+            '<' => {
+                substitutions.splice(0..0, group);
+            }
+            _ => {}
+        }
+    }
+    substitutions
+}
+
+/// How a reference to an attribute that isn't set is handled.
+///
+/// The `attribute-missing` document attribute picks it for attributes that were never
+/// set, while `attribute-undefined` picks it for the ones unset inline with
+/// `{set:name!}`, which can only be dropped along with the line or on their own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum AttributeMissing {
+    /// The reference is left as is.
+    #[default]
+    Skip,
+    /// The reference is removed.
+    Drop,
+    /// The line with the reference is removed.
+    DropLine,
+    /// The reference is left as is, and a warning is logged.
+    Warn,
+}
+
+impl AttributeMissing {
+    pub(crate) fn missing(attributes: &DocumentAttributes) -> Self {
+        match attributes.get("attribute-missing") {
+            Some(AttributeValue::String(value)) => match value.as_str() {
+                "skip" => Self::Skip,
+                "drop" => Self::Drop,
+                "drop-line" => Self::DropLine,
+                "warn" => Self::Warn,
+                unknown => {
+                    tracing::warn!(
+                        value = unknown,
+                        "invalid attribute-missing value, using 'skip'"
+                    );
+                    Self::Skip
+                }
+            },
+            _ => Self::Skip,
+        }
+    }
+
+    pub(crate) fn undefined(attributes: &DocumentAttributes) -> Self {
+        match attributes.get("attribute-undefined") {
+            Some(AttributeValue::String(value)) if value == "drop" => Self::Drop,
+            _ => Self::DropLine,
+        }
+    }
+}
+
+/// Whether `name` can be the name of an attribute, which tells an attribute reference
+/// apart from other text in braces.
+fn is_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 impl Substitute for &str {}
 impl Substitute for String {}
 
@@ -109,39 +211,58 @@ pub trait Substitute: ToString {
     /**
     Given a text and a set of attributes, resolve the attribute references in the text.
 
-    The attribute references are in the form of {name}.
+    The attribute references are in the form of {name}. References to attributes that
+    aren't set are handled as `attribute-missing` says.
      */
     #[must_use]
     fn substitute_attributes(text: &str, attributes: &DocumentAttributes) -> String {
+        let missing = AttributeMissing::missing(attributes);
         let mut result = String::with_capacity(text.len());
-        let mut i: usize = 0;
-
-        while i < text.len() {
-            if text[i..].starts_with('{') {
-                if let Some(end_brace) = text[i + 1..].find('}') {
-                    let attr_name = &text[i + 1..i + 1 + end_brace];
-                    match attributes.get(attr_name) {
-                        Some(AttributeValue::Bool(true)) => {
-                            result.push_str("");
-                        }
-                        Some(AttributeValue::String(attr_value)) => {
-                            result.push_str(attr_value);
+
+        for line in text.split_inclusive('\n') {
+            let line_start = result.len();
+            let mut rest = line;
+            let mut drop_line = false;
+            while let Some(start) = rest.find('{') {
+                result.push_str(&rest[..start]);
+                rest = &rest[start..];
+                let Some(end) = rest.find('}') else {
+                    break;
+                };
+                let reference = &rest[..=end];
+                let attr_name = &rest[1..end];
+                rest = &rest[end + 1..];
+                if !is_attribute_name(attr_name) {
+                    result.push_str(reference);
+                    continue;
+                }
+                match attributes.get(attr_name) {
+                    Some(AttributeValue::String(attr_value)) => result.push_str(attr_value),
+                    Some(AttributeValue::Bool(true)) => {}
+                    _ => match missing {
+                        AttributeMissing::Skip => result.push_str(reference),
+                        AttributeMissing::Warn => {
+                            tracing::warn!(
+                                name = attr_name,
+                                "skipping reference to missing attribute"
+                            );
+                            result.push_str(reference);
                         }
-                        _ => {
-                            // If the attribute is not found, we return the attribute reference as is.
-                            result.push('{');
-                            result.push_str(attr_name);
-                            result.push('}');
+                        AttributeMissing::Drop => {}
+                        AttributeMissing::DropLine => {
+                            drop_line = true;
+                            break;
                         }
-                    }
-                    i += end_brace + 2;
-                } else {
-                    result.push_str(&text[i..=i]);
-                    i += 1;
+                    },
+                }
+            }
+            if drop_line {
+                result.truncate(line_start);
+                if !line.ends_with('\n') && result.ends_with('\n') {
+                    result.pop();
                 }
             } else {
-                result.push_str(&text[i..=i]);
-                i += 1;
+                result.push_str(rest);
             }
         }
 
@@ -178,6 +299,45 @@ pub trait Substitute: ToString {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_resolve_substitutions() {
+        assert_eq!(
+            resolve_substitutions("attributes,quotes", VERBATIM),
+            vec![Substitution::Attributes, Substitution::Quotes]
+        );
+        assert_eq!(
+            resolve_substitutions("+attributes,+macros", VERBATIM),
+            vec![
+                Substitution::SpecialChars,
+                Substitution::Callouts,
+                Substitution::Attributes,
+                Substitution::Macros,
+            ]
+        );
+        assert_eq!(
+            resolve_substitutions("attributes+", VERBATIM),
+            vec![
+                Substitution::Attributes,
+                Substitution::SpecialChars,
+                Substitution::Callouts,
+            ]
+        );
+        assert_eq!(
+            resolve_substitutions("-callouts", VERBATIM),
+            vec![Substitution::SpecialChars]
+        );
+        assert_eq!(resolve_substitutions("normal", VERBATIM), NORMAL.to_vec());
+        assert_eq!(resolve_substitutions("none", VERBATIM), vec![]);
+        assert_eq!(
+            resolve_substitutions("verbatim,+quotes", NORMAL),
+            vec![
+                Substitution::SpecialChars,
+                Substitution::Callouts,
+                Substitution::Quotes,
+            ]
+        );
+    }
+
     #[test]
     fn test_resolve_attribute_references() {
         // These two are attributes we add to the attributes map.
@@ -207,4 +367,34 @@ mod tests {
         let resolved = value.substitute(HEADER, &attributes);
         assert_eq!(resolved, attribute_volume_repeat);
     }
+
+    #[test]
+    fn test_resolve_missing_attribute_references() {
+        let text = "first {missing} line\nsecond line";
+        let mut attributes = DocumentAttributes::default();
+
+        attributes.insert(
+            "attribute-missing".to_string(),
+            AttributeValue::String("drop".to_string()),
+        );
+        assert_eq!(
+            text.substitute(HEADER, &attributes),
+            "first  line\nsecond line"
+        );
+
+        attributes.insert(
+            "attribute-missing".to_string(),
+            AttributeValue::String("drop-line".to_string()),
+        );
+        assert_eq!(text.substitute(HEADER, &attributes), "second line");
+        assert_eq!(
+            "second line\nlast {missing}".substitute(HEADER, &attributes),
+            "second line"
+        );
+        // Braces around something that can't be an attribute name are just text.
+        assert_eq!(
+            "fn main() { x }".substitute(HEADER, &attributes),
+            "fn main() { x }"
+        );
+    }
 }
@@ -1,13 +1,95 @@
 //! The preprocessor module is responsible for processing the input document and expanding include directives.
 use std::path::Path;
 
-use crate::{error::Error, DocumentAttributes};
+use crate::{error::Error, AttributeValue, DocumentAttributes, ErrorDetail, Location, Position};
 
 use include::Include;
 
 #[derive(Debug, Default)]
 pub(crate) struct Preprocessor;
 
+/// How the `leveloffset` of an include directive or attribute entry changes the level of
+/// the sections that follow.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum LevelOffset {
+    /// `leveloffset=1` sets the offset, whatever it was before.
+    Absolute(isize),
+    /// `leveloffset=+1` and `leveloffset=-1` add to the current offset.
+    Relative(isize),
+}
+
+impl LevelOffset {
+    pub(crate) fn parse(value: &str) -> Result<Self, Error> {
+        let value = value.trim();
+        let offset = value
+            .parse()
+            .map_err(|_| Error::InvalidLevelOffset(value.to_string()))?;
+        if value.starts_with('+') || value.starts_with('-') {
+            Ok(LevelOffset::Relative(offset))
+        } else {
+            Ok(LevelOffset::Absolute(offset))
+        }
+    }
+
+    /// Returns the offset that results from applying this one on top of `current`.
+    pub(crate) fn resolve(self, current: isize) -> isize {
+        match self {
+            LevelOffset::Absolute(offset) => offset,
+            LevelOffset::Relative(offset) => current + offset,
+        }
+    }
+}
+
+/// Shifts the level of every section title in `lines` by `offset`, leaving those inside
+/// verbatim blocks (listing, literal, passthrough and comment) untouched.
+///
+/// Levels are clamped between the document title (`=`) and the deepest section level
+/// (`======`).
+fn shift_sections(lines: Vec<String>, offset: isize) -> Vec<String> {
+    if offset == 0 {
+        return lines;
+    }
+    let mut verbatim: Option<String> = None;
+    lines
+        .into_iter()
+        .map(|line| {
+            if let Some(delimiter) = &verbatim {
+                if line == *delimiter {
+                    verbatim = None;
+                }
+                return line;
+            }
+            if is_verbatim_delimiter(&line) {
+                verbatim = Some(line.clone());
+                return line;
+            }
+            shift_section(&line, offset).unwrap_or(line)
+        })
+        .collect()
+}
+
+fn is_verbatim_delimiter(line: &str) -> bool {
+    line.len() >= 4
+        && line.chars().next().is_some_and(|first| {
+            matches!(first, '-' | '.' | '+' | '/') && line.chars().all(|c| c == first)
+        })
+}
+
+fn shift_section(line: &str, offset: isize) -> Option<String> {
+    let marker = line.chars().next().filter(|c| *c == '=' || *c == '#')?;
+    let level = line.chars().take_while(|c| *c == marker).count();
+    if level > 6 || !line[level..].starts_with(' ') {
+        return None;
+    }
+    let level = isize::try_from(level).ok()?;
+    let shifted = usize::try_from((level + offset).clamp(1, 6)).ok()?;
+    Some(format!(
+        "{}{}",
+        marker.to_string().repeat(shifted),
+        &line[usize::try_from(level).ok()?..]
+    ))
+}
+
 mod include {
     use std::{
         path::{Path, PathBuf},
@@ -18,10 +100,11 @@ mod include {
     use pest_derive::Parser;
     use url::Url;
 
+    use super::LevelOffset;
     use crate::{
         error::Error,
         model::{Substitute, HEADER},
-        DocumentAttributes,
+        DocumentAttributes, ErrorDetail,
     };
 
     /**
@@ -45,8 +128,10 @@ mod include {
     #[derive(Debug)]
     pub(crate) struct Include {
         file_parent: PathBuf,
+        /// Line of the directive in the including document, for error reporting.
+        line_number: usize,
         target: Target,
-        level_offset: Option<isize>,
+        level_offset: Option<LevelOffset>,
         lines: Vec<LinesRange>,
         tags: Vec<String>,
         indent: Option<usize>,
@@ -54,6 +139,48 @@ mod include {
         opts: Vec<String>,
     }
 
+    enum TagDirective<'a> {
+        Start(&'a str),
+        End(&'a str),
+    }
+
+    /// Finds a `tag::name[]` or `end::name[]` marker in `line`, typically placed in a
+    /// comment of the included file.
+    fn tag_directive(line: &str) -> Option<TagDirective<'_>> {
+        for (prefix, is_end) in [("tag::", false), ("end::", true)] {
+            let Some(start) = line.find(prefix) else {
+                continue;
+            };
+            if line[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_')
+            {
+                continue;
+            }
+            let rest = &line[start + prefix.len()..];
+            let Some(end) = rest.find("[]") else {
+                continue;
+            };
+            let name = &rest[..end];
+            let after = &rest[end + 2..];
+            if name.is_empty()
+                || name.contains(char::is_whitespace)
+                || !(after.is_empty() || after.starts_with(' '))
+            {
+                continue;
+            }
+            return Some(if is_end {
+                TagDirective::End(name)
+            } else {
+                TagDirective::Start(name)
+            });
+        }
+        None
+    }
+
+    /// A line or range of lines picked by the `lines` attribute. Line numbers start at 1
+    /// and a negative (or missing) end means the end of the file.
     #[derive(Debug)]
     enum LinesRange {
         Single(usize),
@@ -73,7 +200,10 @@ mod include {
             if s.contains("..") {
                 let mut parts = s.split("..");
                 let start = parts.next().expect("no start").parse()?;
-                let end = parts.next().expect("no end").parse()?;
+                let end = match parts.next().expect("no end") {
+                    "" => -1,
+                    end => end.parse()?,
+                };
                 Ok(LinesRange::Range(start, end))
             } else {
                 Ok(LinesRange::Single(s.parse().map_err(|e| {
@@ -120,7 +250,7 @@ url_char = _{ path_char | "?" | "&" | "=" | "%" }
 attributes = _{ attribute_pair ~ ("," ~ attribute_pair)* }
 attribute_pair = _{ attribute_key ~ "=" ~ attribute_value }
 
-attribute_key = { "leveloffset" | "lines" | "tag" | "tags" | "indent" | "encoding" | "opts" }
+attribute_key = { "leveloffset" | "lines" | "tags" | "tag" | "indent" | "encoding" | "opts" }
 attribute_value = {
   ("\"" ~ (!("\"") ~ ANY)+ ~ "\"") |
   (!("," | "]") ~ ANY)+
@@ -138,13 +268,7 @@ attribute_value = {
                 value = &value[1..value.len() - 1];
             }
             match key {
-                "leveloffset" => {
-                    self.level_offset = Some(
-                        value
-                            .parse()
-                            .map_err(|_| Error::InvalidLevelOffset(value.to_string()))?,
-                    );
-                }
+                "leveloffset" => self.level_offset = Some(LevelOffset::parse(value)?),
                 "lines" => {
                     self.lines.extend(LinesRange::parse(value).map_err(|e| {
                         tracing::error!(?value, "failed to parse lines attribute: {:?}", e);
@@ -180,11 +304,13 @@ attribute_value = {
 
         pub(crate) fn parse(
             file_parent: &Path,
+            line_number: usize,
             line: &str,
             attributes: &DocumentAttributes,
         ) -> Result<Self, Error> {
             let mut include = Include {
                 file_parent: file_parent.to_path_buf(),
+                line_number,
                 target: Target::Path(PathBuf::new()),
                 level_offset: None,
                 lines: Vec::new(),
@@ -227,6 +353,144 @@ attribute_value = {
             Ok(include)
         }
 
+        /// Returns the offset applied to the sections of the included content, given the
+        /// one in effect where the directive is.
+        pub(crate) fn level_offset(&self, current: isize) -> isize {
+            self.level_offset
+                .map_or(current, |level_offset| level_offset.resolve(current))
+        }
+
+        /// Keeps the lines of the tagged regions picked by the `tag`/`tags` attributes,
+        /// dropping the `tag::name[]` and `end::name[]` marker lines.
+        ///
+        /// Besides tag names, the selectors can be `**` (every line), `*` (every line in a
+        /// tagged region) and a negation such as `!internal`; a line in nested regions is
+        /// kept or dropped based on the innermost one that the selectors say something
+        /// about. This follows the rules asciidoctor uses.
+        fn select_tagged(&self, content_lines: Vec<String>) -> Result<Vec<String>, Error> {
+            let mut selected_tags = Vec::new();
+            let mut all = None;
+            let mut wildcard = None;
+            for tag in &self.tags {
+                let (name, selected) = match tag.strip_prefix('!') {
+                    Some(name) => (name, false),
+                    None => (tag.as_str(), true),
+                };
+                match name {
+                    "**" => all = Some(selected),
+                    "*" => wildcard = Some(selected),
+                    _ => selected_tags.push((name, selected)),
+                }
+            }
+            // Whether lines outside of any tagged region are kept.
+            let base = match (all, wildcard) {
+                (Some(all), _) => {
+                    if wildcard.is_none()
+                        && !all
+                        && selected_tags
+                            .first()
+                            .is_some_and(|(_, selected)| !*selected)
+                    {
+                        wildcard = Some(true);
+                    }
+                    all
+                }
+                (None, Some(wildcard)) => {
+                    let wildcard_first = self
+                        .tags
+                        .first()
+                        .is_some_and(|tag| tag.trim_start_matches('!') == "*");
+                    wildcard_first && !wildcard
+                }
+                (None, None) => !selected_tags.iter().any(|(_, selected)| *selected),
+            };
+            let lookup = |name: &str| {
+                selected_tags
+                    .iter()
+                    .find(|(tag, _)| *tag == name)
+                    .map(|(_, selected)| *selected)
+            };
+
+            let mut lines = Vec::new();
+            let mut select = base;
+            // The tagged regions we're in: their name, whether their lines are kept and
+            // whether the selectors named them.
+            let mut stack: Vec<(String, bool, bool)> = Vec::new();
+            let mut found = Vec::new();
+            for line in content_lines {
+                match tag_directive(&line) {
+                    Some(TagDirective::End(name)) => {
+                        if stack.last().is_some_and(|(active, _, _)| *active == name) {
+                            stack.pop();
+                            select = stack.last().map_or(base, |(_, selected, _)| *selected);
+                        } else if lookup(name).is_some() {
+                            tracing::warn!(tag = name, "mismatched end tag in include");
+                        }
+                    }
+                    Some(TagDirective::Start(name)) => {
+                        if let Some(selected) = lookup(name) {
+                            found.push(name.to_string());
+                            select = selected;
+                            stack.push((name.to_string(), select, true));
+                        } else if let Some(wildcard) = wildcard {
+                            select = if !stack.is_empty() && !select {
+                                false
+                            } else {
+                                wildcard
+                            };
+                            stack.push((name.to_string(), select, false));
+                        }
+                    }
+                    None if select => lines.push(line),
+                    None => {}
+                }
+            }
+            if let Some((name, _, _)) = stack.into_iter().find(|(_, _, named)| *named) {
+                return Err(Error::UnclosedTag(self.detail(), name));
+            }
+            for (name, selected) in &selected_tags {
+                if *selected && !found.iter().any(|tag| tag == name) {
+                    tracing::warn!(tag = name, "tag not found in included file");
+                }
+            }
+            Ok(lines)
+        }
+
+        /// Keeps the lines picked by the `lines` attribute, in the order the ranges are
+        /// given.
+        ///
+        /// Ranges ending past the end of the file are clamped to it, but a range starting
+        /// outside of the file is an error.
+        fn select_lines(&self, content_lines: &[String]) -> Result<Vec<String>, Error> {
+            let mut lines = Vec::new();
+            for range in &self.lines {
+                let (start, end) = match *range {
+                    LinesRange::Single(line) => (line, line),
+                    LinesRange::Range(start, end) => (
+                        start,
+                        usize::try_from(end)
+                            .map_or(content_lines.len(), |end| end.min(content_lines.len())),
+                    ),
+                };
+                if start < 1 || start > content_lines.len() {
+                    return Err(Error::IncludeLineOutOfRange(
+                        self.detail(),
+                        start,
+                        content_lines.len(),
+                    ));
+                }
+                if start <= end {
+                    lines.extend_from_slice(&content_lines[start - 1..end]);
+                }
+            }
+            Ok(lines)
+        }
+
+        /// Points at the include directive, for errors about the included content.
+        fn detail(&self) -> ErrorDetail {
+            super::line_detail(self.line_number)
+        }
+
         pub(crate) fn lines(&self) -> Result<Vec<String>, Error> {
             // TODO(nlopes): need to read the file according to the properties of the include directive.
             //
@@ -240,12 +504,6 @@ attribute_value = {
                         e
                     })?;
                     let content_lines = content.lines().map(str::to_string).collect::<Vec<_>>();
-                    if let Some(level_offset) = self.level_offset {
-                        tracing::warn!(level_offset, "level offset is not supported yet");
-                    }
-                    if !self.tags.is_empty() {
-                        tracing::warn!(tags = ?self.tags, "tags are not supported yet");
-                    }
                     if let Some(indent) = self.indent {
                         tracing::warn!(indent, "indent is not supported yet");
                     }
@@ -255,69 +513,12 @@ attribute_value = {
                     if !self.opts.is_empty() {
                         tracing::warn!(opts = ?self.opts, "opts are not supported yet");
                     }
-                    // TODO(nlopes): this is so unoptimized, it isn't even funny but I'm
-                    // trying to just get to a place of compatibility, then I can
-                    // optimize.
-                    if self.lines.is_empty() {
-                        lines.extend(content_lines);
+                    if !self.lines.is_empty() {
+                        lines.extend(self.select_lines(&content_lines)?);
+                    } else if !self.tags.is_empty() {
+                        lines.extend(self.select_tagged(content_lines)?);
                     } else {
-                        for line in &self.lines {
-                            match line {
-                                LinesRange::Single(line_number) => {
-                                    if *line_number < 1 {
-                                        // TODO(nlopes): Skip invalid line numbers or should we return an error?
-                                        tracing::warn!(
-                                            ?line_number,
-                                            "invalid line number in include directive"
-                                        );
-                                        continue;
-                                    }
-                                    let line_number = line_number - 1;
-                                    if line_number < content_lines.len() {
-                                        lines.push(content_lines[line_number].clone());
-                                    }
-                                }
-                                LinesRange::Range(start, end) => {
-                                    let raw_size = content_lines.len();
-                                    if *start < 1 {
-                                        // Skip invalid line numbers
-                                        tracing::warn!(
-                                            ?start,
-                                            "invalid start line number in include directive"
-                                        );
-                                        continue;
-                                    }
-                                    let start = *start - 1;
-                                    let end = if *end == -1 {
-                                        raw_size
-                                    } else if *end > 0 {
-                                        match (*end - 1).try_into() {
-                                            Ok(end) => end,
-                                            Err(e) => {
-                                                tracing::error!(
-                                                    ?end,
-                                                    "failed to cast end line number to usize: {:?}",
-                                                    e
-                                                );
-                                                continue;
-                                            }
-                                        }
-                                    } else {
-                                        // Skip invalid line numbers
-                                        tracing::error!(
-                                            ?end,
-                                            "invalid end line number in include directive"
-                                        );
-                                        continue;
-                                    };
-                                    if start < raw_size && end < raw_size {
-                                        for line in &content_lines[start..=end] {
-                                            lines.push(line.clone());
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                        lines.extend(content_lines);
                     }
                 }
                 Target::Url(_url) => {
@@ -330,10 +531,16 @@ attribute_value = {
 }
 
 mod conditional {
+    use std::cmp::Ordering;
+
     use pest::Parser as _;
     use pest_derive::Parser;
 
-    use crate::{error::Error, DocumentAttributes};
+    use crate::{
+        error::Error,
+        model::{Substitute, HEADER},
+        DocumentAttributes,
+    };
 
     #[derive(Debug)]
     pub(crate) enum Conditional {
@@ -362,10 +569,128 @@ mod conditional {
         operation: Option<Operation>,
     }
 
+    /// An `ifeval::[left operator right]` directive. The operands are kept as written and
+    /// have attribute references replaced when the condition is checked.
     #[derive(Debug)]
     pub(crate) struct Ifeval {
-        #[allow(dead_code)]
-        expression: String,
+        left: String,
+        operator: Operator,
+        right: String,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub(crate) enum Operator {
+        Equal,
+        NotEqual,
+        Less,
+        LessOrEqual,
+        Greater,
+        GreaterOrEqual,
+    }
+
+    impl Operator {
+        /// Operators, longest first so `<=` isn't taken for `<`.
+        const ALL: [(&'static str, Operator); 6] = [
+            ("==", Operator::Equal),
+            ("!=", Operator::NotEqual),
+            ("<=", Operator::LessOrEqual),
+            (">=", Operator::GreaterOrEqual),
+            ("<", Operator::Less),
+            (">", Operator::Greater),
+        ];
+
+        fn holds(self, ordering: Option<Ordering>) -> bool {
+            match (self, ordering) {
+                (Operator::NotEqual, ordering) => ordering != Some(Ordering::Equal),
+                (_, None) => false,
+                (Operator::Equal, Some(ordering)) => ordering.is_eq(),
+                (Operator::Less, Some(ordering)) => ordering.is_lt(),
+                (Operator::LessOrEqual, Some(ordering)) => ordering.is_le(),
+                (Operator::Greater, Some(ordering)) => ordering.is_gt(),
+                (Operator::GreaterOrEqual, Some(ordering)) => ordering.is_ge(),
+            }
+        }
+    }
+
+    impl Ifeval {
+        /// Splits an expression such as `"{version}" >= "2.0"` into its operands and
+        /// operator, returning `None` when the quotes aren't balanced, there's no operator
+        /// or it isn't one we know.
+        fn parse(expression: &str) -> Option<Self> {
+            let mut quote = None;
+            let mut split = None;
+            for (i, c) in expression.char_indices() {
+                match quote {
+                    Some(open) if c == open => quote = None,
+                    Some(_) => {}
+                    None if c == '"' || c == '\'' => quote = Some(c),
+                    None => {
+                        if let Some((symbol, operator)) = Operator::ALL
+                            .iter()
+                            .find(|(symbol, _)| expression[i..].starts_with(symbol))
+                        {
+                            split = Some((i, symbol.len(), *operator));
+                            break;
+                        }
+                        if c == '=' || c == '!' {
+                            return None;
+                        }
+                    }
+                }
+            }
+            let (at, len, operator) = split?;
+            let left = expression[..at].trim();
+            let right = expression[at + len..].trim();
+            if right.starts_with(['=', '<', '>', '!']) || !is_operand(left) || !is_operand(right) {
+                return None;
+            }
+            Some(Self {
+                left: left.to_string(),
+                operator,
+                right: right.to_string(),
+            })
+        }
+
+        /// Compares the operands numerically when both are numbers and as text otherwise.
+        fn is_true(&self, attributes: &DocumentAttributes) -> bool {
+            let value = |operand: &str| {
+                let operand = operand.substitute(HEADER, attributes);
+                unquote(&operand).to_string()
+            };
+            let (left, right) = (value(&self.left), value(&self.right));
+            let ordering = match (left.parse::<f64>(), right.parse::<f64>()) {
+                (Ok(left), Ok(right)) => left.partial_cmp(&right),
+                _ => Some(left.cmp(&right)),
+            };
+            self.operator.holds(ordering)
+        }
+    }
+
+    /// Whether `operand` is a single quoted string or a bare value without quotes.
+    fn is_operand(operand: &str) -> bool {
+        if operand.is_empty() {
+            return false;
+        }
+        match operand.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                operand.len() >= 2
+                    && operand.ends_with(quote)
+                    && !operand[1..operand.len() - 1].contains(quote)
+            }
+            _ => !operand.contains(['"', '\'']),
+        }
+    }
+
+    fn unquote(operand: &str) -> &str {
+        operand
+            .strip_prefix('"')
+            .and_then(|operand| operand.strip_suffix('"'))
+            .or_else(|| {
+                operand
+                    .strip_prefix('\'')
+                    .and_then(|operand| operand.strip_suffix('\''))
+            })
+            .unwrap_or(operand)
     }
 
     impl Conditional {
@@ -425,7 +750,7 @@ mod conditional {
                     }
                     is_true
                 }
-                Conditional::Ifeval(_ifeval) => todo!("ifeval conditional check"),
+                Conditional::Ifeval(ifeval) => ifeval.is_true(attributes),
             }
         }
     }
@@ -450,7 +775,7 @@ expression = { (!"]" ~ ANY)+ }
     pub(crate) struct Parser;
 
     #[tracing::instrument(level = "trace")]
-    pub(crate) fn parse_line(line: &str) -> Result<Conditional, Error> {
+    pub(crate) fn parse_line(line: &str, line_number: usize) -> Result<Conditional, Error> {
         match Parser::parse(Rule::conditional, line) {
             Ok(pairs) => {
                 let mut conditional = Conditional::Ifdef(Ifdef {
@@ -467,7 +792,7 @@ expression = { (!"]" ~ ANY)+ }
                             conditional = parse_ifndef(pair)?;
                         }
                         Rule::ifeval => {
-                            conditional = parse_ifeval(pair)?;
+                            conditional = parse_ifeval(pair, line_number)?;
                         }
                         unknown => {
                             tracing::warn!(?unknown, "unknown rule in conditional directive");
@@ -478,7 +803,9 @@ expression = { (!"]" ~ ANY)+ }
             }
             Err(e) => {
                 tracing::error!(?e, "failed to parse conditional directive");
-                Err(Error::InvalidConditionalDirective)
+                Err(Error::InvalidConditionalDirective(super::line_detail(
+                    line_number,
+                )))
             }
         }
     }
@@ -550,13 +877,16 @@ expression = { (!"]" ~ ANY)+ }
     }
 
     #[tracing::instrument(level = "trace")]
-    fn parse_ifeval(pair: pest::iterators::Pair<Rule>) -> Result<Conditional, Error> {
-        let mut expression = String::new();
+    fn parse_ifeval(
+        pair: pest::iterators::Pair<Rule>,
+        line_number: usize,
+    ) -> Result<Conditional, Error> {
+        let mut expression = "";
 
         for pair in pair.into_inner() {
             match pair.as_rule() {
                 Rule::expression => {
-                    expression = pair.as_str().to_string();
+                    expression = pair.as_str();
                 }
                 unknown => {
                     tracing::warn!(?unknown, "unknown rule in ifeval directive");
@@ -564,7 +894,14 @@ expression = { (!"]" ~ ANY)+ }
             }
         }
 
-        Ok(Conditional::Ifeval(Ifeval { expression }))
+        if let Some(ifeval) = Ifeval::parse(expression) {
+            Ok(Conditional::Ifeval(ifeval))
+        } else {
+            tracing::error!(expression, "invalid ifeval expression");
+            Err(Error::InvalidConditionalDirective(super::line_detail(
+                line_number,
+            )))
+        }
     }
 }
 
@@ -623,6 +960,30 @@ value = { (!EOI ~ ANY)+ }"#]
     }
 }
 
+/// Points at the start of a line of the document, for errors about a directive on it.
+fn line_detail(line_number: usize) -> ErrorDetail {
+    ErrorDetail {
+        location: Location {
+            start: Position {
+                line: line_number,
+                column: 1,
+            },
+            end: Position {
+                line: line_number,
+                column: 1,
+            },
+            ..Location::default()
+        },
+    }
+}
+
+/// Whether `line` is an attribute entry setting or unsetting `leveloffset`.
+fn is_level_offset_entry(line: &str) -> bool {
+    line.starts_with(":leveloffset:")
+        || line.starts_with(":leveloffset!:")
+        || line.starts_with(":!leveloffset:")
+}
+
 impl Preprocessor {
     fn normalize(input: &str) -> String {
         input
@@ -669,9 +1030,11 @@ impl Preprocessor {
     fn process_either(&self, input: &str, file_parent: Option<&Path>) -> Result<String, Error> {
         let input = Preprocessor::normalize(input);
         let mut attributes = DocumentAttributes::default();
+        let mut level_offset = 0;
+        let mut verbatim: Option<&str> = None;
         let mut output = Vec::new();
-        let mut lines = input.lines().peekable();
-        while let Some(line) = lines.next() {
+        let mut lines = input.lines().enumerate().peekable();
+        while let Some((index, line)) = lines.next() {
             if line.starts_with(':') && (line.ends_with(" + \\") || line.ends_with(" \\")) {
                 let mut attribute_content = String::new();
                 if line.ends_with(" + \\") {
@@ -680,7 +1043,7 @@ impl Preprocessor {
                 } else if line.ends_with(" \\") {
                     attribute_content.push_str(line.trim_end_matches('\\'));
                 }
-                while let Some(next_line) = lines.peek() {
+                while let Some((_, next_line)) = lines.peek() {
                     let next_line = next_line.trim();
                     // If the next line isn't the end of a continuation, or a
                     // continuation, we need to break out.
@@ -709,6 +1072,30 @@ impl Preprocessor {
                 continue;
             } else if line.starts_with(':') {
                 attribute::parse_line(&mut attributes, line.trim());
+                if is_level_offset_entry(line) {
+                    level_offset = match attributes.get("leveloffset") {
+                        Some(AttributeValue::String(value)) => match LevelOffset::parse(value) {
+                            Ok(offset) => offset.resolve(level_offset),
+                            Err(e) => {
+                                tracing::warn!(?value, "ignoring invalid leveloffset: {e}");
+                                level_offset
+                            }
+                        },
+                        _ => 0,
+                    };
+                }
+            }
+            if let Some(delimiter) = verbatim {
+                if line == delimiter {
+                    verbatim = None;
+                }
+            } else if is_verbatim_delimiter(line) {
+                verbatim = Some(line);
+            } else if level_offset != 0 {
+                if let Some(shifted) = shift_section(line, level_offset) {
+                    output.push(shifted);
+                    continue;
+                }
             }
             // Taken from
             // https://github.com/asciidoctor/asciidoctor/blob/306111f480e2853ba59107336408de15253ca165/lib/asciidoctor/reader.rb#L604
@@ -727,8 +1114,8 @@ impl Preprocessor {
                     || line.starts_with("ifeval")
                 {
                     let mut content = String::new();
-                    let condition = conditional::parse_line(line)?;
-                    while let Some(next_line) = lines.peek() {
+                    let condition = conditional::parse_line(line, index + 1)?;
+                    while let Some((_, next_line)) = lines.peek() {
                         if next_line.is_empty() {
                             tracing::trace!(?line, "single line if directive");
                             break;
@@ -738,7 +1125,8 @@ impl Preprocessor {
                             lines.next();
                             break;
                         }
-                        content.push_str(&format!("{next_line}\n"));
+                        content.push_str(next_line);
+                        content.push('\n');
                         lines.next();
                     }
                     if condition.is_true(&attributes, &mut content) {
@@ -748,9 +1136,12 @@ impl Preprocessor {
                     // TODO(nlopes): need to read the file according to the type of file
                     if let Some(file_parent) = file_parent {
                         // Parse the include directive
-                        let include = Include::parse(file_parent, line, &attributes)?;
+                        let include = Include::parse(file_parent, index + 1, line, &attributes)?;
                         // Process the include directive
-                        output.extend(include.lines()?);
+                        output.extend(shift_sections(
+                            include.lines()?,
+                            include.level_offset(level_offset),
+                        ));
                     } else {
                         tracing::error!(
                             "file parent is missing - include directive cannot be processed"
@@ -785,4 +1176,120 @@ endif::[]
         let output = Preprocessor.process(input).unwrap();
         assert_eq!(output, ":attribute: value\n\ncontent\n");
     }
+
+    #[test]
+    fn test_process_ifeval() {
+        let input = ":version: 10
+:edition: basic
+
+ifeval::[{version} > 9]
+numeric
+endif::[]
+ifeval::[\"{edition}\" < 'pro']
+text
+endif::[]
+ifeval::[\"{edition}\" != \"pro\"]
+not pro
+endif::[]
+ifeval::[{version} == 3]
+version 3
+endif::[]
+";
+        let output = Preprocessor.process(input).unwrap();
+        assert_eq!(
+            output,
+            ":version: 10\n:edition: basic\n\nnumeric\n\ntext\n\nnot pro\n"
+        );
+    }
+
+    #[test]
+    fn test_process_invalid_ifeval() {
+        for expression in ["\"a == \"a\"", "1 = 1", "1 =~ 1", "1 <> 2", "1"] {
+            let input = format!("Some text.\nifeval::[{expression}]\nmore\nendif::[]\n");
+            let error = Preprocessor.process(&input).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "Invalid conditional directive, position: start(line: 2, column: 1), end(line: 2, column: 1)",
+                "{expression}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_process_include_leveloffset() {
+        let output = Preprocessor
+            .process_file("fixtures/samples/leveloffset/book.adoc")
+            .unwrap();
+        assert_eq!(
+            output,
+            "= Book\n\n== Chapter\n\nText.\n\n=== Section\n\n----\n== not a section\n----\n\n=== Chapter\n\nText.\n\n==== Section\n\n----\n== not a section\n----"
+        );
+    }
+
+    #[test]
+    fn test_process_leveloffset_attribute() {
+        let input = "= Document\n\n:leveloffset: +1\n\n= Part\n\n:leveloffset: -1\n\n== Back\n\n:leveloffset: 2\n\n= Deep\n\n:leveloffset!:\n\n== Reset\n";
+        let output = Preprocessor.process(input).unwrap();
+        assert_eq!(
+            output,
+            "= Document\n\n:leveloffset: +1\n\n== Part\n\n:leveloffset: -1\n\n== Back\n\n:leveloffset: 2\n\n=== Deep\n\n:leveloffset!:\n\n== Reset"
+        );
+    }
+
+    #[test]
+    fn test_process_include_tagged_regions() {
+        let output = Preprocessor
+            .process_file("fixtures/samples/tags/regions.adoc")
+            .unwrap();
+        let regions = output.split("\n--\n").collect::<Vec<_>>();
+        assert_eq!(
+            regions,
+            vec![
+                "let config = Config::default();\nlet secret = 42;\nlet app = App::new(config);",
+                "let config = Config::default();\nlet secret = 42;\nlet app = App::new(config);\napp.run();",
+                "let config = Config::default();\nlet app = App::new(config);\napp.run();",
+                "let config = Config::default();\nlet app = App::new(config);\nfn untagged() {}\napp.run();",
+                "let config = Config::default();\nlet app = App::new(config);\nfn untagged() {}\napp.run();",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_process_include_line_ranges() {
+        let output = Preprocessor
+            .process_file("fixtures/samples/lines/ranges.adoc")
+            .unwrap();
+        let ranges = output.split("\n--\n").collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            vec![
+                "line 2\nline 3",
+                "line 1\nline 5\nline 6\nline 7\nline 8",
+                "line 6\nline 7\nline 8",
+                "line 4\nline 8",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_process_include_line_out_of_range() {
+        let error = Preprocessor
+            .process_file("fixtures/samples/lines/out-of-range.adoc")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 12 is out of range of the 8 lines of the included file, position: start(line: 3, column: 1), end(line: 3, column: 1)"
+        );
+    }
+
+    #[test]
+    fn test_process_include_unclosed_tag() {
+        let error = Preprocessor
+            .process_file("fixtures/samples/tags/unclosed.adoc")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unclosed tag 'oops' in included file, position: start(line: 3, column: 1), end(line: 3, column: 1)"
+        );
+    }
 }
@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+/// How long each phase of parsing a document took, as returned by
+/// [`parse_with_timings`](crate::parse_with_timings) and
+/// [`parse_file_with_timings`](crate::parse_file_with_timings).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseTimings {
+    /// Reading the source and resolving includes and conditionals.
+    pub preprocess: Duration,
+    /// Matching the source against the grammar.
+    pub grammar: Duration,
+    /// Building the header and blocks out of what the grammar matched.
+    pub blocks: Duration,
+    /// Nesting the blocks under the sections they belong to.
+    pub section_tree: Duration,
+    /// Counting the words, for `wordcount` and `reading-time`, and filling them in.
+    pub word_count: Duration,
+    /// Assigning ids to the sections without one.
+    pub section_ids: Duration,
+    /// Numbering the footnotes.
+    pub footnotes: Duration,
+    /// Collecting the table of contents entries.
+    pub toc: Duration,
+}
+
+impl ParseTimings {
+    /// The name of each phase, as shown to users, and the time it took, in the order
+    /// they run in.
+    pub fn phases(&self) -> impl Iterator<Item = (&'static str, Duration)> {
+        [
+            ("Preprocess", self.preprocess),
+            ("Grammar parse", self.grammar),
+            ("Blocks", self.blocks),
+            ("Section tree", self.section_tree),
+            ("Word count", self.word_count),
+            ("Section ids", self.section_ids),
+            ("Footnotes", self.footnotes),
+            ("Table of contents", self.toc),
+        ]
+        .into_iter()
+    }
+
+    /// The time all the phases took together.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.phases().map(|(_, elapsed)| elapsed).sum()
+    }
+}
+
+/// Runs `f`, adding the time it took to `phase`.
+pub(crate) fn measure<T>(phase: &mut Duration, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    *phase += start.elapsed();
+    result
+}
@@ -1,5 +1,10 @@
-use std::path::PathBuf;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    string::FromUtf8Error,
+};
 
+use acdc_parser::Document;
 use clap::ValueEnum;
 
 /// document type to use when converting document
@@ -128,6 +133,7 @@ pub trait Processable {
     type Config;
     type Error;
 
+    #[must_use]
     fn new(config: Self::Config) -> Self;
 
     /// Run the processor
@@ -149,4 +155,41 @@ pub trait Processable {
     /// - parsing or rendering errors. Implementations are free to return any error type
     ///   they wish though.
     fn output(&self) -> Result<String, Self::Error>;
+
+    /// Convert an already parsed document, writing the result to `writer`.
+    ///
+    /// `source_file` is the file the document was read from, if any, which some
+    /// processors look at (e.g. for its modification time or to resolve relative paths).
+    ///
+    /// # Errors
+    ///
+    /// Will typically return rendering or I/O errors.
+    fn convert<W: Write>(
+        &self,
+        doc: &Document,
+        source_file: Option<&Path>,
+        writer: &mut W,
+    ) -> Result<(), Self::Error>;
+
+    /// Convert an already parsed document, returning the result as a string.
+    ///
+    /// This is only available when the processor's error can be built from a
+    /// `FromUtf8Error`, as the converted output is decoded as UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// Will return the errors of [`Processable::convert`], or a `FromUtf8Error` if the
+    /// output isn't valid UTF-8.
+    fn convert_to_string(
+        &self,
+        doc: &Document,
+        source_file: Option<&Path>,
+    ) -> Result<String, Self::Error>
+    where
+        Self::Error: From<FromUtf8Error>,
+    {
+        let mut buffer = Vec::new();
+        self.convert(doc, source_file, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
 }
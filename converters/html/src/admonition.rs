@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use acdc_parser::Admonition;
+use acdc_parser::{Admonition, AdmonitionVariant, AttributeValue, BlockMetadata, InlineNode};
 
 use crate::{Processor, Render, RenderOptions};
 
@@ -13,23 +13,259 @@ impl Render for Admonition {
         processor: &Processor,
         options: &RenderOptions,
     ) -> Result<(), Self::Error> {
-        writeln!(w, "<div class=\"admonitionblock {}\">", self.variant)?;
-        writeln!(w, "<table>")?;
-        writeln!(w, "<tr>")?;
-        writeln!(w, "<td class=\"icon\">")?;
-        writeln!(w, "<div class=\"title\">{}</div>", self.variant)?;
-        writeln!(w, "</td>")?;
-        writeln!(w, "<td class=\"content\">")?;
-        write!(w, "<div class=\"title\">")?;
-        crate::inlines::render_inlines(&self.title, w, processor, options)?;
-        writeln!(w, "</div>")?;
-        for block in &self.blocks {
-            block.render(w, processor, options)?;
+        let name = self.variant.to_string();
+        let label = Label {
+            name: &name,
+            caption: caption(&self.variant),
+            text: &name,
+            icon: Some(default_icon(&self.variant)),
+        };
+        render(
+            &label,
+            &self.title,
+            &self.metadata,
+            w,
+            processor,
+            options,
+            |w| {
+                for block in &self.blocks {
+                    block.render(w, processor, options)?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// What sets an admonition apart from the others.
+struct Label<'a> {
+    /// The type of admonition, which it's styled after.
+    name: &'a str,
+    /// What the admonition is called, shown when hovering its icon or collapsing it.
+    caption: &'a str,
+    /// The label shown instead of an icon, when `:icons: font` isn't set.
+    text: &'a str,
+    /// The Font Awesome icon used when no `<name>-caption-icon` attribute overrides it.
+    icon: Option<&'a str>,
+}
+
+/// Returns the type of the custom admonition a block is, if it is one: the lowercased
+/// style for an upper case style that isn't a built-in admonition (`[QUESTION]`), or
+/// the role next to the `admonition` role (`[.admonition.question]`).
+pub(crate) fn custom_type(metadata: &BlockMetadata) -> Option<String> {
+    if let Some(style) = &metadata.style {
+        if !style.is_empty() && style.chars().all(|c| c.is_ascii_uppercase() || c == '-') {
+            return Some(style.to_ascii_lowercase());
         }
-        writeln!(w, "</td>")?;
-        writeln!(w, "</tr>")?;
-        writeln!(w, "</table>")?;
+    }
+    if metadata.roles.iter().any(|r| r == "admonition") {
+        return metadata.roles.iter().find(|r| *r != "admonition").cloned();
+    }
+    None
+}
+
+/// Renders a block as a custom admonition of type `name`, with `content` rendering its
+/// content.
+///
+/// Its caption is the block's `caption` attribute, or the `<name>-caption` document
+/// attribute, or else the name itself capitalized. It only gets an icon when the
+/// `<name>-caption-icon` document attribute gives it one.
+pub(crate) fn render_custom<W: Write>(
+    name: &str,
+    title: &[InlineNode],
+    metadata: &BlockMetadata,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+    content: impl FnOnce(&mut W) -> Result<(), crate::Error>,
+) -> Result<(), crate::Error> {
+    let caption = match metadata.attributes.get("caption") {
+        Some(AttributeValue::String(caption)) => caption.clone(),
+        _ => match processor
+            .document_attributes
+            .borrow()
+            .get(&format!("{name}-caption"))
+        {
+            Some(AttributeValue::String(caption)) => caption.clone(),
+            _ => capitalize(name),
+        },
+    };
+    let label = Label {
+        name,
+        caption: &caption,
+        text: &caption,
+        icon: None,
+    };
+    // The roles naming the admonition are already part of its classes.
+    let metadata = BlockMetadata {
+        roles: metadata
+            .roles
+            .iter()
+            .filter(|r| *r != "admonition" && *r != name)
+            .cloned()
+            .collect(),
+        ..metadata.clone()
+    };
+    render(&label, title, &metadata, w, processor, options, content)
+}
+
+fn render<W: Write>(
+    label: &Label,
+    title: &[InlineNode],
+    metadata: &BlockMetadata,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+    content: impl FnOnce(&mut W) -> Result<(), crate::Error>,
+) -> Result<(), crate::Error> {
+    let caption = crate::inlines::escape_attribute(label.caption);
+    let collapsible = crate::delimited::is_collapsible(metadata);
+    if collapsible {
+        crate::delimited::open_details(title, &caption, metadata, w, processor, options)?;
+    }
+    writeln!(
+        w,
+        "<div{} class=\"{}\">",
+        crate::xref::id_attribute(metadata),
+        crate::delimited::block_classes(&format!("admonitionblock {}", label.name), metadata)
+    )?;
+    writeln!(w, "<table>")?;
+    writeln!(w, "<tr>")?;
+    writeln!(w, "<td class=\"icon\">")?;
+    let icon = {
+        let attributes = processor.document_attributes.borrow();
+        if matches!(attributes.get("icons"), Some(AttributeValue::String(icons)) if icons == "font")
+        {
+            match attributes.get(&format!("{}-caption-icon", label.name)) {
+                Some(AttributeValue::String(icon)) => Some(icon.clone()),
+                _ => label.icon.map(str::to_string),
+            }
+        } else {
+            None
+        }
+    };
+    if let Some(icon) = icon {
+        writeln!(
+            w,
+            "<i class=\"fa {}\" title=\"{caption}\"></i>",
+            crate::inlines::escape_attribute(&icon)
+        )?;
+    } else {
+        writeln!(
+            w,
+            "<div class=\"title\">{}</div>",
+            crate::inlines::escape_attribute(label.text)
+        )?;
+    }
+    writeln!(w, "</td>")?;
+    writeln!(w, "<td class=\"content\">")?;
+    if !collapsible {
+        write!(w, "<div class=\"title\">")?;
+        crate::inlines::render_inlines(title, w, processor, options)?;
         writeln!(w, "</div>")?;
-        Ok(())
+    }
+    content(w)?;
+    writeln!(w, "</td>")?;
+    writeln!(w, "</tr>")?;
+    writeln!(w, "</table>")?;
+    writeln!(w, "</div>")?;
+    if collapsible {
+        writeln!(w, "</details>")?;
+    }
+    Ok(())
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// The Font Awesome icon used for an admonition when `:icons: font` is set and no
+/// `<variant>-caption-icon` attribute overrides it.
+fn default_icon(variant: &AdmonitionVariant) -> &'static str {
+    match variant {
+        AdmonitionVariant::Note => "fa-info-circle",
+        AdmonitionVariant::Tip => "fa-lightbulb-o",
+        AdmonitionVariant::Important => "fa-exclamation-circle",
+        AdmonitionVariant::Caution => "fa-fire",
+        AdmonitionVariant::Warning => "fa-exclamation-triangle",
+    }
+}
+
+fn caption(variant: &AdmonitionVariant) -> &'static str {
+    match variant {
+        AdmonitionVariant::Note => "Note",
+        AdmonitionVariant::Tip => "Tip",
+        AdmonitionVariant::Important => "Important",
+        AdmonitionVariant::Caution => "Caution",
+        AdmonitionVariant::Warning => "Warning",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_font_icons() {
+        let html = render_string(
+            "= Title\n:icons: font\n:note-caption-icon: fa-sticky-note\n\nNOTE: A note.\n\nTIP: A tip.\n\nWARNING: A warning.\n",
+        );
+        assert!(html.contains("<i class=\"fa fa-sticky-note\" title=\"Note\"></i>"));
+        assert!(html.contains("<i class=\"fa fa-lightbulb-o\" title=\"Tip\"></i>"));
+        assert!(html.contains("<i class=\"fa fa-exclamation-triangle\" title=\"Warning\"></i>"));
+    }
+
+    #[test]
+    fn test_render_collapsible_admonition() {
+        let html = render_string("[%collapsible]\nNOTE: Look closer.\n");
+        assert!(html.contains(
+            "<details>\n<summary class=\"title\">Note</summary>\n<div class=\"admonitionblock note\">"
+        ));
+        assert!(html.contains("</table>\n</div>\n</details>"));
+
+        let html = render_string(".More\n[NOTE%collapsible]\n====\nBody.\n====\n");
+        assert!(html.contains("<details>\n<summary class=\"title\">More</summary>"));
+    }
+
+    #[test]
+    fn test_render_admonition_break_classes() {
+        let html = render_string("[.avoid-break]\nNOTE: Keep me whole.\n");
+        assert!(html.contains("<div class=\"admonitionblock note avoid-break unbreakable\">"));
+
+        let html = render_string("[%breakable]\nTIP: Split me.\n");
+        assert!(html.contains("<div class=\"admonitionblock tip breakable\">"));
+    }
+
+    #[test]
+    fn test_render_custom_admonition() {
+        let html = render_string("[QUESTION]\nIs it ready?\n");
+        assert!(html.contains(
+            "<div class=\"admonitionblock question\">\n<table>\n<tr>\n<td class=\"icon\">\n<div class=\"title\">Question</div>\n</td>\n<td class=\"content\">"
+        ));
+        assert!(html.contains("<p>Is it ready?</p>"));
+
+        let html = render_string(
+            "= Doc\n:icons: font\n:question-caption-icon: fa-question-circle\n\n[.admonition.question.wide,caption=Quiz]\n====\nWhat is it?\n====\n",
+        );
+        assert!(html.contains("<div class=\"admonitionblock question wide\">"));
+        assert!(html.contains("<i class=\"fa fa-question-circle\" title=\"Quiz\"></i>"));
+        assert!(html.contains("<p>What is it?</p>"));
+        assert!(!html.contains("<div class=\"exampleblock"));
+    }
+
+    #[test]
+    fn test_render_custom_admonition_escapes_caption() {
+        let html = render_string("[QUESTION,caption=\"<b>Q</b>\"]\nIs it ready?\n");
+        assert!(html.contains("<div class=\"title\">&lt;b&gt;Q&lt;/b&gt;</div>"));
+
+        let html = render_string(
+            "= Doc\n:icons: font\n:question-caption: A & B\n:question-caption-icon: fa-x\" onclick=\"y\n\n[QUESTION]\nIs it ready?\n",
+        );
+        assert!(
+            html.contains("<i class=\"fa fa-x&quot; onclick=&quot;y\" title=\"A &amp; B\"></i>")
+        );
     }
 }
@@ -19,6 +19,22 @@ impl Render for Block {
             Block::DelimitedBlock(d) => d.render(w, processor, options),
             Block::Section(s) => s.render(w, processor, options),
             Block::UnorderedList(u) => u.render(w, processor, options),
+            Block::OrderedList(o) => o.render(w, processor, options),
+            Block::CalloutList(c) => c.render(w, processor, options),
+            Block::DescriptionList(d) => d.render(w, processor, options),
+            Block::Image(i) => crate::image::render_block(i, w, processor, options),
+            Block::DocumentAttribute(attribute) => {
+                // Attribute entries in the body take effect from where they appear on.
+                processor
+                    .document_attributes
+                    .borrow_mut()
+                    .insert(attribute.name.clone(), attribute.value.clone());
+                Ok(())
+            }
+            Block::TableOfContents(_) => {
+                write!(w, "{}", processor.toc.borrow())?;
+                Ok(())
+            }
             unknown => todo!("rendering for block type: {:?}", unknown),
         }
     }
@@ -0,0 +1,44 @@
+use std::cell::Cell;
+
+use acdc_parser::{AttributeValue, ElementAttributes};
+
+use crate::Processor;
+
+/// Returns the label that goes before the title of a titled block, e.g. "Table 1. ".
+///
+/// That's the block's `caption` attribute when it has one. Otherwise it's the
+/// `<kind>-caption` document attribute (`default` when it isn't set) followed by the next
+/// number from `counter`, unless the document unsets the attribute. The counters live
+/// in the processor and are only reset per document, so numbering runs across sections
+/// and included files. The label is escaped, as it's written as text.
+pub(crate) fn prefix(
+    kind: &str,
+    default: &str,
+    attributes: &ElementAttributes,
+    counter: &Cell<usize>,
+    processor: &Processor,
+) -> String {
+    if let Some(AttributeValue::String(caption)) = attributes.get("caption") {
+        return escape(caption);
+    }
+    let label = match processor
+        .document_attributes
+        .borrow()
+        .get(&format!("{kind}-caption"))
+    {
+        Some(AttributeValue::Bool(false)) => return String::new(),
+        Some(AttributeValue::String(label)) => label.clone(),
+        _ => String::from(default),
+    };
+    let number = counter.get() + 1;
+    counter.set(number);
+    format!("{} {number}. ", escape(&label))
+}
+
+fn escape(text: &str) -> String {
+    if text.is_empty() {
+        String::new()
+    } else {
+        crate::inlines::substitution_text(text)
+    }
+}
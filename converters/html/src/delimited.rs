@@ -1,8 +1,17 @@
 use std::io::Write;
 
-use acdc_parser::{DelimitedBlock, DelimitedBlockType};
+use acdc_parser::{
+    AttributeValue, BlockMetadata, DelimitedBlock, DelimitedBlockType, ElementAttributes,
+    InlineNode, Substitution,
+};
 
-use crate::{Processor, Render, RenderOptions};
+use crate::{
+    inlines::{escape_attribute, substitution_text},
+    Processor, Render, RenderOptions,
+};
+
+const DEFAULT_FOLD_START: &str = "// <editor-fold";
+const DEFAULT_FOLD_END: &str = "// </editor-fold>";
 
 impl Render for DelimitedBlock {
     type Error = crate::Error;
@@ -13,40 +22,664 @@ impl Render for DelimitedBlock {
         processor: &Processor,
         options: &RenderOptions,
     ) -> Result<(), Self::Error> {
+        if let DelimitedBlockType::DelimitedComment(inlines) = &self.inner {
+            return render_comment(inlines, w, processor);
+        }
         writeln!(w, "<div>")?;
         match &self.inner {
-            DelimitedBlockType::DelimitedTable(t) => t.render(w, processor, options)?,
+            DelimitedBlockType::DelimitedTable(t) => {
+                crate::table::render_table(self, t, w, processor, options)?;
+            }
+            DelimitedBlockType::DelimitedPass(inlines) if crate::stem::is_stem_block(self) => {
+                crate::stem::render_stem_block(self, inlines, w, processor, options)?;
+            }
             DelimitedBlockType::DelimitedPass(inlines) => {
                 crate::inlines::render_inlines(inlines, w, processor, options)?;
             }
             DelimitedBlockType::DelimitedListing(inlines)
             | DelimitedBlockType::DelimitedLiteral(inlines) => {
-                if let Some(style) = &self.metadata.style {
-                    writeln!(w, "<div class=\"{style}block\">")?;
-                } else {
-                    writeln!(w, "<div class=\"literalblock\">")?;
-                }
+                let name = format!(
+                    "{}block",
+                    self.metadata.style.as_deref().unwrap_or("literal")
+                );
+                writeln!(
+                    w,
+                    "<div{} class=\"{}\">",
+                    crate::xref::id_attribute(&self.metadata),
+                    block_classes(&name, &self.metadata)
+                )?;
                 write!(w, "<div class=\"title\">")?;
                 crate::inlines::render_inlines(&self.title, w, processor, options)?;
                 writeln!(w, "</div>")?;
                 writeln!(w, "<div class=\"content\">")?;
-                writeln!(w, "<pre>")?;
+                match pre_classes(&self.metadata, processor) {
+                    Some(classes) => writeln!(w, "<pre class=\"{classes}\">")?,
+                    None => writeln!(w, "<pre>")?,
+                }
+                // Without a `subs` attribute, the block gets the verbatim substitutions.
+                let applies = |substitution| {
+                    self.metadata
+                        .substitutions
+                        .as_ref()
+                        .is_none_or(|subs| subs.contains(&substitution))
+                };
+                let mut content = Vec::new();
                 crate::inlines::render_inlines(
                     inlines,
-                    w,
+                    &mut content,
                     processor,
                     &RenderOptions {
-                        inlines_substitutions: true,
+                        raw_text: !applies(Substitution::SpecialChars),
                         ..*options
                     },
                 )?;
+                let mut content = String::from_utf8(content)?;
+                if let Some(tabsize) = tabsize(self, processor) {
+                    content = expand_tabs(&content, tabsize);
+                }
+                if let Some(indent) = source_indent(self, processor) {
+                    content = reindent(&content, indent);
+                }
+                if applies(Substitution::Callouts) {
+                    content = render_callouts(&content, processor);
+                } else {
+                    processor.callouts.take();
+                }
+                if self.metadata.options.iter().any(|o| o == "fold") {
+                    content = fold_regions(&self.metadata.attributes, &content);
+                }
+                w.write_all(content.as_bytes())?;
                 writeln!(w, "</pre>")?;
                 writeln!(w, "</div>")?;
                 writeln!(w, "</div>")?;
             }
+            DelimitedBlockType::DelimitedExample(blocks) if is_collapsible(&self.metadata) => {
+                open_details(
+                    &self.title,
+                    "Details",
+                    &self.metadata,
+                    w,
+                    processor,
+                    options,
+                )?;
+                writeln!(w, "<div class=\"content\">")?;
+                for block in blocks {
+                    block.render(w, processor, options)?;
+                }
+                writeln!(w, "</div>")?;
+                writeln!(w, "</details>")?;
+            }
+            DelimitedBlockType::DelimitedExample(blocks)
+                if crate::admonition::custom_type(&self.metadata).is_some() =>
+            {
+                let name = crate::admonition::custom_type(&self.metadata).unwrap_or_default();
+                crate::admonition::render_custom(
+                    &name,
+                    &self.title,
+                    &self.metadata,
+                    w,
+                    processor,
+                    options,
+                    |w| {
+                        for block in blocks {
+                            block.render(w, processor, options)?;
+                        }
+                        Ok(())
+                    },
+                )?;
+            }
+            DelimitedBlockType::DelimitedExample(blocks) => {
+                writeln!(
+                    w,
+                    "<div{} class=\"{}\">",
+                    crate::xref::id_attribute(&self.metadata),
+                    block_classes("exampleblock", &self.metadata)
+                )?;
+                if !self.title.is_empty() {
+                    write!(w, "<div class=\"title\">")?;
+                    let caption = crate::caption::prefix(
+                        "example",
+                        "Example",
+                        &self.metadata.attributes,
+                        &processor.example_counter,
+                        processor,
+                    );
+                    write!(w, "{caption}")?;
+                    crate::inlines::render_inlines(&self.title, w, processor, options)?;
+                    writeln!(w, "</div>")?;
+                }
+                writeln!(w, "<div class=\"content\">")?;
+                for block in blocks {
+                    block.render(w, processor, options)?;
+                }
+                writeln!(w, "</div>")?;
+                writeln!(w, "</div>")?;
+            }
+            DelimitedBlockType::DelimitedOpen(blocks) => {
+                writeln!(
+                    w,
+                    "<div{} class=\"{}\">",
+                    crate::xref::id_attribute(&self.metadata),
+                    block_classes("openblock", &self.metadata)
+                )?;
+                if !self.title.is_empty() {
+                    write!(w, "<div class=\"title\">")?;
+                    crate::inlines::render_inlines(&self.title, w, processor, options)?;
+                    writeln!(w, "</div>")?;
+                }
+                writeln!(w, "<div class=\"content\">")?;
+                for block in blocks {
+                    block.render(w, processor, options)?;
+                }
+                writeln!(w, "</div>")?;
+                writeln!(w, "</div>")?;
+            }
+            DelimitedBlockType::DelimitedSidebar(blocks) => {
+                let collapsible = is_collapsible(&self.metadata);
+                if collapsible {
+                    open_details(
+                        &self.title,
+                        "Details",
+                        &self.metadata,
+                        w,
+                        processor,
+                        options,
+                    )?;
+                }
+                writeln!(
+                    w,
+                    "<div{} class=\"{}\">",
+                    crate::xref::id_attribute(&self.metadata),
+                    block_classes("sidebarblock", &self.metadata)
+                )?;
+                writeln!(w, "<div class=\"content\">")?;
+                if !collapsible && !self.title.is_empty() {
+                    write!(w, "<div class=\"title\">")?;
+                    crate::inlines::render_inlines(&self.title, w, processor, options)?;
+                    writeln!(w, "</div>")?;
+                }
+                for block in blocks {
+                    block.render(w, processor, options)?;
+                }
+                writeln!(w, "</div>")?;
+                writeln!(w, "</div>")?;
+                if collapsible {
+                    writeln!(w, "</details>")?;
+                }
+            }
             unknown => todo!("Unknown delimited block type: {:?}", unknown),
         }
         writeln!(w, "</div>")?;
         Ok(())
     }
 }
+
+/// Whether a block has the `%collapsible` option, which folds it behind its title.
+pub(crate) fn is_collapsible(metadata: &BlockMetadata) -> bool {
+    metadata.options.iter().any(|o| o == "collapsible")
+}
+
+/// Opens the `<details>` element a collapsible block goes in, summarized by the block's
+/// title or by `default` when it has none. The block starts expanded with the `%open`
+/// option. The caller closes the element.
+pub(crate) fn open_details<W: Write>(
+    title: &[InlineNode],
+    default: &str,
+    metadata: &BlockMetadata,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    if metadata.options.iter().any(|o| o == "open") {
+        writeln!(w, "<details open>")?;
+    } else {
+        writeln!(w, "<details>")?;
+    }
+    write!(w, "<summary class=\"title\">")?;
+    if title.is_empty() {
+        write!(w, "{default}")?;
+    } else {
+        crate::inlines::render_inlines(title, w, processor, options)?;
+    }
+    writeln!(w, "</summary>")?;
+    Ok(())
+}
+
+/// Emits a comment block as an HTML comment when the `preserve-comments` document
+/// attribute is set, and drops it otherwise.
+fn render_comment<W: Write>(
+    inlines: &[InlineNode],
+    w: &mut W,
+    processor: &Processor,
+) -> Result<(), crate::Error> {
+    if !processor
+        .document_attributes
+        .borrow()
+        .contains_key("preserve-comments")
+    {
+        return Ok(());
+    }
+    let mut text = inlines
+        .iter()
+        .filter_map(|inline| match inline {
+            InlineNode::PlainText(plain) => Some(plain.content.as_str()),
+            _ => None,
+        })
+        .collect::<String>();
+    // `--` isn't allowed inside an HTML comment, nor is ending it with a `-`. A single
+    // pass would leave `a---b` as `a- --b`.
+    while text.contains("--") {
+        text = text.replace("--", "- -");
+    }
+    if text.ends_with('-') {
+        text.push(' ');
+    }
+    writeln!(w, "<!--\n{text}\n-->")?;
+    Ok(())
+}
+
+/// Replaces the callout markers (`<1>`, `<.>`) at the end of the lines of a verbatim
+/// block with conum badges, along with the line comment they're often hidden behind.
+///
+/// The numbers are kept in the processor so the callout list that follows the block can
+/// be checked against them. `<.>` markers are numbered automatically.
+fn render_callouts(content: &str, processor: &Processor) -> String {
+    let mut numbers: Vec<usize> = Vec::new();
+    let mut result = String::new();
+    for line in content.split_inclusive('\n') {
+        let (line, newline) = line
+            .strip_suffix('\n')
+            .map_or((line, ""), |line| (line, "\n"));
+        let (code, markers) = split_callouts(line);
+        if markers.is_empty() {
+            result.push_str(line);
+        } else {
+            result.push_str(code);
+            for marker in markers {
+                let number = marker.parse().unwrap_or(numbers.len() + 1);
+                if numbers.contains(&number) {
+                    tracing::warn!(number, "duplicate callout in verbatim block");
+                }
+                numbers.push(number);
+                result.push_str(&format!(
+                    " <i class=\"conum\" data-value=\"{number}\"></i><b>({number})</b>"
+                ));
+            }
+        }
+        result.push_str(newline);
+    }
+    processor.callouts.replace(numbers);
+    result
+}
+
+/// Splits the callout markers at the end of a line of rendered verbatim content from its
+/// code, returning what's inside them. Markers are escaped unless the block turned the
+/// special characters substitution off.
+fn split_callouts(line: &str) -> (&str, Vec<&str>) {
+    let mut code = line.trim_end();
+    let mut markers = Vec::new();
+    loop {
+        let marker = code
+            .strip_suffix("&gt;")
+            .and_then(|rest| rest.rfind("&lt;").map(|open| (open, open + 4, rest)))
+            .or_else(|| {
+                code.strip_suffix('>')
+                    .and_then(|rest| rest.rfind('<').map(|open| (open, open + 1, rest)))
+            });
+        let Some((open, start, rest)) = marker else {
+            break;
+        };
+        let inner = &rest[start..];
+        if inner != "." && (inner.is_empty() || !inner.chars().all(|c| c.is_ascii_digit())) {
+            break;
+        }
+        markers.push(inner);
+        code = rest[..open].trim_end();
+    }
+    if markers.is_empty() {
+        return (line, markers);
+    }
+    markers.reverse();
+    if let Some(rest) = ["//", "#", "--", ";;"]
+        .iter()
+        .find_map(|comment| code.strip_suffix(comment))
+    {
+        code = rest.trim_end();
+    }
+    (code, markers)
+}
+
+/// Returns the indentation a source block's lines should be shifted to, if any.
+///
+/// The block's `indent` attribute takes precedence over the `source-indent` document
+/// attribute. Blocks that aren't `[source]` blocks keep their indentation as written.
+fn source_indent(block: &DelimitedBlock, processor: &Processor) -> Option<usize> {
+    if block.metadata.style.as_deref() != Some("source") {
+        return None;
+    }
+    let indent = match block.metadata.attributes.get("indent") {
+        Some(AttributeValue::String(indent)) => indent.clone(),
+        _ => match processor.document_attributes.borrow().get("source-indent") {
+            Some(AttributeValue::String(indent)) => indent.clone(),
+            _ => return None,
+        },
+    };
+    indent.trim().parse().ok()
+}
+
+/// Builds the `class` attribute of the `<pre>` of a verbatim block, which controls how its
+/// lines wrap: `nowrap` keeps long lines from wrapping, for the `%nowrap` option or when
+/// the document unsets `prewrap`, while `break-all` also breaks long words such as URLs,
+/// for the `%break-all` option.
+fn pre_classes(metadata: &BlockMetadata, processor: &Processor) -> Option<String> {
+    let has_option = |name: &str| metadata.options.iter().any(|o| o == name);
+    let mut classes = Vec::new();
+    if has_option("nowrap")
+        || matches!(
+            processor.document_attributes.borrow().get("prewrap"),
+            Some(AttributeValue::Bool(false))
+        )
+    {
+        classes.push("nowrap");
+    }
+    if has_option("break-all") {
+        classes.push("break-all");
+    }
+    (!classes.is_empty()).then(|| classes.join(" "))
+}
+
+/// The number of columns between tab stops in a verbatim block: its `tabsize` attribute,
+/// or the document's. Tabs are left alone when neither is set.
+fn tabsize(block: &DelimitedBlock, processor: &Processor) -> Option<usize> {
+    let tabsize = match block.metadata.attributes.get("tabsize") {
+        Some(AttributeValue::String(tabsize)) => tabsize.clone(),
+        _ => match processor.document_attributes.borrow().get("tabsize") {
+            Some(AttributeValue::String(tabsize)) => tabsize.clone(),
+            _ => return None,
+        },
+    };
+    tabsize.trim().parse().ok().filter(|tabsize| *tabsize > 0)
+}
+
+/// Replaces the tabs in `content` with spaces up to the next tab stop.
+///
+/// Columns are counted in the rendered text, so a character reference such as `&lt;`
+/// takes one and a tag none.
+fn expand_tabs(content: &str, tabsize: usize) -> String {
+    let mut expanded = String::with_capacity(content.len());
+    for line in content.split_inclusive('\n') {
+        let mut column = 0;
+        let mut in_tag = false;
+        let mut in_reference = false;
+        for c in line.chars() {
+            match c {
+                '\t' => {
+                    let spaces = tabsize - column % tabsize;
+                    expanded.extend(std::iter::repeat_n(' ', spaces));
+                    column += spaces;
+                    continue;
+                }
+                '<' => in_tag = true,
+                '>' if in_tag => in_tag = false,
+                '&' if !in_tag => {
+                    in_reference = true;
+                    column += 1;
+                }
+                ';' if in_reference => in_reference = false,
+                _ if in_tag || in_reference => {}
+                _ => column += 1,
+            }
+            expanded.push(c);
+        }
+    }
+    expanded
+}
+
+/// Strips the indentation common to all non-blank lines of `content` and indents every
+/// line by `indent` spaces instead, so relative indentation is preserved.
+///
+/// Unless they were expanded first, tabs count as a single column, like any other
+/// leading whitespace.
+fn reindent(content: &str, indent: usize) -> String {
+    let leading = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let common = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(leading)
+        .min()
+        .unwrap_or(0);
+    let padding = " ".repeat(indent);
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            if line.trim().is_empty() {
+                line.trim_start_matches([' ', '\t']).to_string()
+            } else {
+                format!("{padding}{}", &line[common..])
+            }
+        })
+        .collect()
+}
+
+/// Roles asking for a block to be kept on a single page when printing, like the
+/// `%unbreakable` option does.
+const UNBREAKABLE_ROLES: [&str; 2] = ["avoid-break", "keep-together"];
+
+/// Builds the `class` attribute of a block: its own class, followed by its roles and its
+/// page break class, escaped to be written as an attribute value.
+pub(crate) fn block_classes(name: &str, metadata: &BlockMetadata) -> String {
+    let mut classes = vec![name];
+    classes.extend(metadata.roles.iter().map(String::as_str));
+    classes.extend(break_class(metadata));
+    escape_attribute(&classes.join(" "))
+}
+
+/// Returns the class the print stylesheet controls page breaks with: `unbreakable`,
+/// which keeps a block on a single page, for the `%unbreakable` option and the
+/// `avoid-break` and `keep-together` roles, or `breakable` for the `%breakable` option.
+pub(crate) fn break_class(metadata: &BlockMetadata) -> Option<&'static str> {
+    if metadata.options.iter().any(|o| o == "unbreakable")
+        || metadata
+            .roles
+            .iter()
+            .any(|r| UNBREAKABLE_ROLES.contains(&r.as_str()))
+    {
+        Some("unbreakable")
+    } else if metadata.options.iter().any(|o| o == "breakable") {
+        Some("breakable")
+    } else {
+        None
+    }
+}
+
+/// Wraps every region between a fold start and fold end marker line in a collapsed
+/// `<details>` element, dropping the marker lines themselves.
+///
+/// The markers default to `// <editor-fold` and `// </editor-fold>` and can be changed
+/// with the `fold-start` and `fold-end` block attributes. If the start marker line
+/// carries a `desc="..."`, it's used as the `<summary>`.
+///
+/// `content` has already gone through the special characters substitution, so the
+/// markers are escaped the same way before looking for them.
+fn fold_regions(attributes: &ElementAttributes, content: &str) -> String {
+    let marker = |name: &str, default: &str| match attributes.get(name) {
+        Some(AttributeValue::String(value)) => substitution_text(value),
+        _ => substitution_text(default),
+    };
+    let start = marker("fold-start", DEFAULT_FOLD_START);
+    let end = marker("fold-end", DEFAULT_FOLD_END);
+
+    let mut result = String::new();
+    let mut depth = 0;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with(&start) {
+            let summary = trimmed
+                .split_once("desc=&quot;")
+                .and_then(|(_, rest)| rest.split_once("&quot;"))
+                .map_or("...", |(desc, _)| desc);
+            result.push_str("<details><summary>");
+            result.push_str(summary);
+            result.push_str("</summary>");
+            depth += 1;
+        } else if depth > 0 && trimmed.starts_with(&end) {
+            result.push_str("</details>");
+            depth -= 1;
+        } else {
+            result.push_str(line);
+        }
+    }
+    for _ in 0..depth {
+        result.push_str("</details>");
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_listing_with_fold_markers() {
+        let html = render_string(
+            "[source%fold]\n----\n// <editor-fold desc=\"Imports\">\nuse std::io;\n// </editor-fold>\nfn main() {}\n----\n",
+        );
+        assert!(html.contains(
+            "<pre>\n<details><summary>Imports</summary>use std::io;\n</details>fn main() {}"
+        ));
+        assert!(!html.contains("editor-fold"));
+    }
+
+    #[test]
+    fn test_render_source_indent() {
+        let source = "[source,rust]\n----\n    fn main() {\n        run();\n    }\n----\n";
+        let html = render_string(&format!("= Document\n:source-indent: 0\n\n{source}"));
+        assert!(html.contains("<pre>\nfn main() {\n    run();\n}"));
+
+        let html = render_string(&format!("= Document\n:source-indent: 2\n\n{source}"));
+        assert!(html.contains("<pre>\n  fn main() {\n      run();\n  }"));
+
+        let html = render_string(source);
+        assert!(html.contains("<pre>\n    fn main() {\n        run();\n    }"));
+    }
+
+    #[test]
+    fn test_render_pre_wrapping() {
+        let html = render_string("[source%break-all,text]\n----\ndata:base64,iVBORw0KGgo\n----\n");
+        assert!(html.contains("<pre class=\"break-all\">\ndata:base64,iVBORw0KGgo"));
+
+        let html = render_string("[%nowrap]\n----\nlong line\n----\n");
+        assert!(html.contains("<pre class=\"nowrap\">"));
+
+        let html = render_string("= Document\n:prewrap!:\n\n----\nlong line\n----\n");
+        assert!(html.contains("<pre class=\"nowrap\">"));
+
+        let html = render_string("----\nlong line\n----\n");
+        assert!(html.contains("<pre>\nlong line"));
+    }
+
+    #[test]
+    fn test_render_listing_without_specialchars() {
+        let html = render_string("[subs=\"-specialchars\"]\n----\n<b>bold</b>\n----\n");
+        assert!(html.contains("<pre>\n<b>bold</b>"));
+
+        let html = render_string("[subs=none]\n....\n<b>bold</b>\n....\n");
+        assert!(html.contains("<b>bold</b>"));
+        assert!(!html.contains("&lt;b&gt;"));
+
+        let html = render_string("----\n<b>bold</b>\n----\n");
+        assert!(html.contains("&lt;b&gt;bold&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn test_render_listing_without_callouts() {
+        for subs in ["-callouts", "none"] {
+            let html = render_string(&format!("[subs=\"{subs}\"]\n----\nputs 1 <1>\n----\n"));
+            assert!(!html.contains("<i class=\"conum\""), "{subs}");
+        }
+        let html = render_string("----\nputs 1 <1>\n----\n");
+        assert!(html.contains("<i class=\"conum\" data-value=\"1\">"));
+    }
+
+    #[test]
+    fn test_render_tabsize() {
+        let source = "[source,c]\n----\nint main() {\n\treturn 0;\t/* ok */\n}\n----\n";
+        let html = render_string(&format!("= Document\n:tabsize: 2\n\n{source}"));
+        assert!(html.contains("<pre>\nint main() {\n  return 0; /* ok */\n}"));
+
+        let html = render_string(&source.replace("[source,c]", "[source,c,tabsize=4]"));
+        assert!(html.contains("\n    return 0;   /* ok */\n"));
+
+        let html = render_string(source);
+        assert!(html.contains("\n\treturn 0;\t/* ok */\n"));
+
+        // A character reference takes up a single column.
+        let html = render_string("= Document\n:tabsize: 4\n\n....\na<b\tc\n....\n");
+        assert!(html.contains("a&lt;b c"));
+    }
+
+    #[test]
+    fn test_render_collapsible_blocks() {
+        let html = render_string(".Spoiler\n[%collapsible]\n====\nHidden.\n====\n");
+        assert!(html.contains(
+            "<details>\n<summary class=\"title\">Spoiler</summary>\n<div class=\"content\">"
+        ));
+        assert!(!html.contains("Example 1."));
+
+        let html = render_string(".Aside\n[%collapsible%open]\n****\nSide.\n****\n");
+        assert!(html.contains(
+            "<details open>\n<summary class=\"title\">Aside</summary>\n<div class=\"sidebarblock\">\n<div class=\"content\">\n"
+        ));
+        assert!(html.contains("</div>\n</div>\n</details>"));
+    }
+
+    #[test]
+    fn test_render_float_group() {
+        let html = render_string(
+            "[.float-group]\n--\n[.left]\nimage::a.png[A]\n\nimage::b.png[B,float=right]\n--\n",
+        );
+        assert!(html.contains("<div class=\"openblock float-group\">\n<div class=\"content\">\n<div class=\"imageblock left\">"));
+        assert!(html.contains("<div class=\"imageblock right\">"));
+    }
+
+    #[test]
+    fn test_render_unbreakable_example() {
+        let html = render_string(".Together\n[%unbreakable]\n====\nKeep me whole.\n====\n");
+        assert!(html.contains(
+            "<div class=\"exampleblock unbreakable\">\n<div class=\"title\">Example 1. Together</div>"
+        ));
+    }
+
+    #[test]
+    fn test_render_comment_block() {
+        let content = "Before.\n\n////\nA comment\n////\n\nAfter.\n";
+        let html = render_string(content);
+        assert!(!html.contains("A comment"));
+
+        let html = render_string(&format!("= Document\n:preserve-comments:\n\n{content}"));
+        assert!(html.contains("<!--\nA comment\n-->"));
+
+        let html = render_string("= Document\n:preserve-comments:\n\n////\na---b -\n////\n");
+        assert!(html.contains("<!--\na- - -b - \n-->"));
+    }
+
+    #[test]
+    fn test_render_example_role_and_caption() {
+        let html = render_string(
+            ".First\n====\nOne.\n====\n\n.Second\n[.tip,caption=\"Recipe A: \"]\n====\nTwo.\n====\n",
+        );
+        assert!(html.contains("<div class=\"title\">Example 1. First</div>"));
+        assert!(html.contains(
+            "<div class=\"exampleblock tip\">\n<div class=\"title\">Recipe A: Second</div>"
+        ));
+    }
+
+    #[test]
+    fn test_render_block_roles_escaped() {
+        let html = render_string("[.x\"y]\n....\nliteral\n....\n");
+        assert!(html.contains("<div class=\"literalblock x&quot;y\">"));
+
+        let html = render_string("[.a<b]\n====\nExample.\n====\n");
+        assert!(html.contains("<div class=\"exampleblock a&lt;b\">"));
+    }
+}
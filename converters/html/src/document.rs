@@ -1,9 +1,13 @@
 use std::io::Write;
 
-use acdc_parser::{Author, Block, Document, Header};
+use acdc_parser::{AttributeValue, Author, Block, Document, DocumentAttributes, Header};
 
 use crate::{Processor, Render, RenderOptions};
 
+const DEFAULT_WEBFONTS: &str = "Open+Sans:300,300italic,400,400italic,600,600italic%7CNoto+Serif:400,400italic,700,700italic%7CDroid+Sans+Mono:400,700";
+const DEFAULT_ICONFONT_CDN: &str =
+    "https://cdnjs.cloudflare.com/ajax/libs/font-awesome/4.7.0/css/font-awesome.min.css";
+
 impl Render for Document {
     type Error = crate::Error;
 
@@ -13,6 +17,24 @@ impl Render for Document {
         processor: &Processor,
         options: &RenderOptions,
     ) -> Result<(), Self::Error> {
+        processor.table_counter.set(0);
+        processor.example_counter.set(0);
+        processor.figure_counter.set(0);
+        processor.equation_counter.set(0);
+        processor.section_numbers.take();
+        processor.callouts.take();
+        processor
+            .document_attributes
+            .replace(self.header_attributes().clone());
+        processor
+            .equations
+            .replace(crate::stem::collect_equations(self));
+        processor
+            .references
+            .replace(crate::xref::collect_references(self, processor, options)?);
+        let mut toc = Vec::new();
+        crate::toc::render(self, "macro", &mut toc, processor, options)?;
+        processor.toc.replace(String::from_utf8(toc)?);
         writeln!(w, "<!DOCTYPE html>")?;
         writeln!(w, "<html>")?;
         writeln!(w, "<head>")?;
@@ -36,12 +58,19 @@ impl Render for Document {
                 },
             )?;
         }
-        writeln!(w, "<link rel=\"stylesheet\" href=\"https://fonts.googleapis.com/css?family=Open+Sans:300,300italic,400,400italic,600,600italic%7CNoto+Serif:400,400italic,700,700italic%7CDroid+Sans+Mono:400,700\">")?;
-        writeln!(w, "<style>")?;
-        writeln!(w, "{}", include_str!("../static/asciidoctor.css"))?;
-        writeln!(w, "</style>")?;
+        render_app_links(&self.attributes, w)?;
+        render_font_links(&self.attributes, w)?;
+        crate::stylesheet::render(&self.attributes, w, processor, options)?;
+        render_role_styles(&self.attributes, w)?;
         writeln!(w, "</head>")?;
-        writeln!(w, "<body class=\"{}\">", processor.config.doctype)?;
+        match crate::toc::position(&self.attributes) {
+            Some(position) if crate::toc::is_sidebar(position) => writeln!(
+                w,
+                "<body class=\"{} toc2 toc-{position}\">",
+                processor.doctype()
+            )?,
+            _ => writeln!(w, "<body class=\"{}\">", processor.doctype())?,
+        }
         writeln!(w, "<div id=\"header\">")?;
         if let Some(header) = &self.header {
             if !header.title.is_empty() {
@@ -87,49 +116,176 @@ impl Render for Document {
                 writeln!(w, "</div>")?;
             }
         }
+        crate::toc::render(self, "auto", w, processor, options)?;
         writeln!(w, "</div>")?;
+        // Titles rendered for the table of contents and cross references above don't
+        // count as footnote references, those in the body do.
+        processor.footnotes.take();
         writeln!(w, "<div id=\"content\">")?;
-        let mut blocks = self.blocks.clone();
-        let preamble = find_preamble(&mut blocks);
-        if let Some(preamble) = preamble {
+        let (preamble, blocks) = self.blocks.split_at(preamble_len(&self.blocks));
+        if !preamble.is_empty() {
             writeln!(w, "<div id=\"preamble\">")?;
             writeln!(w, "<div class=\"sectionbody\">")?;
-            for block in &preamble {
+            for block in preamble {
                 block.render(w, processor, options)?;
             }
             writeln!(w, "</div>")?;
+            crate::toc::render(self, "preamble", w, processor, options)?;
             writeln!(w, "</div>")?;
         }
-        for block in &blocks {
+        for block in blocks {
             block.render(w, processor, options)?;
         }
         writeln!(w, "</div>")?;
+        crate::footnote::render_footnotes(self, w)?;
         writeln!(w, "<div id=\"footer\">")?;
         writeln!(w, "<div id=\"footer-text\">")?;
+        if let Some(AttributeValue::String(minutes)) = self.attributes.get("reading-time") {
+            writeln!(w, "~{minutes} min read<br>")?;
+        }
         if let Some(last_updated) = options.last_updated {
             writeln!(w, "Last updated {}", last_updated.format("%F %T %Z"))?;
         }
         writeln!(w, "</div>")?;
         writeln!(w, "</div>")?;
+        crate::stem::render_math_script(self, w, processor)?;
         writeln!(w, "</body>")?;
         writeln!(w, "</html>")?;
         Ok(())
     }
 }
 
-fn find_preamble(blocks: &mut Vec<Block>) -> Option<Vec<Block>> {
-    let mut first_section_index = 0;
-    for (index, block) in blocks.iter().enumerate() {
-        if let Block::Section(_) = block {
-            first_section_index = index;
-            break;
+/// Writes the head entries used when the document is served as a web app: the
+/// `application-name` and `theme-color` meta tags for `app-name` and `theme-color`, and
+/// the `manifest` link.
+fn render_app_links<W: Write>(
+    attributes: &DocumentAttributes,
+    w: &mut W,
+) -> Result<(), crate::Error> {
+    let string = |name: &str| match attributes.get(name) {
+        Some(AttributeValue::String(value)) if !value.is_empty() => {
+            Some(crate::inlines::escape_attribute(value))
         }
+        _ => None,
+    };
+    if let Some(name) = string("app-name") {
+        writeln!(w, "<meta name=\"application-name\" content=\"{name}\">")?;
+    }
+    if let Some(color) = string("theme-color") {
+        writeln!(w, "<meta name=\"theme-color\" content=\"{color}\">")?;
+    }
+    if let Some(manifest) = string("manifest") {
+        writeln!(w, "<link rel=\"manifest\" href=\"{manifest}\">")?;
+    }
+    Ok(())
+}
+
+/// Writes the stylesheet links for the web fonts and, with `:icons: font`, the icon font.
+///
+/// Web fonts are loaded from Google Fonts unless `webfonts` is unset (a value replaces
+/// the font families requested). The icon font comes from its CDN (or `iconfont-cdn`)
+/// unless `iconfont-remote` is unset or `false`, in which case the stylesheet is linked
+/// locally as `{stylesdir}/{iconfont-name}.css`.
+fn render_font_links<W: Write>(
+    attributes: &DocumentAttributes,
+    w: &mut W,
+) -> Result<(), crate::Error> {
+    let string = |name: &str| match attributes.get(name) {
+        Some(AttributeValue::String(value)) if !value.is_empty() => {
+            Some(crate::inlines::escape_attribute(value))
+        }
+        _ => None,
+    };
+    match attributes.get("webfonts") {
+        Some(AttributeValue::Bool(false)) => {}
+        _ => writeln!(
+            w,
+            "<link rel=\"stylesheet\" href=\"https://fonts.googleapis.com/css?family={}\">",
+            string("webfonts").as_deref().unwrap_or(DEFAULT_WEBFONTS)
+        )?,
+    }
+
+    if string("icons").as_deref() != Some("font") {
+        return Ok(());
     }
-    if first_section_index > 0 {
-        Some(blocks.drain(..first_section_index).collect::<Vec<_>>())
+    let remote = match attributes.get("iconfont-remote") {
+        Some(AttributeValue::Bool(false)) => false,
+        Some(AttributeValue::String(value)) => value != "false",
+        _ => true,
+    };
+    if remote {
+        writeln!(
+            w,
+            "<link rel=\"stylesheet\" href=\"{}\">",
+            string("iconfont-cdn")
+                .as_deref()
+                .unwrap_or(DEFAULT_ICONFONT_CDN)
+        )?;
     } else {
-        None
+        writeln!(
+            w,
+            "<link rel=\"stylesheet\" href=\"{}/{}.css\">",
+            string("stylesdir").as_deref().unwrap_or("."),
+            string("iconfont-name").as_deref().unwrap_or("font-awesome")
+        )?;
     }
+    Ok(())
+}
+
+/// Collects the `role-<name>-color` and `role-<name>-bg` document attributes into CSS
+/// custom properties, plus one rule per role that references them.
+///
+/// This lets `:role-danger-color: red` style every `[.danger]#text#` in the document
+/// without having to ship a separate stylesheet. Roles that aren't CSS identifiers and
+/// values that could break out of their declaration are skipped.
+fn render_role_styles<W: Write>(
+    attributes: &DocumentAttributes,
+    w: &mut W,
+) -> Result<(), crate::Error> {
+    let mut roles = Vec::new();
+    for (name, value) in attributes.iter() {
+        let AttributeValue::String(value) = value else {
+            continue;
+        };
+        let Some(rest) = name.strip_prefix("role-") else {
+            continue;
+        };
+        let (role, suffix, property) = if let Some(role) = rest.strip_suffix("-color") {
+            (role, "color", "color")
+        } else if let Some(role) = rest.strip_suffix("-bg") {
+            (role, "bg", "background-color")
+        } else {
+            continue;
+        };
+        if !is_css_identifier(role) || value.contains([';', '{', '}', '<', '>']) {
+            tracing::warn!(name, value, "ignoring role style that isn't valid CSS");
+            continue;
+        }
+        roles.push((role, suffix, property, value));
+    }
+    if roles.is_empty() {
+        return Ok(());
+    }
+    writeln!(w, "<style>")?;
+    writeln!(w, ":root {{")?;
+    for (role, suffix, _, value) in &roles {
+        writeln!(w, "  --role-{role}-{suffix}: {value};")?;
+    }
+    writeln!(w, "}}")?;
+    for (role, suffix, property, _) in &roles {
+        writeln!(w, ".{role} {{ {property}: var(--role-{role}-{suffix}); }}")?;
+    }
+    writeln!(w, "</style>")?;
+    Ok(())
+}
+
+/// Number of blocks before the first section, which make up the preamble. There's no
+/// preamble without sections.
+fn preamble_len(blocks: &[Block]) -> usize {
+    blocks
+        .iter()
+        .position(|block| matches!(block, Block::Section(_)))
+        .unwrap_or(0)
 }
 
 impl Render for Header {
@@ -174,3 +330,93 @@ impl Render for Author {
         Ok(())
     }
 }
+
+/// Whether `name` can be used as is as a CSS class selector: letters, digits, `-` and
+/// `_`, not starting with a digit or with a `-` followed by a digit.
+fn is_css_identifier(name: &str) -> bool {
+    let rest = name.strip_prefix('-').unwrap_or(name);
+    rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_role_styles() {
+        let html = render_string("= Title\n:role-danger-color: red\n\nThis is [.danger]#x#.\n");
+        assert!(html.contains("--role-danger-color: red;"));
+        assert!(html.contains(".danger { color: var(--role-danger-color); }"));
+        assert!(html.contains("<span class=\"danger\">x</span>"));
+    }
+
+    #[test]
+    fn test_render_role_styles_rejects_invalid_css() {
+        let html = render_string(
+            "= Title\n:role-1x-color: red\n:role-ok-bg: red;}</style><script>\n:role-fine-color: blue\n\nText.\n",
+        );
+        assert!(!html.contains(".1x"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains(".fine { color: var(--role-fine-color); }"));
+
+        let html = render_string("[role=a\"b]#x#\n");
+        assert!(html.contains("<span class=\"a&quot;b\">x</span>"));
+    }
+
+    #[test]
+    fn test_render_manifest_link() {
+        let html = render_string(
+            "= Site\n:manifest: site.webmanifest\n:theme-color: #1e1e1e\n:app-name: Docs\n\nHello.\n",
+        );
+        assert!(html.contains("<link rel=\"manifest\" href=\"site.webmanifest\">"));
+        assert!(html.contains("<meta name=\"theme-color\" content=\"#1e1e1e\">"));
+        assert!(html.contains("<meta name=\"application-name\" content=\"Docs\">"));
+
+        let html = render_string(
+            "= Site\n:manifest: a\"b.json\n:theme-color: <red>\n:app-name: Tom & Jerry\n\nHello.\n",
+        );
+        assert!(html.contains("<link rel=\"manifest\" href=\"a&quot;b.json\">"));
+        assert!(html.contains("<meta name=\"theme-color\" content=\"&lt;red&gt;\">"));
+        assert!(html.contains("<meta name=\"application-name\" content=\"Tom &amp; Jerry\">"));
+
+        let html = render_string("= Site\n\nHello.\n");
+        assert!(!html.contains("rel=\"manifest\""));
+    }
+
+    #[test]
+    fn test_render_local_iconfont() {
+        let html = render_string(
+            "= Document\n:icons: font\n:iconfont-remote: false\n:!webfonts:\n\nNOTE: Offline.\n",
+        );
+        assert!(!html.contains("href=\"https://cdnjs.cloudflare.com"));
+        assert!(!html.contains("href=\"https://fonts.googleapis.com"));
+        assert!(html.contains("<link rel=\"stylesheet\" href=\"./font-awesome.css\">"));
+        assert!(html.contains("<i class=\"fa fa-info-circle\" title=\"Note\"></i>"));
+
+        let html = render_string("= Document\n:icons: font\n\nNOTE: Online.\n");
+        assert!(html.contains("font-awesome/4.7.0/css/font-awesome.min.css"));
+        assert!(html.contains("href=\"https://fonts.googleapis.com"));
+
+        let html = render_string(
+            "= Document\n:icons: font\n:iconfont-remote: false\n:stylesdir: a\"b\n:iconfont-name: <fa>\n:webfonts: Lato&x\n\nNOTE: Offline.\n",
+        );
+        assert!(html.contains("<link rel=\"stylesheet\" href=\"a&quot;b/&lt;fa&gt;.css\">"));
+        assert!(html.contains("css?family=Lato&amp;x\">"));
+
+        let html =
+            render_string("= Document\n:icons: font\n:iconfont-cdn: x\"y\n\nNOTE: Online.\n");
+        assert!(html.contains("<link rel=\"stylesheet\" href=\"x&quot;y\">"));
+    }
+
+    #[test]
+    fn test_render_reading_time_in_footer() {
+        let html = render_string("= Post\n:reading-time:\n\nA short read.\n");
+        assert!(html.contains("<div id=\"footer-text\">\n~1 min read<br>\n</div>"));
+
+        let html = render_string("= Post\n\nA short read.\n");
+        assert!(!html.contains("min read"));
+    }
+}
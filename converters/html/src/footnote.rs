@@ -0,0 +1,99 @@
+use std::io::Write;
+
+use acdc_parser::{Document, Footnote};
+
+use crate::{inlines::substitution_text, Processor, Render, RenderOptions};
+
+impl Render for Footnote {
+    type Error = crate::Error;
+
+    fn render<W: Write>(
+        &self,
+        w: &mut W,
+        processor: &Processor,
+        _options: &RenderOptions,
+    ) -> Result<(), Self::Error> {
+        let number = self.number;
+        if number == 0 {
+            let name = self.id.as_deref().unwrap_or_default();
+            write!(
+                w,
+                "<sup class=\"footnoteref red\" title=\"Unresolved footnote reference.\">[{name}]</sup>"
+            )?;
+            return Ok(());
+        }
+        // The first time a footnote shows up is the one its definition at the bottom
+        // links back to, any later reference to it only links to the definition.
+        if processor.footnotes.borrow_mut().insert(number) {
+            write!(w, "<sup class=\"footnote\"")?;
+            if let Some(id) = &self.id {
+                write!(w, " id=\"_footnote_{id}\"")?;
+            }
+            write!(
+                w,
+                ">[<a id=\"_footnoteref_{number}\" class=\"footnote\" href=\"#_footnotedef_{number}\" title=\"View footnote.\">{number}</a>]</sup>"
+            )?;
+        } else {
+            write!(
+                w,
+                "<sup class=\"footnoteref\">[<a class=\"footnote\" href=\"#_footnotedef_{number}\" title=\"View footnote.\">{number}</a>]</sup>"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the text of every footnote of `document`, numbered and linking back to where
+/// it's first referenced.
+pub(crate) fn render_footnotes<W: Write>(
+    document: &Document,
+    w: &mut W,
+) -> Result<(), crate::Error> {
+    if document.footnotes().is_empty() {
+        return Ok(());
+    }
+    writeln!(w, "<div id=\"footnotes\">")?;
+    writeln!(w, "<hr>")?;
+    for footnote in document.footnotes() {
+        let number = footnote.number;
+        writeln!(w, "<div class=\"footnote\" id=\"_footnotedef_{number}\">")?;
+        writeln!(
+            w,
+            "<a href=\"#_footnoteref_{number}\">{number}</a>. {}",
+            substitution_text(footnote.text.as_deref().unwrap_or_default())
+        )?;
+        writeln!(w, "</div>")?;
+    }
+    writeln!(w, "</div>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_footnotes() {
+        let html = render_string(
+            "Rust.footnote:[A language.] Pest.footnote:parser[A parser.]\n\nAgain.footnote:parser[]\n",
+        );
+        assert!(html.contains(
+            "Rust.<sup class=\"footnote\">[<a id=\"_footnoteref_1\" class=\"footnote\" href=\"#_footnotedef_1\" title=\"View footnote.\">1</a>]</sup>"
+        ));
+        assert!(html.contains(
+            "Pest.<sup class=\"footnote\" id=\"_footnote_parser\">[<a id=\"_footnoteref_2\" class=\"footnote\" href=\"#_footnotedef_2\" title=\"View footnote.\">2</a>]</sup>"
+        ));
+        assert!(html.contains(
+            "Again.<sup class=\"footnoteref\">[<a class=\"footnote\" href=\"#_footnotedef_2\" title=\"View footnote.\">2</a>]</sup>"
+        ));
+        assert!(html.contains(
+            "</div>\n<div id=\"footnotes\">\n<hr>\n<div class=\"footnote\" id=\"_footnotedef_1\">\n<a href=\"#_footnoteref_1\">1</a>. A language.\n</div>\n<div class=\"footnote\" id=\"_footnotedef_2\">\n<a href=\"#_footnoteref_2\">2</a>. A parser.\n</div>\n</div>\n<div id=\"footer\">"
+        ));
+    }
+
+    #[test]
+    fn test_render_without_footnotes() {
+        let html = render_string("No notes here.\n");
+        assert!(!html.contains("<div id=\"footnotes\">"));
+    }
+}
@@ -0,0 +1,305 @@
+use std::{io::Write, path::Path};
+
+use acdc_converters_common::SafeMode;
+use acdc_parser::{AttributeValue, Image, ImageSource};
+
+use crate::{inlines::escape_attribute, Processor, Render, RenderOptions};
+
+/// Attributes that, when present on an image, are passed through as-is to the `<img>`
+/// element.
+const PASSTHROUGH_ATTRIBUTES: &[&str] = &["width", "height", "srcset", "sizes"];
+
+/// SVG images up to this size are embedded as (percent-encoded) text rather than base64,
+/// which keeps them smaller and readable.
+const SVG_TEXT_LIMIT: usize = 4096;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl Render for Image {
+    type Error = crate::Error;
+
+    fn render<W: Write>(
+        &self,
+        w: &mut W,
+        processor: &Processor,
+        options: &RenderOptions,
+    ) -> Result<(), Self::Error> {
+        match &self.source {
+            ImageSource::Url(url) => write!(w, "<img src=\"{url}\"")?,
+            ImageSource::Path(path) => match data_uri(path, processor, options) {
+                Some(uri) => write!(w, "<img src=\"{uri}\"")?,
+                None => write!(w, "<img src=\"{path}\"")?,
+            },
+        }
+        if self.is_decorative() {
+            write!(w, " alt=\"\" role=\"presentation\"")?;
+        } else if let Some(AttributeValue::String(alt)) = self.metadata.attributes.get("alt") {
+            write!(w, " alt=\"{}\"", escape_attribute(alt))?;
+        } else if !self.title.is_empty() {
+            write!(w, " alt=\"",)?;
+            self.title
+                .iter()
+                .try_for_each(|node| node.render(w, processor, options))?;
+            write!(w, "\"")?;
+        }
+        for name in PASSTHROUGH_ATTRIBUTES {
+            if let Some(AttributeValue::String(value)) = self.metadata.attributes.get(name) {
+                write!(w, " {name}=\"{}\"", escape_attribute(value))?;
+            }
+        }
+        write!(w, ">")?;
+        Ok(())
+    }
+}
+
+/// Returns the contents of a local image as a data URI when the `data-uri` document
+/// attribute is set, so the HTML doesn't depend on the image file.
+///
+/// Embedding isn't allowed in the secure safe mode, nor for images outside the document
+/// directory short of the unsafe mode. Images that can't be read, or whose type can't be
+/// told from their extension, are left as links with a warning.
+fn data_uri(path: &str, processor: &Processor, options: &RenderOptions) -> Option<String> {
+    if !processor
+        .document_attributes
+        .borrow()
+        .contains_key("data-uri")
+    {
+        return None;
+    }
+    if matches!(processor.config.safe_mode, SafeMode::Secure) {
+        tracing::warn!(path, "data-uri is not allowed in secure mode");
+        return None;
+    }
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    let mime = match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        _ => {
+            tracing::warn!(path, "unknown image type, not embedding it");
+            return None;
+        }
+    };
+    let file = crate::resolve_path(path, processor, options)?;
+    let contents = match std::fs::read(&file) {
+        Ok(contents) => contents,
+        Err(e) => {
+            tracing::warn!(?file, "failed to read image to embed it: {e}");
+            return None;
+        }
+    };
+    if mime == "image/svg+xml" && contents.len() <= SVG_TEXT_LIMIT {
+        if let Ok(svg) = std::str::from_utf8(&contents) {
+            return Some(format!("data:{mime};utf8,{}", percent_encode(svg)));
+        }
+    }
+    Some(format!("data:{mime};base64,{}", base64(&contents)))
+}
+
+/// Escapes the characters that can't appear as-is in a `utf8` data URI inside an HTML
+/// attribute.
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '%' => encoded.push_str("%25"),
+            '#' => encoded.push_str("%23"),
+            '"' => encoded.push_str("%22"),
+            '&' => encoded.push_str("%26"),
+            '<' => encoded.push_str("%3C"),
+            '>' => encoded.push_str("%3E"),
+            '\n' => encoded.push_str("%0A"),
+            '\r' => {}
+            c => encoded.push(c),
+        }
+    }
+    encoded
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | u32::from(*byte) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(char::from(
+                    BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Renders a block image (`image::target[]`), wrapping the `<img>` element the same way
+/// asciidoctor does.
+pub(crate) fn render_block<W: Write>(
+    image: &Image,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    let mut classes = vec!["imageblock"];
+    if let Some(AttributeValue::String(float)) = image.metadata.attributes.get("float") {
+        classes.push(float);
+    }
+    classes.extend(image.metadata.roles.iter().map(String::as_str));
+    classes.extend(crate::delimited::break_class(&image.metadata));
+    writeln!(
+        w,
+        "<div{} class=\"{}\">",
+        crate::xref::id_attribute(&image.metadata),
+        escape_attribute(&classes.join(" "))
+    )?;
+    writeln!(w, "<div class=\"content\">")?;
+    image.render(w, processor, options)?;
+    writeln!(w)?;
+    writeln!(w, "</div>")?;
+    if !image.title.is_empty() {
+        write!(w, "<div class=\"title\">")?;
+        let caption = crate::caption::prefix(
+            "figure",
+            "Figure",
+            &image.metadata.attributes,
+            &processor.figure_counter,
+            processor,
+        );
+        write!(w, "{caption}")?;
+        crate::inlines::render_inlines(&image.title, w, processor, options)?;
+        writeln!(w, "</div>")?;
+    }
+    writeln!(w, "</div>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use acdc_converters_common::{Config, Processable, SafeMode, Source};
+
+    use crate::{render_string, Processor};
+
+    #[test]
+    fn test_render_image_with_srcset() {
+        let html =
+            render_string("image::photo.png[Photo,srcset=\"a.png 1x, b.png 2x\",sizes=\"50vw\"]\n");
+        assert!(html.contains(
+            "<img src=\"photo.png\" alt=\"Photo\" srcset=\"a.png 1x, b.png 2x\" sizes=\"50vw\">"
+        ));
+
+        let html = render_string("image::a.png[alt=\"\",srcset=\"a--b.png 1x, c -- d.png 2x\"]\n");
+        assert!(html.contains("<img src=\"a.png\" alt=\"\" srcset=\"a--b.png 1x, c -- d.png 2x\">"));
+    }
+
+    #[test]
+    fn test_render_decorative_image() {
+        let html = render_string("image::divider.png[Divider,role=decorative]\n");
+        assert!(html.contains("<img src=\"divider.png\" alt=\"\" role=\"presentation\">"));
+
+        let html = render_string("image::chart.png[Sales chart]\n");
+        assert!(html.contains("<img src=\"chart.png\" alt=\"Sales chart\">"));
+    }
+
+    #[test]
+    fn test_render_image_roles_escaped() {
+        let html = render_string("[.x\"y]\nimage::chart.png[Chart]\n");
+        assert!(html.contains("<div class=\"imageblock x&quot;y\">"));
+    }
+
+    #[test]
+    fn test_render_figure_captions_across_sections() {
+        let html = render_string(
+            "= Document\n\n== One\n\n.First\nimage::a.png[A]\n\n.Numbers\n|===\n|1\n|===\n\n== Two\n\n.Second\nimage::b.png[B]\n\n.More numbers\n|===\n|2\n|===\n",
+        );
+        assert!(html.contains("<div class=\"title\">Figure 1. First</div>"));
+        assert!(html.contains("<div class=\"title\">Figure 2. Second</div>"));
+        assert!(html.contains("<caption class=\"title\">Table 1. Numbers</caption>"));
+        assert!(html.contains("<caption class=\"title\">Table 2. More numbers</caption>"));
+
+        let html = render_string(
+            "= Document\n:figure-caption: Fig.\n:table-caption!:\n\n.First\nimage::a.png[A]\n\n.Numbers\n|===\n|1\n|===\n",
+        );
+        assert!(html.contains("<div class=\"title\">Fig. 1. First</div>"));
+        assert!(html.contains("<caption class=\"title\">Numbers</caption>"));
+    }
+
+    #[test]
+    fn test_render_data_uri_images() {
+        let dir = std::env::temp_dir().join(format!("acdc-data-uri-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("dot.png"), b"\x89PNG\r\n").unwrap();
+        std::fs::write(dir.join("box.svg"), "<svg><rect fill=\"#fff\"/></svg>").unwrap();
+        let path = dir.display();
+        let content = format!(
+            "= Document\n:data-uri:\n\nimage::{path}/dot.png[Dot]\n\nimage::{path}/box.svg[Box]\n\nimage::{path}/missing.png[Missing]\n"
+        );
+
+        let html = render_string(&content);
+        assert!(html.contains("<img src=\"data:image/png;base64,iVBORw0K\" alt=\"Dot\">"));
+        assert!(html.contains(
+            "<img src=\"data:image/svg+xml;utf8,%3Csvg%3E%3Crect fill=%22%23fff%22/%3E%3C/svg%3E\" alt=\"Box\">"
+        ));
+        assert!(html.contains(&format!("<img src=\"{path}/missing.png\" alt=\"Missing\">")));
+
+        let html = Processor::new(Config {
+            source: Source::String(content),
+            safe_mode: SafeMode::Secure,
+            ..Config::default()
+        })
+        .output()
+        .unwrap();
+        assert!(html.contains(&format!("<img src=\"{path}/dot.png\" alt=\"Dot\">")));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_data_uri_images_outside_docdir() {
+        let dir = std::env::temp_dir().join(format!("acdc-data-uri-jail-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        std::fs::write(dir.join("docs/inside.png"), b"\x89PNG\r\n").unwrap();
+        std::fs::write(dir.join("outside.png"), b"\x89PNG\r\n").unwrap();
+        let file = dir.join("docs/document.adoc");
+        let outside = dir.join("outside.png");
+        std::fs::write(
+            &file,
+            format!(
+                "= Document\n:data-uri:\n\nimage::inside.png[Inside]\n\nimage::../outside.png[Up]\n\nimage::{}[Absolute]\n",
+                outside.display()
+            ),
+        )
+        .unwrap();
+        let processor = |safe_mode| {
+            Processor::new(Config {
+                source: Source::Files(vec![file.clone()]),
+                safe_mode,
+                ..Config::default()
+            })
+            .output()
+            .unwrap()
+        };
+
+        let html = processor(SafeMode::Unsafe);
+        assert!(html.contains("alt=\"Up\"") && !html.contains("src=\"../outside.png\""));
+
+        for safe_mode in [SafeMode::Safe, SafeMode::Server] {
+            let html = processor(safe_mode);
+            assert!(html.contains("<img src=\"data:image/png;base64,iVBORw0K\" alt=\"Inside\">"));
+            assert!(html.contains("<img src=\"../outside.png\" alt=\"Up\">"));
+            assert!(html.contains(&format!(
+                "<img src=\"{}\" alt=\"Absolute\">",
+                outside.display()
+            )));
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,7 +1,7 @@
 use std::io::Write;
 
 use acdc_parser::{
-    AttributeValue, Image, ImageSource, InlineMacro, InlineNode, Link, Pass, PassthroughKind,
+    AttributeValue, Autolink, InlineMacro, InlineNode, Keyboard, Link, Menu, Pass, PassthroughKind,
     Substitution, Url,
 };
 
@@ -18,15 +18,33 @@ impl Render for InlineNode {
     ) -> Result<(), Self::Error> {
         match self {
             InlineNode::PlainText(p) => {
-                let text = substitution_text(&p.content);
-                write!(w, "{text}")?;
+                let text = if options.raw_text {
+                    p.content.clone()
+                } else {
+                    substitution_text(&p.content)
+                };
+                if options.hardbreaks {
+                    write!(w, "{}", text.replace('\n', "<br>\n"))?;
+                } else {
+                    write!(w, "{text}")?;
+                }
+            }
+            InlineNode::LineBreak(_) => writeln!(w, "<br>")?,
+            InlineNode::InlineAnchor(anchor) => {
+                if !options.inlines_basic {
+                    write!(w, "<a id=\"{}\"></a>", escape_attribute(&anchor.id))?;
+                }
             }
             InlineNode::RawText(r) => {
                 write!(w, "{}", r.content)?;
             }
             InlineNode::BoldText(b) => {
                 if !options.inlines_basic {
-                    write!(w, "<strong>")?;
+                    write!(
+                        w,
+                        "<strong{}>",
+                        span_attributes(b.role.as_ref(), b.id.as_ref())
+                    )?;
                 }
                 for inline in &b.content {
                     inline.render(w, processor, options)?;
@@ -37,7 +55,7 @@ impl Render for InlineNode {
             }
             InlineNode::ItalicText(i) => {
                 if !options.inlines_basic {
-                    write!(w, "<em>")?;
+                    write!(w, "<em{}>", span_attributes(i.role.as_ref(), i.id.as_ref()))?;
                 }
                 for inline in &i.content {
                     inline.render(w, processor, options)?;
@@ -48,7 +66,11 @@ impl Render for InlineNode {
             }
             InlineNode::MonospaceText(m) => {
                 if !options.inlines_basic {
-                    write!(w, "<code>")?;
+                    write!(
+                        w,
+                        "<code{}>",
+                        span_attributes(m.role.as_ref(), m.id.as_ref())
+                    )?;
                 }
                 for inline in &m.content {
                     inline.render(w, processor, options)?;
@@ -57,6 +79,26 @@ impl Render for InlineNode {
                     write!(w, "</code>")?;
                 }
             }
+            InlineNode::HighlightText(h) => {
+                if !options.inlines_basic {
+                    let attributes = span_attributes(h.role.as_ref(), h.id.as_ref());
+                    if h.role.is_some() {
+                        write!(w, "<span{attributes}>")?;
+                    } else {
+                        write!(w, "<mark{attributes}>")?;
+                    }
+                }
+                for inline in &h.content {
+                    inline.render(w, processor, options)?;
+                }
+                if !options.inlines_basic {
+                    if h.role.is_some() {
+                        write!(w, "</span>")?;
+                    } else {
+                        write!(w, "</mark>")?;
+                    }
+                }
+            }
             InlineNode::Macro(m) => m.render(w, processor, options)?,
             unknown => todo!("inlines: {:?}", unknown),
         };
@@ -64,6 +106,18 @@ impl Render for InlineNode {
     }
 }
 
+/// Builds the `class` and `id` attributes given to quoted text, with a leading space.
+fn span_attributes(role: Option<&String>, id: Option<&String>) -> String {
+    let mut attributes = String::new();
+    if let Some(role) = role {
+        attributes.push_str(&format!(" class=\"{}\"", escape_attribute(role)));
+    }
+    if let Some(id) = id {
+        attributes.push_str(&format!(" id=\"{}\"", escape_attribute(id)));
+    }
+    attributes
+}
+
 impl Render for InlineMacro {
     type Error = crate::Error;
 
@@ -78,6 +132,12 @@ impl Render for InlineMacro {
             InlineMacro::Image(i) => i.render(w, processor, options),
             InlineMacro::Pass(p) => p.render(w, processor, options),
             InlineMacro::Url(u) => u.render(w, processor, options),
+            InlineMacro::Autolink(a) => a.render(w, processor, options),
+            InlineMacro::Stem(s) => s.render(w, processor, options),
+            InlineMacro::Menu(m) => m.render(w, processor, options),
+            InlineMacro::Keyboard(k) => k.render(w, processor, options),
+            InlineMacro::CrossReference(x) => x.render(w, processor, options),
+            InlineMacro::Footnote(f) => f.render(w, processor, options),
             unknown => todo!("inline macro: {:?}", unknown),
         }
     }
@@ -100,7 +160,11 @@ impl Render for Link {
         if options.inlines_basic {
             write!(w, "{text}")?;
         } else {
-            write!(w, "<a href=\"{}\">{text}</a>", self.target)?;
+            write!(
+                w,
+                "<a href=\"{}\">{text}</a>",
+                self.target.replace('&', "&amp;")
+            )?;
         }
         Ok(())
     }
@@ -137,31 +201,94 @@ impl Render for Url {
     }
 }
 
-impl Render for Image {
+impl Render for Autolink {
     type Error = crate::Error;
 
     fn render<W: Write>(
         &self,
         w: &mut W,
-        processor: &Processor,
+        _processor: &Processor,
         options: &RenderOptions,
     ) -> Result<(), Self::Error> {
+        // A bare URL links to itself, classed `bare` like Asciidoctor does.
+        let url = escape_attribute(&self.url);
+        if options.inlines_basic {
+            write!(w, "{url}")?;
+        } else {
+            write!(w, "<a href=\"{url}\" class=\"bare\">{url}</a>")?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for Menu {
+    type Error = crate::Error;
+
+    fn render<W: Write>(
+        &self,
+        w: &mut W,
+        _processor: &Processor,
+        options: &RenderOptions,
+    ) -> Result<(), Self::Error> {
+        if options.inlines_basic {
+            let mut path = vec![self.target.as_str()];
+            path.extend(self.items.iter().map(String::as_str));
+            write!(w, "{}", substitution_text(&path.join(" > ")))?;
+            return Ok(());
+        }
+        let Some((last, submenus)) = self.items.split_last() else {
+            write!(
+                w,
+                "<b class=\"menuref\">{}</b>",
+                substitution_text(&self.target)
+            )?;
+            return Ok(());
+        };
+        let caret = "&#160;<b class=\"caret\">&#8250;</b> ";
         write!(
             w,
-            "<img src=\"{}\"",
-            match &self.source {
-                ImageSource::Url(url) => url,
-                ImageSource::Path(path) => path,
-            }
+            "<span class=\"menuseq\"><b class=\"menu\">{}</b>{caret}",
+            substitution_text(&self.target)
+        )?;
+        for submenu in submenus {
+            write!(
+                w,
+                "<b class=\"submenu\">{}</b>{caret}",
+                substitution_text(submenu)
+            )?;
+        }
+        write!(
+            w,
+            "<b class=\"menuitem\">{}</b></span>",
+            substitution_text(last)
         )?;
-        if !self.title.is_empty() {
-            write!(w, " alt=\"",)?;
-            self.title
-                .iter()
-                .try_for_each(|node| node.render(w, processor, options))?;
-            write!(w, "\"")?;
+        Ok(())
+    }
+}
+
+impl Render for Keyboard {
+    type Error = crate::Error;
+
+    fn render<W: Write>(
+        &self,
+        w: &mut W,
+        _processor: &Processor,
+        options: &RenderOptions,
+    ) -> Result<(), Self::Error> {
+        if options.inlines_basic {
+            write!(w, "{}", substitution_text(&self.keys.join("+")))?;
+            return Ok(());
+        }
+        let keys = self
+            .keys
+            .iter()
+            .map(|key| format!("<kbd>{}</kbd>", substitution_text(key)))
+            .collect::<Vec<_>>();
+        if let [key] = &keys[..] {
+            write!(w, "{key}")?;
+        } else {
+            write!(w, "<span class=\"keyseq\">{}</span>", keys.join("+"))?;
         }
-        write!(w, "\">")?;
         Ok(())
     }
 }
@@ -202,7 +329,16 @@ pub(crate) fn render_inlines(
     Ok(())
 }
 
-fn substitution_text(text: &str) -> String {
+/// Escapes `value` to be written between the double quotes of an attribute.
+pub(crate) fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn substitution_text(text: &str) -> String {
     if text.is_empty() {
         return String::from("__EMPTY_WHEN_IT_SHOULD_NOT_BE__");
     }
@@ -214,3 +350,70 @@ fn substitution_text(text: &str) -> String {
         .replace(" --", "&thinsp;&mdash;")
         .replace("-- ", "&mdash;&thinsp;")
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_menu_separators() {
+        let expected = "<span class=\"menuseq\"><b class=\"menu\">File</b>&#160;<b class=\"caret\">&#8250;</b> <b class=\"submenu\">New</b>&#160;<b class=\"caret\">&#8250;</b> <b class=\"menuitem\">Open</b></span>";
+        assert!(render_string("menu:File[New > Open]\n").contains(expected));
+        assert!(render_string("menu:File[New, Open]\n").contains(expected));
+    }
+
+    #[test]
+    fn test_render_span_roles_and_id() {
+        let html = render_string("[.role1.role2#id%option]*bold*\n");
+        assert!(html.contains("<strong class=\"role1 role2\" id=\"id\">bold</strong>"));
+        let html = render_string("[#marked]#text#\n");
+        assert!(html.contains("<mark id=\"marked\">text</mark>"));
+        let html = render_string("[.big]_it_ and _plain_\n");
+        assert!(html.contains("<em class=\"big\">it</em> and <em>plain</em>"));
+    }
+
+    #[test]
+    fn test_render_keyboard() {
+        let html = render_string(":experimental:\n\nkbd:[Ctrl++]\n");
+        assert!(html.contains("<span class=\"keyseq\"><kbd>Ctrl</kbd>+<kbd>+</kbd></span>"));
+        let html = render_string(":experimental:\n\nkbd:[F11]\n");
+        assert!(html.contains("<kbd>F11</kbd>"));
+
+        let html = render_string(
+            ":experimental:\n\nPress kbd:[Ctrl++] to zoom in and kbd:[Ctrl+-] to zoom out.\n",
+        );
+        assert!(html
+            .contains("<span class=\"keyseq\"><kbd>Ctrl</kbd>+<kbd>-</kbd></span> to zoom out."));
+        assert!(!html.contains('\u{FFFD}'));
+    }
+
+    #[test]
+    fn test_render_autolink() {
+        let html = render_string("see https://x.org/a now\n");
+        assert!(html.contains(
+            "<p>see <a href=\"https://x.org/a\" class=\"bare\">https://x.org/a</a> now</p>"
+        ));
+    }
+
+    #[test]
+    fn test_render_mailto() {
+        let html = render_string("mailto:me@x.com[Contact,Hi,Hello there]\n");
+        assert!(html
+            .contains("<a href=\"mailto:me@x.com?subject=Hi&amp;body=Hello%20there\">Contact</a>"));
+        let html = render_string("link:mailto:me@x.com[Email Me]\n");
+        assert!(html.contains("<a href=\"mailto:me@x.com\">Email Me</a>"));
+    }
+
+    #[test]
+    fn test_render_line_through() {
+        let html = render_string("[.line-through]#gone#\n");
+        assert!(html.contains("<span class=\"line-through\">gone</span>"));
+    }
+
+    #[test]
+    fn test_render_size_roles() {
+        let html = render_string("[.small]#fine print# and [.big]#large#\n");
+        assert!(html.contains("<span class=\"small\">fine print</span>"));
+        assert!(html.contains("<span class=\"big\">large</span>"));
+    }
+}
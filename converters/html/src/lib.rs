@@ -1,10 +1,12 @@
 use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
     io::{BufWriter, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-use acdc_converters_common::{Config, PrettyDuration, Processable, Source};
-use acdc_parser::Document;
+use acdc_converters_common::{Config, Doctype, PrettyDuration, Processable, SafeMode, Source};
+use acdc_parser::{AttributeValue, Document, DocumentAttributes};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -20,9 +22,58 @@ pub enum Error {
 
 pub struct Processor {
     config: Config,
+    /// Number of titled tables rendered so far in the current document.
+    table_counter: Cell<usize>,
+    /// Number of titled example blocks rendered so far in the current document.
+    example_counter: Cell<usize>,
+    /// Number of titled block images rendered so far in the current document.
+    figure_counter: Cell<usize>,
+    /// Number of titled stem blocks rendered so far in the current document.
+    equation_counter: Cell<usize>,
+    /// Titled stem blocks of the current document, in the order they're rendered in.
+    equations: RefCell<Vec<stem::Equation>>,
+    /// Numbers handed out to the sections rendered so far in the current document.
+    section_numbers: RefCell<sectnum::SectionNumbers>,
+    /// Text shown by a cross reference to each id of the current document, when it has
+    /// none of its own.
+    references: RefCell<HashMap<String, String>>,
+    /// Attributes of the document currently being rendered.
+    document_attributes: RefCell<DocumentAttributes>,
+    /// Numbers of the callouts in the last verbatim block, which the callout list that
+    /// follows it explains.
+    callouts: RefCell<Vec<usize>>,
+    /// Table of contents of the current document, rendered up front for the `toc::[]`
+    /// macro to place when the `toc` attribute is `macro`.
+    toc: RefCell<String>,
+    /// Numbers of the footnotes referenced so far in the body of the current document.
+    footnotes: RefCell<HashSet<usize>>,
+    /// Script loaded to typeset the stem content of a document.
+    math_renderer: MathRenderer,
 }
 
 impl Processor {
+    /// Typesets stem content with `renderer` rather than `MathJax`.
+    #[must_use]
+    pub fn with_math_renderer(mut self, renderer: MathRenderer) -> Self {
+        self.math_renderer = renderer;
+        self
+    }
+
+    /// The doctype of the document being rendered: the one its `doctype` attribute names,
+    /// or the configured one.
+    fn doctype(&self) -> Doctype {
+        match self.document_attributes.borrow().get("doctype") {
+            Some(AttributeValue::String(doctype)) => match doctype.as_str() {
+                "article" => Doctype::Article,
+                "book" => Doctype::Book,
+                "manpage" => Doctype::Manpage,
+                "inline" => Doctype::Inline,
+                _ => self.config.doctype.clone(),
+            },
+            _ => self.config.doctype.clone(),
+        }
+    }
+
     fn to_file<P: AsRef<Path>>(
         &self,
         doc: &Document,
@@ -31,24 +82,58 @@ impl Processor {
     ) -> Result<(), crate::Error> {
         let mut file = std::fs::File::create(path)?;
         let mut writer = BufWriter::new(&mut file);
-        let options = RenderOptions {
-            last_updated: std::fs::metadata(original)?
-                .modified()
-                .ok()
-                .map(chrono::DateTime::from),
-            ..RenderOptions::default()
-        };
-        doc.render(&mut writer, self, &options)?;
+        self.convert(doc, Some(original.as_ref()), &mut writer)?;
         writer.flush()?;
         Ok(())
     }
 }
 
 #[derive(Debug, Default)]
-struct RenderOptions {
+struct RenderOptions<'a> {
     last_updated: Option<chrono::DateTime<chrono::Utc>>,
+    /// Directory of the document being rendered, which relative image paths are read
+    /// from when embedding them.
+    docdir: Option<&'a Path>,
     inlines_basic: bool,
-    inlines_substitutions: bool,
+    /// Whether line breaks in text are kept as `<br>`, as in paragraphs with hardbreaks.
+    hardbreaks: bool,
+    /// Whether plain text is written as is, for blocks whose substitutions leave out
+    /// `specialchars`.
+    raw_text: bool,
+}
+
+/// Resolves `path` against the directory of the document, for reading a file into the
+/// output.
+///
+/// Except in the unsafe mode, the file must be inside that directory, as asciidoctor
+/// jails it: paths that lead out of it, whether absolute, through `..` or through a
+/// symbolic link, are refused with a warning, as are paths that can't be resolved.
+fn resolve_path(path: &str, processor: &Processor, options: &RenderOptions) -> Option<PathBuf> {
+    let docdir = options.docdir.unwrap_or(Path::new(""));
+    let file = docdir.join(path);
+    if matches!(processor.config.safe_mode, SafeMode::Unsafe) {
+        return Some(file);
+    }
+    let jail = if docdir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        docdir
+    };
+    let resolved = match (jail.canonicalize(), file.canonicalize()) {
+        (Ok(jail), Ok(resolved)) if resolved.starts_with(&jail) => resolved,
+        (Ok(_), Ok(_)) => {
+            tracing::warn!(
+                ?file,
+                "refusing to read a file outside the document directory"
+            );
+            return None;
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::warn!(?file, "failed to resolve file: {e}");
+            return None;
+        }
+    };
+    Some(resolved)
 }
 
 /// A simple trait for helping in rendering `AsciiDoc` content.
@@ -67,9 +152,22 @@ impl Processable for Processor {
     type Config = Config;
     type Error = Error;
 
-    #[must_use]
     fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            table_counter: Cell::new(0),
+            example_counter: Cell::new(0),
+            figure_counter: Cell::new(0),
+            equation_counter: Cell::new(0),
+            equations: RefCell::new(Vec::new()),
+            section_numbers: RefCell::new(sectnum::SectionNumbers::default()),
+            references: RefCell::new(HashMap::new()),
+            document_attributes: RefCell::new(DocumentAttributes::default()),
+            callouts: RefCell::new(Vec::new()),
+            toc: RefCell::new(String::new()),
+            footnotes: RefCell::new(HashSet::new()),
+            math_renderer: MathRenderer::default(),
+        }
     }
 
     fn run(&self) -> Result<(), Self::Error> {
@@ -85,7 +183,7 @@ impl Processable for Processor {
                     // Read and parse the document
                     let now = std::time::Instant::now();
                     let mut total_elapsed = std::time::Duration::new(0, 0);
-                    let doc = acdc_parser::parse_file(file)?;
+                    let (doc, parse_timings) = acdc_parser::parse_file_with_timings(file)?;
                     let elapsed = now.elapsed();
                     tracing::debug!(time = elapsed.pretty_print_precise(3), source = ?file, destination = ?html_path, "time to read and parse source");
                     total_elapsed += elapsed;
@@ -94,6 +192,9 @@ impl Processable for Processor {
                             "  Time to read and parse source: {}",
                             elapsed.pretty_print()
                         );
+                        for (phase, elapsed) in parse_timings.phases() {
+                            println!("    {phase}: {}", elapsed.pretty_print());
+                        }
                     }
 
                     // Convert the document
@@ -124,44 +225,71 @@ impl Processable for Processor {
     }
 
     fn output(&self) -> Result<String, Self::Error> {
-        let mut options = RenderOptions {
-            ..RenderOptions::default()
-        };
         match &self.config.source {
             Source::Files(files) => {
                 let mut buffer = Vec::new();
                 for file in files {
-                    options.last_updated = std::fs::metadata(file)?
-                        .modified()
-                        .ok()
-                        .map(chrono::DateTime::from);
-                    acdc_parser::parse_file(file)?.render(&mut buffer, self, &options)?;
+                    let doc = acdc_parser::parse_file(file)?;
+                    self.convert(&doc, Some(file), &mut buffer)?;
                 }
                 Ok(String::from_utf8(buffer)?)
             }
-            Source::String(content) => {
-                let mut buffer = Vec::new();
-                acdc_parser::parse(content)?.render(&mut buffer, self, &options)?;
-                Ok(String::from_utf8(buffer)?)
-            }
+            Source::String(content) => self.convert_to_string(&acdc_parser::parse(content)?, None),
             Source::Stdin => {
                 let stdin = std::io::stdin();
                 let mut reader = std::io::BufReader::new(stdin.lock());
                 let doc = acdc_parser::parse_from_reader(&mut reader)?;
-                let mut buffer = Vec::new();
-                doc.render(&mut buffer, self, &options)?;
-                Ok(String::from_utf8(buffer)?)
+                self.convert_to_string(&doc, None)
             }
         }
     }
+
+    fn convert<W: Write>(
+        &self,
+        doc: &Document,
+        source_file: Option<&Path>,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        let options = RenderOptions {
+            last_updated: source_file
+                .map(std::fs::metadata)
+                .transpose()?
+                .and_then(|metadata| metadata.modified().ok())
+                .map(chrono::DateTime::from),
+            docdir: source_file.and_then(Path::parent),
+            ..RenderOptions::default()
+        };
+        doc.render(writer, self, &options)
+    }
+}
+
+/// Renders `content` with the default configuration, for use in tests.
+#[cfg(test)]
+pub(crate) fn render_string(content: &str) -> String {
+    Processor::new(Config {
+        source: Source::String(content.to_string()),
+        ..Config::default()
+    })
+    .output()
+    .unwrap()
 }
 
 mod admonition;
 mod block;
+mod caption;
 mod delimited;
 mod document;
+mod footnote;
+mod image;
 mod inlines;
 mod list;
 mod paragraph;
 mod section;
+mod sectnum;
+mod stem;
+mod stylesheet;
 mod table;
+mod toc;
+mod xref;
+
+pub use stem::MathRenderer;
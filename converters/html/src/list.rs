@@ -1,6 +1,9 @@
 use std::io::Write;
 
-use acdc_parser::{ListItem, UnorderedList};
+use acdc_parser::{
+    BlockMetadata, CalloutList, DescriptionList, DescriptionListDescription, ListItem, OrderedList,
+    UnorderedList,
+};
 
 use crate::{Processor, Render, RenderOptions};
 
@@ -13,17 +16,237 @@ impl Render for UnorderedList {
         processor: &Processor,
         options: &RenderOptions,
     ) -> Result<(), Self::Error> {
-        writeln!(w, "<div class=\"ulist\">")?;
-        writeln!(w, "<ul>")?;
-        for item in &self.items {
-            item.render(w, processor, options)?;
+        let style = unordered_list_style(&self.metadata);
+        let name = style.map_or_else(|| "ulist".to_string(), |style| format!("ulist {style}"));
+        writeln!(
+            w,
+            "<div{} class=\"{}\">",
+            crate::xref::id_attribute(&self.metadata),
+            list_classes(&name, &self.metadata)
+        )?;
+        match style {
+            Some(style) => writeln!(w, "<ul class=\"{style}\">")?,
+            None => writeln!(w, "<ul>")?,
         }
+        render_items(
+            &self.items,
+            false,
+            is_compact(&self.metadata),
+            w,
+            processor,
+            options,
+        )?;
         writeln!(w, "</ul>")?;
         writeln!(w, "</div>")?;
         Ok(())
     }
 }
 
+impl Render for OrderedList {
+    type Error = crate::Error;
+
+    fn render<W: Write>(
+        &self,
+        w: &mut W,
+        processor: &Processor,
+        options: &RenderOptions,
+    ) -> Result<(), Self::Error> {
+        let style =
+            self.metadata.style.as_deref().unwrap_or_else(|| {
+                ordered_list_style(self.items.first().map_or(1, |item| item.level))
+            });
+        writeln!(
+            w,
+            "<div{} class=\"{}\">",
+            crate::xref::id_attribute(&self.metadata),
+            list_classes(&format!("olist {style}"), &self.metadata)
+        )?;
+        write!(w, "<ol class=\"{style}\"")?;
+        if let Some(marker) = ordered_list_type(style) {
+            write!(w, " type=\"{marker}\"")?;
+        }
+        if let Some(start) = self.start {
+            write!(w, " start=\"{start}\"")?;
+        }
+        if self.reversed {
+            write!(w, " reversed")?;
+        }
+        writeln!(w, ">")?;
+        render_items(
+            &self.items,
+            true,
+            is_compact(&self.metadata),
+            w,
+            processor,
+            options,
+        )?;
+        writeln!(w, "</ol>")?;
+        writeln!(w, "</div>")?;
+        Ok(())
+    }
+}
+
+impl Render for CalloutList {
+    type Error = crate::Error;
+
+    fn render<W: Write>(
+        &self,
+        w: &mut W,
+        processor: &Processor,
+        options: &RenderOptions,
+    ) -> Result<(), Self::Error> {
+        let callouts = processor.callouts.take();
+        writeln!(
+            w,
+            "<div{} class=\"{}\">",
+            crate::xref::id_attribute(&self.metadata),
+            list_classes("colist arabic", &self.metadata)
+        )?;
+        if !self.title.is_empty() {
+            write!(w, "<div class=\"title\">")?;
+            crate::inlines::render_inlines(&self.title, w, processor, options)?;
+            writeln!(w, "</div>")?;
+        }
+        writeln!(w, "<table>")?;
+        let mut numbers = Vec::new();
+        for (i, item) in self.items.iter().enumerate() {
+            let number = callout_number(&item.marker).unwrap_or(i + 1);
+            if !callouts.contains(&number) {
+                tracing::warn!(number, "callout list item has no matching callout");
+            }
+            numbers.push(number);
+            writeln!(w, "<tr>")?;
+            writeln!(
+                w,
+                "<td><i class=\"conum\" data-value=\"{number}\"></i><b>{number}</b></td>"
+            )?;
+            write!(w, "<td>")?;
+            crate::inlines::render_inlines(&item.content, w, processor, options)?;
+            writeln!(w, "</td>")?;
+            writeln!(w, "</tr>")?;
+        }
+        for number in callouts.iter().filter(|number| !numbers.contains(number)) {
+            tracing::warn!(number, "callout is missing from the callout list");
+        }
+        writeln!(w, "</table>")?;
+        writeln!(w, "</div>")?;
+        Ok(())
+    }
+}
+
+impl Render for DescriptionList {
+    type Error = crate::Error;
+
+    fn render<W: Write>(
+        &self,
+        w: &mut W,
+        processor: &Processor,
+        options: &RenderOptions,
+    ) -> Result<(), Self::Error> {
+        let style = self.metadata.style.as_deref();
+        let name = match style {
+            Some("horizontal") => "hdlist",
+            Some("qanda") => "qlist qanda",
+            _ => "dlist",
+        };
+        writeln!(
+            w,
+            "<div{} class=\"{}\">",
+            crate::xref::id_attribute(&self.metadata),
+            list_classes(name, &self.metadata)
+        )?;
+        if !self.title.is_empty() {
+            write!(w, "<div class=\"title\">")?;
+            crate::inlines::render_inlines(&self.title, w, processor, options)?;
+            writeln!(w, "</div>")?;
+        }
+        match style {
+            // Terms in one column, descriptions in the other.
+            Some("horizontal") => {
+                writeln!(w, "<table>")?;
+                for item in &self.items {
+                    writeln!(w, "<tr>")?;
+                    writeln!(w, "<td class=\"hdlist1\">")?;
+                    for (i, term) in item.terms.iter().enumerate() {
+                        if i > 0 {
+                            writeln!(w, "<br>")?;
+                        }
+                        crate::inlines::render_inlines(term, w, processor, options)?;
+                        writeln!(w)?;
+                    }
+                    writeln!(w, "</td>")?;
+                    writeln!(w, "<td class=\"hdlist2\">")?;
+                    render_description(&item.description, w, processor, options)?;
+                    writeln!(w, "</td>")?;
+                    writeln!(w, "</tr>")?;
+                }
+                writeln!(w, "</table>")?;
+            }
+            // Each item is a numbered question, followed by its answer.
+            Some("qanda") => {
+                writeln!(w, "<ol>")?;
+                for item in &self.items {
+                    writeln!(w, "<li>")?;
+                    for term in &item.terms {
+                        write!(w, "<p><em>")?;
+                        crate::inlines::render_inlines(term, w, processor, options)?;
+                        writeln!(w, "</em></p>")?;
+                    }
+                    render_description(&item.description, w, processor, options)?;
+                    writeln!(w, "</li>")?;
+                }
+                writeln!(w, "</ol>")?;
+            }
+            _ => {
+                writeln!(w, "<dl>")?;
+                for item in &self.items {
+                    for term in &item.terms {
+                        write!(w, "<dt class=\"hdlist1\">")?;
+                        crate::inlines::render_inlines(term, w, processor, options)?;
+                        writeln!(w, "</dt>")?;
+                    }
+                    writeln!(w, "<dd>")?;
+                    render_description(&item.description, w, processor, options)?;
+                    writeln!(w, "</dd>")?;
+                }
+                writeln!(w, "</dl>")?;
+            }
+        }
+        writeln!(w, "</div>")?;
+        Ok(())
+    }
+}
+
+fn render_description<W: Write>(
+    description: &DescriptionListDescription,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    match description {
+        DescriptionListDescription::Inline(inlines) => {
+            write!(w, "<p>")?;
+            crate::inlines::render_inlines(inlines, w, processor, options)?;
+            writeln!(w, "</p>")?;
+        }
+        DescriptionListDescription::Blocks(blocks) => {
+            for block in blocks {
+                block.render(w, processor, options)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the number in a callout marker such as `<1>`, or `None` for an
+/// automatically numbered `<.>` marker.
+fn callout_number(marker: &str) -> Option<usize> {
+    marker
+        .strip_prefix('<')
+        .and_then(|marker| marker.strip_suffix('>'))
+        .and_then(|number| number.parse().ok())
+}
+
 impl Render for ListItem {
     type Error = crate::Error;
 
@@ -33,11 +256,246 @@ impl Render for ListItem {
         processor: &Processor,
         options: &RenderOptions,
     ) -> Result<(), Self::Error> {
-        writeln!(w, "<li>")?;
         writeln!(w, "<p>")?;
         crate::inlines::render_inlines(&self.content, w, processor, options)?;
         writeln!(w, "</p>")?;
-        writeln!(w, "</li>")?;
         Ok(())
     }
 }
+
+/// Whether a list is compact, through either the `%compact` option or the `compact`
+/// role.
+fn is_compact(metadata: &BlockMetadata) -> bool {
+    metadata.options.iter().any(|o| o == "compact") || metadata.roles.iter().any(|r| r == "compact")
+}
+
+/// The marker style of an unordered list, such as `square` or `unstyled`, which only
+/// applies to its own level: the lists nested in it get the default bullets.
+fn unordered_list_style(metadata: &BlockMetadata) -> Option<&str> {
+    metadata.style.as_deref().or_else(|| {
+        metadata
+            .options
+            .iter()
+            .any(|o| o == "unstyled")
+            .then_some("unstyled")
+    })
+}
+
+/// The numbering style of an ordered list with no explicit style, which changes with
+/// each level of nesting.
+fn ordered_list_style(level: u8) -> &'static str {
+    match level {
+        0 | 1 => "arabic",
+        2 => "loweralpha",
+        3 => "lowerroman",
+        4 => "upperalpha",
+        _ => "upperroman",
+    }
+}
+
+/// The `type` attribute of an `<ol>` in the given numbering style, as browsers don't
+/// all pick the numbering up from the class alone.
+fn ordered_list_type(style: &str) -> Option<&'static str> {
+    match style {
+        "loweralpha" => Some("a"),
+        "upperalpha" => Some("A"),
+        "lowerroman" => Some("i"),
+        "upperroman" => Some("I"),
+        _ => None,
+    }
+}
+
+fn list_classes(name: &str, metadata: &BlockMetadata) -> String {
+    let mut classes = vec![name];
+    classes.extend(metadata.roles.iter().map(String::as_str));
+    if is_compact(metadata) && !metadata.roles.iter().any(|r| r == "compact") {
+        classes.push("compact");
+    }
+    classes.extend(crate::delimited::break_class(metadata));
+    crate::inlines::escape_attribute(&classes.join(" "))
+}
+
+/// Renders the items of a list. The items of a compact list aren't wrapped in a
+/// paragraph, so they sit closer together.
+///
+/// Items nested deeper than the one before them are rendered as a list of their own
+/// inside it, so each nested ordered list is numbered on its own.
+fn render_items<W: Write>(
+    items: &[ListItem],
+    ordered: bool,
+    compact: bool,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    let mut rest = items;
+    while let Some((item, tail)) = rest.split_first() {
+        let nested = tail
+            .iter()
+            .take_while(|next| next.level > item.level)
+            .count();
+        let (children, tail) = tail.split_at(nested);
+        if compact {
+            write!(w, "<li>")?;
+            crate::inlines::render_inlines(&item.content, w, processor, options)?;
+            if !children.is_empty() {
+                writeln!(w)?;
+            }
+        } else {
+            writeln!(w, "<li>")?;
+            item.render(w, processor, options)?;
+        }
+        if !children.is_empty() {
+            render_nested_list(children, ordered, w, processor, options)?;
+        }
+        writeln!(w, "</li>")?;
+        rest = tail;
+    }
+    Ok(())
+}
+
+fn render_nested_list<W: Write>(
+    items: &[ListItem],
+    ordered: bool,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    if ordered {
+        let style = ordered_list_style(items[0].level);
+        writeln!(w, "<div class=\"olist {style}\">")?;
+        write!(w, "<ol class=\"{style}\"")?;
+        if let Some(marker) = ordered_list_type(style) {
+            write!(w, " type=\"{marker}\"")?;
+        }
+        writeln!(w, ">")?;
+        render_items(items, ordered, false, w, processor, options)?;
+        writeln!(w, "</ol>")?;
+    } else {
+        writeln!(w, "<div class=\"ulist\">")?;
+        writeln!(w, "<ul>")?;
+        render_items(items, ordered, false, w, processor, options)?;
+        writeln!(w, "</ul>")?;
+    }
+    writeln!(w, "</div>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_compact_lists() {
+        let html = render_string("[%compact]\n* one\n* two\n");
+        assert!(
+            html.contains("<div class=\"ulist compact\">\n<ul>\n<li>one</li>\n<li>two</li>\n</ul>")
+        );
+
+        let html = render_string("[.compact]\n. first\n. second\n");
+        assert!(html.contains(
+            "<div class=\"olist arabic compact\">\n<ol class=\"arabic\">\n<li>first</li>\n<li>second</li>\n</ol>"
+        ));
+
+        let html = render_string("* loose\n");
+        assert!(html.contains("<div class=\"ulist\">\n<ul>\n<li>\n<p>\nloose</p>"));
+    }
+
+    #[test]
+    fn test_render_list_roles_escaped() {
+        let html = render_string("[.x\"y]\n* one\n");
+        assert!(html.contains("<div class=\"ulist x&quot;y\">"));
+    }
+
+    #[test]
+    fn test_render_ordered_list_numbering() {
+        let html = render_string("[%reversed,start=5]\n. five\n. four\n");
+        assert!(html.contains(
+            "<div class=\"olist arabic\">\n<ol class=\"arabic\" start=\"5\" reversed>\n<li>\n<p>\nfive</p>\n</li>"
+        ));
+
+        let html = render_string("[upperroman]\n. one\n. two\n");
+        assert!(
+            html.contains("<div class=\"olist upperroman\">\n<ol class=\"upperroman\" type=\"I\">")
+        );
+    }
+
+    #[test]
+    fn test_render_nested_lists() {
+        let html = render_string(". one\n.. one a\n.. one b\n. two\n.. two a\n");
+        assert!(html.contains(
+            "<li>\n<p>\none</p>\n<div class=\"olist loweralpha\">\n<ol class=\"loweralpha\" type=\"a\">\n<li>\n<p>\none a</p>\n</li>\n<li>\n<p>\none b</p>\n</li>\n</ol>\n</div>\n</li>"
+        ));
+        assert!(html.contains(
+            "<li>\n<p>\ntwo</p>\n<div class=\"olist loweralpha\">\n<ol class=\"loweralpha\" type=\"a\">\n<li>\n<p>\ntwo a</p>\n</li>\n</ol>\n</div>\n</li>"
+        ));
+
+        let html = render_string("* one\n** one a\n* two\n");
+        assert!(html.contains(
+            "<li>\n<p>\none</p>\n<div class=\"ulist\">\n<ul>\n<li>\n<p>\none a</p>\n</li>\n</ul>\n</div>\n</li>\n<li>\n<p>\ntwo</p>"
+        ));
+    }
+
+    #[test]
+    fn test_render_unstyled_lists() {
+        let html = render_string("[%unstyled]\n* one\n** one a\n");
+        assert!(html.contains(
+            "<div class=\"ulist unstyled\">\n<ul class=\"unstyled\">\n<li>\n<p>\none</p>\n<div class=\"ulist\">\n<ul>\n<li>\n<p>\none a</p>"
+        ));
+
+        let html = render_string("[.no-bullet]\n* one\n** one a\n");
+        assert!(html.contains(
+            "<div class=\"ulist no-bullet\">\n<ul>\n<li>\n<p>\none</p>\n<div class=\"ulist\">\n<ul>"
+        ));
+
+        let html = render_string("[square]\n* one\n");
+        assert!(html.contains("<div class=\"ulist square\">\n<ul class=\"square\">"));
+    }
+
+    #[test]
+    fn test_render_description_list() {
+        let html = render_string("CPU:: The brain\nRAM::\nMemory:: Short-term storage\n");
+        assert!(html.contains(
+            "<div class=\"dlist\">\n<dl>\n<dt class=\"hdlist1\">CPU</dt>\n<dd>\n<p>The brain</p>\n</dd>\n<dt class=\"hdlist1\">RAM</dt>\n<dt class=\"hdlist1\">Memory</dt>\n<dd>\n<p>Short-term storage</p>\n</dd>\n</dl>\n</div>"
+        ));
+    }
+
+    #[test]
+    fn test_render_nested_description_list() {
+        let html = render_string("Outer:: Text\nInner::: Nested\nNext:: More\n");
+        assert!(html.contains(
+            "<dt class=\"hdlist1\">Outer</dt>\n<dd>\n<div class=\"paragraph\">\n<p>Text</p>\n</div>\n<div class=\"dlist\">\n<dl>\n<dt class=\"hdlist1\">Inner</dt>\n<dd>\n<p>Nested</p>\n</dd>\n</dl>\n</div>\n</dd>\n<dt class=\"hdlist1\">Next</dt>"
+        ));
+    }
+
+    #[test]
+    fn test_render_horizontal_description_list() {
+        let html = render_string("[horizontal]\nCPU::\nProcessor:: The brain\n");
+        assert!(html.contains(
+            "<div class=\"hdlist\">\n<table>\n<tr>\n<td class=\"hdlist1\">\nCPU\n<br>\nProcessor\n</td>\n<td class=\"hdlist2\">\n<p>The brain</p>\n</td>\n</tr>\n</table>\n</div>"
+        ));
+    }
+
+    #[test]
+    fn test_render_qanda_list() {
+        let html = render_string("[qanda]\nWhat is it?:: A parser.\n");
+        assert!(html.contains(
+            "<div class=\"qlist qanda\">\n<ol>\n<li>\n<p><em>What is it?</em></p>\n<p>A parser.</p>\n</li>\n</ol>\n</div>"
+        ));
+    }
+
+    #[test]
+    fn test_render_callout_list() {
+        let html = render_string(
+            "[source,rust,subs=\"verbatim,quotes\"]\n----\nlet x = 1; <1>\nlet y = 2; // <2>\n----\n<1> The x\n<2> The y\n",
+        );
+        assert!(html.contains(
+            "let x = 1; <i class=\"conum\" data-value=\"1\"></i><b>(1)</b>\nlet y = 2; <i class=\"conum\" data-value=\"2\"></i><b>(2)</b>"
+        ));
+        assert!(html.contains(
+            "<div class=\"colist arabic\">\n<table>\n<tr>\n<td><i class=\"conum\" data-value=\"1\"></i><b>1</b></td>\n<td>The x</td>\n</tr>"
+        ));
+        assert!(html
+            .contains("<td><i class=\"conum\" data-value=\"2\"></i><b>2</b></td>\n<td>The y</td>"));
+    }
+}
@@ -1,9 +1,12 @@
 use std::io::Write;
 
-use acdc_parser::Paragraph;
+use acdc_parser::{AttributeValue, BlockMetadata, Paragraph};
 
 use crate::{Processor, Render, RenderOptions};
 
+/// Roles that align the text of a paragraph, emitted as classes on its `<p>`.
+const ALIGNMENT_ROLES: [&str; 4] = ["text-left", "text-center", "text-right", "text-justify"];
+
 impl Render for Paragraph {
     type Error = crate::Error;
 
@@ -13,11 +16,113 @@ impl Render for Paragraph {
         processor: &Processor,
         options: &RenderOptions,
     ) -> Result<(), Self::Error> {
-        writeln!(w, "<div class=\"paragraph\">")?;
-        write!(w, "<p>")?;
-        crate::inlines::render_inlines(&self.content, w, processor, options)?;
+        if let Some(name) = crate::admonition::custom_type(&self.metadata) {
+            // The paragraph goes in the admonition as a plain one, under its title.
+            let paragraph = Paragraph {
+                metadata: BlockMetadata::default(),
+                title: Vec::new(),
+                ..self.clone()
+            };
+            return crate::admonition::render_custom(
+                &name,
+                &self.title,
+                &self.metadata,
+                w,
+                processor,
+                options,
+                |w| paragraph.render(w, processor, options),
+            );
+        }
+        writeln!(
+            w,
+            "<div{} class=\"paragraph{}\">",
+            crate::xref::id_attribute(&self.metadata),
+            crate::delimited::break_class(&self.metadata)
+                .map(|class| format!(" {class}"))
+                .unwrap_or_default()
+        )?;
+        let alignment = self
+            .metadata
+            .roles
+            .iter()
+            .filter(|role| ALIGNMENT_ROLES.contains(&role.as_str()))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        if alignment.is_empty() {
+            write!(w, "<p>")?;
+        } else {
+            write!(w, "<p class=\"{}\">", alignment.join(" "))?;
+        }
+        crate::inlines::render_inlines(
+            &self.content,
+            w,
+            processor,
+            &RenderOptions {
+                hardbreaks: hardbreaks(&self.metadata, processor),
+                ..*options
+            },
+        )?;
         writeln!(w, "</p>")?;
         writeln!(w, "</div>")?;
         Ok(())
     }
 }
+
+/// Whether the line breaks of a paragraph are kept: `%nohardbreaks` on the paragraph
+/// turns them off and `%hardbreaks` on, and otherwise it's up to the `hardbreaks` (or
+/// `hardbreaks-option`) document attribute.
+fn hardbreaks(metadata: &BlockMetadata, processor: &Processor) -> bool {
+    if metadata.options.iter().any(|o| o == "nohardbreaks") {
+        return false;
+    }
+    if metadata.options.iter().any(|o| o == "hardbreaks") {
+        return true;
+    }
+    let attributes = processor.document_attributes.borrow();
+    ["hardbreaks", "hardbreaks-option"].iter().any(|name| {
+        attributes
+            .get(name)
+            .is_some_and(|value| value != &AttributeValue::Bool(false))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_paragraph_alignment_roles() {
+        for role in ["text-left", "text-center", "text-right", "text-justify"] {
+            let html = render_string(&format!("[.{role}]\nAligned text.\n"));
+            assert!(
+                html.contains(&format!("<p class=\"{role}\">Aligned text.</p>")),
+                "missing {role} in {html}"
+            );
+        }
+
+        let html = render_string("[.lead]\nNot aligned.\n");
+        assert!(html.contains("<p>Not aligned.</p>"));
+    }
+
+    #[test]
+    fn test_render_hardbreaks() {
+        let html = render_string(
+            "= Document\n:hardbreaks:\n\nRoses are red,\nviolets are blue.\n\n[%nohardbreaks]\nOne\nline.\n",
+        );
+        assert!(html.contains("<p>Roses are red,<br>\nviolets are blue.</p>"));
+        assert!(html.contains("<p>One\nline.</p>"));
+
+        let html = render_string("[%hardbreaks]\nFirst\nSecond\n\nThird\nFourth\n");
+        assert!(html.contains("<p>First<br>\nSecond</p>"));
+        assert!(html.contains("<p>Third\nFourth</p>"));
+
+        let html = render_string("Line one +\nLine two\n");
+        assert!(html.contains("<br>\nLine two"));
+    }
+
+    #[test]
+    fn test_render_paragraph_break_class() {
+        let html = render_string("[.keep-together]\nAll on one page.\n");
+        assert!(html.contains("<div class=\"paragraph unbreakable\">\n<p>All on one page.</p>"));
+    }
+}
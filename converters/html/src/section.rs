@@ -1,6 +1,7 @@
 use std::io::Write;
 
-use acdc_parser::Section;
+use acdc_converters_common::Doctype;
+use acdc_parser::{Block, Section};
 
 use crate::{Processor, Render, RenderOptions};
 
@@ -13,10 +14,13 @@ impl Render for Section {
         processor: &Processor,
         options: &RenderOptions,
     ) -> Result<(), Self::Error> {
+        if self.level == 0 && matches!(processor.doctype(), Doctype::Book) {
+            return render_part(self, w, processor, options);
+        }
         writeln!(w, "<div class=\"sect{}\">", self.level)?;
-        write!(w, "<h{}>", self.level + 1)?;
-        crate::inlines::render_inlines(&self.title, w, processor, options)?;
-        writeln!(w, "</h{}>", self.level)?;
+        write!(w, "<h{} id=\"{}\">", self.level + 1, self.id())?;
+        render_title(self, w, processor, options)?;
+        writeln!(w, "</h{}>", self.level + 1)?;
         writeln!(w, "<div class=\"sectionbody\">")?;
         for block in &self.content {
             block.render(w, processor, options)?;
@@ -26,3 +30,84 @@ impl Render for Section {
         Ok(())
     }
 }
+
+/// Renders a part of a book: a top-level heading that isn't wrapped with its content,
+/// since the chapters in it follow as sections of their own. The blocks before its first
+/// chapter are its introduction.
+fn render_part<W: Write>(
+    part: &Section,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    write!(w, "<h1 id=\"{}\" class=\"sect0", part.id())?;
+    for role in &part.metadata.roles {
+        write!(w, " {}", crate::inlines::escape_attribute(role))?;
+    }
+    write!(w, "\">")?;
+    render_title(part, w, processor, options)?;
+    writeln!(w, "</h1>")?;
+    let intro = part
+        .content
+        .iter()
+        .take_while(|block| !matches!(block, Block::Section(_)))
+        .count();
+    let (intro, chapters) = part.content.split_at(intro);
+    if !intro.is_empty() {
+        writeln!(w, "<div class=\"openblock partintro\">")?;
+        writeln!(w, "<div class=\"content\">")?;
+        for block in intro {
+            block.render(w, processor, options)?;
+        }
+        writeln!(w, "</div>")?;
+        writeln!(w, "</div>")?;
+    }
+    for block in chapters {
+        block.render(w, processor, options)?;
+    }
+    Ok(())
+}
+
+/// Renders the title of `section`, after its number when it has one.
+fn render_title<W: Write>(
+    section: &Section,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    if let Some(number) = processor
+        .section_numbers
+        .borrow_mut()
+        .next(section, &processor.document_attributes.borrow())
+    {
+        write!(w, "{number}")?;
+    }
+    crate::inlines::render_inlines(&section.title, w, processor, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_book_parts() {
+        let html = render_string(
+            "= Book\n:doctype: book\n\n[.lead]\n= Part One\n\nAn introduction.\n\n== Chapter\n\nText.\n",
+        );
+        assert!(html.contains("<body class=\"book\">"));
+        assert!(html.contains(
+            "<h1 id=\"_part_one\" class=\"sect0 lead\">Part One</h1>\n<div class=\"openblock partintro\">\n<div class=\"content\">"
+        ));
+        assert!(html.contains("An introduction."));
+        assert!(html
+            .contains("</div>\n</div>\n<div class=\"sect1\">\n<h2 id=\"_chapter\">Chapter</h2>"));
+
+        // Outside of a book, a level 0 section is still wrapped like any other.
+        let html = render_string("= Doc\n\n= Part One\n\n== Chapter\n\nText.\n");
+        assert!(html.contains("<div class=\"sect0\">\n<h1 id=\"_part_one\">Part One</h1>"));
+
+        let html =
+            render_string("= Book\n:doctype: book\n\n[.x\"y]\n= Part One\n\n== Chapter\n\nText.\n");
+        assert!(html.contains("<h1 id=\"_part_one\" class=\"sect0 x&quot;y\">Part One</h1>"));
+    }
+}
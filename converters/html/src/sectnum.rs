@@ -0,0 +1,226 @@
+use acdc_parser::{AttributeValue, DocumentAttributes, Section};
+
+/// Section styles that are never numbered, and neither are the sections nested in them.
+const UNNUMBERED_STYLES: &[&str] = &[
+    "abstract",
+    "acknowledgments",
+    "bibliography",
+    "colophon",
+    "dedication",
+    "glossary",
+    "index",
+    "preface",
+];
+
+const DEFAULT_SECTNUMLEVELS: u8 = 3;
+
+/// Keeps track of the numbers handed out to sections so far, as they're rendered in
+/// document order.
+#[derive(Debug, Default)]
+pub(crate) struct SectionNumbers {
+    parts: usize,
+    chapters: usize,
+    appendices: usize,
+    in_appendix: bool,
+    /// Level of the special section being rendered, if any, whose subsections stay
+    /// unnumbered.
+    unnumbered_level: Option<u8>,
+    /// Counters for the sections below the current chapter (or appendix), starting at
+    /// level 2.
+    nested: Vec<usize>,
+}
+
+impl SectionNumbers {
+    /// Returns the prefix to render before the title of `section`, e.g. "1.2. ", if it's
+    /// numbered.
+    ///
+    /// Sections are only numbered while the `sectnums` attribute is set and down to
+    /// `sectnumlevels` (3 by default). Parts (level 0) get roman numerals when `partnums`
+    /// is set too, prefixed with the `part-signifier` when there is one. Appendices get a
+    /// letter (and the `appendix-caption`), and special sections such as a preface none.
+    ///
+    /// Chapters are numbered continuously across parts unless `part-reset-chapters` is
+    /// set, and are prefixed with the `chapter-signifier` when there is one.
+    pub(crate) fn next(
+        &mut self,
+        section: &Section,
+        attributes: &DocumentAttributes,
+    ) -> Option<String> {
+        if !is_set(attributes, "sectnums") {
+            return None;
+        }
+        if let Some(level) = self.unnumbered_level {
+            if section.level > level {
+                return None;
+            }
+            self.unnumbered_level = None;
+        }
+        let style = section.metadata.style.as_deref();
+        if style.is_some_and(|style| UNNUMBERED_STYLES.contains(&style))
+            || section.metadata.options.iter().any(|o| o == "unnumbered")
+        {
+            self.unnumbered_level = Some(section.level);
+            return None;
+        }
+        if section.level > sectnumlevels(attributes) {
+            return None;
+        }
+        match section.level {
+            0 => {
+                self.parts += 1;
+                if is_set(attributes, "part-reset-chapters") {
+                    self.chapters = 0;
+                }
+                if !is_set(attributes, "partnums") {
+                    return None;
+                }
+                match attributes.get("part-signifier") {
+                    Some(AttributeValue::String(signifier)) if !signifier.is_empty() => {
+                        Some(format!("{signifier} {}: ", roman(self.parts)))
+                    }
+                    _ => Some(format!("{}: ", roman(self.parts))),
+                }
+            }
+            1 if style == Some("appendix") => {
+                self.appendices += 1;
+                self.in_appendix = true;
+                self.nested.clear();
+                let caption = match attributes.get("appendix-caption") {
+                    Some(AttributeValue::String(caption)) => format!("{caption} "),
+                    Some(AttributeValue::Bool(false)) => String::new(),
+                    _ => String::from("Appendix "),
+                };
+                Some(format!("{caption}{}: ", self.appendix_letter()))
+            }
+            1 => {
+                self.chapters += 1;
+                self.in_appendix = false;
+                self.nested.clear();
+                match attributes.get("chapter-signifier") {
+                    Some(AttributeValue::String(signifier)) if !signifier.is_empty() => {
+                        Some(format!("{signifier} {}. ", self.chapters))
+                    }
+                    _ => Some(format!("{}. ", self.chapters)),
+                }
+            }
+            level => {
+                let depth = usize::from(level) - 1;
+                self.nested.resize(depth, 0);
+                self.nested[depth - 1] += 1;
+                let mut number = if self.in_appendix {
+                    self.appendix_letter().to_string()
+                } else {
+                    self.chapters.to_string()
+                };
+                for n in &self.nested {
+                    number.push('.');
+                    number.push_str(&n.to_string());
+                }
+                Some(format!("{number}. "))
+            }
+        }
+    }
+
+    fn appendix_letter(&self) -> char {
+        u8::try_from(self.appendices - 1)
+            .ok()
+            .filter(|n| *n < 26)
+            .map_or('?', |n| char::from(b'A' + n))
+    }
+}
+
+fn is_set(attributes: &DocumentAttributes, name: &str) -> bool {
+    !matches!(
+        attributes.get(name),
+        None | Some(AttributeValue::Bool(false))
+    )
+}
+
+fn sectnumlevels(attributes: &DocumentAttributes) -> u8 {
+    match attributes.get("sectnumlevels") {
+        Some(AttributeValue::String(levels)) => levels.parse().unwrap_or(DEFAULT_SECTNUMLEVELS),
+        _ => DEFAULT_SECTNUMLEVELS,
+    }
+}
+
+/// Writes `n` in (uppercase) roman numerals.
+fn roman(mut n: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut numeral = String::new();
+    for (value, symbol) in NUMERALS {
+        while n >= value {
+            numeral.push_str(symbol);
+            n -= value;
+        }
+    }
+    numeral
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_section_numbers() {
+        let html = render_string(
+            "= Document\n:sectnums:\n\n[preface]\n== Preface\n\nText.\n\n=== Background\n\nText.\n\n== Install\n\nText.\n\n=== Linux\n\nText.\n\n==== Debian\n\nText.\n\n===== Bookworm\n\nText.\n\n=== macOS\n\nText.\n\n== Usage\n\nText.\n\n[appendix]\n== Options\n\nText.\n\n=== Flags\n\nText.\n\n:sectnums!:\n\n== Colophon\n\nText.\n",
+        );
+        assert!(html.contains("id=\"_preface\">Preface</h2>"));
+        assert!(html.contains("id=\"_background\">Background</h3>"));
+        assert!(html.contains("id=\"_install\">1. Install</h2>"));
+        assert!(html.contains("id=\"_linux\">1.1. Linux</h3>"));
+        assert!(html.contains("id=\"_debian\">1.1.1. Debian</h4>"));
+        assert!(html.contains("id=\"_bookworm\">Bookworm</h5>"));
+        assert!(html.contains("id=\"_macos\">1.2. macOS</h3>"));
+        assert!(html.contains("id=\"_usage\">2. Usage</h2>"));
+        assert!(html.contains("id=\"_options\">Appendix A: Options</h2>"));
+        assert!(html.contains("id=\"_flags\">A.1. Flags</h3>"));
+        assert!(html.contains("id=\"_colophon\">Colophon</h2>"));
+    }
+
+    #[test]
+    fn test_render_chapter_numbers_across_parts() {
+        let book = "= Book\n:doctype: book\n:sectnums:\n:chapter-signifier: Chapter\n{reset}\n= Part One\n\n== Intro\n\nText.\n\n== Setup\n\nText.\n\n= Part Two\n\n== Usage\n\nText.\n";
+
+        let html = render_string(&book.replace("{reset}", ""));
+        assert!(html.contains("id=\"_part_one\" class=\"sect0\">Part One</h1>"));
+        assert!(html.contains("id=\"_intro\">Chapter 1. Intro</h2>"));
+        assert!(html.contains("id=\"_setup\">Chapter 2. Setup</h2>"));
+        assert!(html.contains("id=\"_part_two\" class=\"sect0\">Part Two</h1>"));
+        assert!(html.contains("id=\"_usage\">Chapter 3. Usage</h2>"));
+
+        let html = render_string(&book.replace("{reset}", ":part-reset-chapters:\n"));
+        assert!(html.contains("id=\"_intro\">Chapter 1. Intro</h2>"));
+        assert!(html.contains("id=\"_setup\">Chapter 2. Setup</h2>"));
+        assert!(html.contains("id=\"_usage\">Chapter 1. Usage</h2>"));
+
+        let html = render_string(&book.replace("{reset}", ":partnums:\n"));
+        assert!(html.contains("id=\"_part_one\" class=\"sect0\">I: Part One</h1>"));
+        assert!(html.contains("id=\"_part_two\" class=\"sect0\">II: Part Two</h1>"));
+
+        let html = render_string(&book.replace("{reset}", ":partnums:\n:part-signifier: Part\n"));
+        assert!(html.contains("id=\"_part_one\" class=\"sect0\">Part I: Part One</h1>"));
+    }
+
+    #[test]
+    fn test_roman_numerals() {
+        assert_eq!(super::roman(1), "I");
+        assert_eq!(super::roman(4), "IV");
+        assert_eq!(super::roman(14), "XIV");
+        assert_eq!(super::roman(1999), "MCMXCIX");
+    }
+}
@@ -0,0 +1,429 @@
+use std::io::Write;
+
+use acdc_converters_common::SafeMode;
+use acdc_parser::{
+    AttributeValue, Block, DelimitedBlock, DelimitedBlockType, DescriptionListDescription,
+    Document, InlineMacro, InlineNode, Stem, StemNotation,
+};
+
+use crate::{inlines::escape_attribute, Processor, Render, RenderOptions};
+
+const MATHJAX_CONFIG: &str = r#"<script type="text/x-mathjax-config">
+MathJax.Hub.Config({
+  messageStyle: "none",
+  tex2jax: {
+    inlineMath: [["\\(", "\\)"]],
+    displayMath: [["\\[", "\\]"]],
+    ignoreClass: "nostem|nolatexmath"
+  },
+  asciimath2jax: {
+    delimiters: [["\\$", "\\$"]],
+    ignoreClass: "nostem|noasciimath"
+  },
+  TeX: { equationNumbers: { autoNumber: "none" } }
+})
+</script>
+<script src="https://cdnjs.cloudflare.com/ajax/libs/mathjax/2.7.9/MathJax.js?config=TeX-MML-AM_HTMLorMML"></script>"#;
+
+const KATEX_CONFIG: &str = r#"<link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/katex@0.16.11/dist/katex.min.css">
+<script defer src="https://cdn.jsdelivr.net/npm/katex@0.16.11/dist/katex.min.js"></script>
+<script defer src="https://cdn.jsdelivr.net/npm/katex@0.16.11/dist/contrib/auto-render.min.js"
+  onload="renderMathInElement(document.body, {
+    delimiters: [
+      {left: '\\[', right: '\\]', display: true},
+      {left: '\\(', right: '\\)', display: false}
+    ],
+    ignoredClasses: ['nostem', 'nolatexmath']
+  });"></script>"#;
+
+/// The script that typesets the math in a rendered document.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MathRenderer {
+    /// `MathJax`, which typesets both `asciimath` and `latexmath`.
+    #[default]
+    MathJax,
+    /// `KaTeX`, which is faster but only typesets `latexmath`.
+    Katex,
+}
+
+impl Render for Stem {
+    type Error = crate::Error;
+
+    fn render<W: Write>(
+        &self,
+        w: &mut W,
+        _processor: &Processor,
+        options: &RenderOptions,
+    ) -> Result<(), Self::Error> {
+        let content = escape_attribute(&self.content);
+        if options.inlines_basic {
+            write!(w, "{content}")?;
+            return Ok(());
+        }
+        match self.notation {
+            StemNotation::Asciimath => write!(w, "\\${content}\\$")?,
+            StemNotation::Latexmath => write!(w, "\\({content}\\)")?,
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether a delimited block is a stem block, i.e. a passthrough block styled
+/// `stem`, `asciimath` or `latexmath`.
+pub(crate) fn is_stem_block(block: &DelimitedBlock) -> bool {
+    matches!(
+        block.metadata.style.as_deref(),
+        Some("stem" | "asciimath" | "latexmath")
+    )
+}
+
+/// Renders a stem block as display math.
+///
+/// When the `equation-caption` document attribute is set, a titled stem block is numbered
+/// and its title prefixed with the caption, e.g. "Equation 1. ", as worked out up front
+/// by [`collect_equations`].
+pub(crate) fn render_stem_block<W: Write>(
+    block: &DelimitedBlock,
+    inlines: &[InlineNode],
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    let notation = match block.metadata.style.as_deref() {
+        Some("latexmath") => StemNotation::Latexmath,
+        Some("stem") => StemNotation::from_document(&processor.document_attributes.borrow()),
+        _ => StemNotation::Asciimath,
+    };
+    writeln!(
+        w,
+        "<div{} class=\"stemblock\">",
+        crate::xref::id_attribute(&block.metadata)
+    )?;
+    if !block.title.is_empty() {
+        write!(w, "<div class=\"title\">")?;
+        let index = processor.equation_counter.get();
+        processor.equation_counter.set(index + 1);
+        if let Some(caption) = processor
+            .equations
+            .borrow()
+            .get(index)
+            .and_then(|equation| equation.caption.as_ref())
+        {
+            write!(w, "{caption}. ")?;
+        }
+        crate::inlines::render_inlines(&block.title, w, processor, options)?;
+        writeln!(w, "</div>")?;
+    }
+    let content = inlines
+        .iter()
+        .filter_map(|inline| match inline {
+            InlineNode::RawText(raw) => Some(raw.content.as_str()),
+            InlineNode::PlainText(plain) => Some(plain.content.as_str()),
+            _ => None,
+        })
+        .collect::<String>();
+    let content = escape_attribute(content.trim());
+    writeln!(w, "<div class=\"content\">")?;
+    match notation {
+        StemNotation::Asciimath => writeln!(w, "\\${content}\\$")?,
+        StemNotation::Latexmath => writeln!(w, "\\[{content}\\]")?,
+    }
+    writeln!(w, "</div>")?;
+    writeln!(w, "</div>")?;
+    Ok(())
+}
+
+/// Writes the configuration and loader of the math renderer, which typesets the
+/// delimited math written out for each stem expression.
+///
+/// Nothing is written unless the `stem` attribute is set and the document has any stem
+/// content, or when running in secure mode, where no scripts are loaded.
+pub(crate) fn render_math_script<W: Write>(
+    document: &Document,
+    w: &mut W,
+    processor: &Processor,
+) -> Result<(), crate::Error> {
+    if matches!(processor.config.safe_mode, SafeMode::Secure)
+        || matches!(
+            document.attributes.get("stem"),
+            None | Some(AttributeValue::Bool(false))
+        )
+    {
+        return Ok(());
+    }
+    let has_stem = has_stem(&document.blocks)
+        || document
+            .header
+            .as_ref()
+            .is_some_and(|header| has_stem_inlines(&header.title));
+    if has_stem {
+        match processor.math_renderer {
+            MathRenderer::MathJax => writeln!(w, "{MATHJAX_CONFIG}")?,
+            MathRenderer::Katex => writeln!(w, "{KATEX_CONFIG}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether there's any stem expression in `blocks`, looking at titles (of
+/// sections and blocks alike) as well as content.
+pub(crate) fn has_stem(blocks: &[Block]) -> bool {
+    let mut found = false;
+    acdc_parser::walk_blocks(blocks, &mut |block| found = found || block_has_stem(block));
+    found
+}
+
+/// Returns whether `block` itself has a stem expression, leaving out the blocks nested
+/// in it.
+fn block_has_stem(block: &Block) -> bool {
+    match block {
+        Block::Section(section) => has_stem_inlines(&section.title),
+        Block::Paragraph(paragraph) => {
+            has_stem_inlines(&paragraph.title) || has_stem_inlines(&paragraph.content)
+        }
+        Block::Admonition(admonition) => has_stem_inlines(&admonition.title),
+        Block::UnorderedList(list) => {
+            has_stem_inlines(&list.title)
+                || list
+                    .items
+                    .iter()
+                    .any(|item| has_stem_inlines(&item.content))
+        }
+        Block::OrderedList(list) => {
+            has_stem_inlines(&list.title)
+                || list
+                    .items
+                    .iter()
+                    .any(|item| has_stem_inlines(&item.content))
+        }
+        Block::DescriptionList(list) => {
+            has_stem_inlines(&list.title)
+                || list.items.iter().any(|item| {
+                    item.terms.iter().any(|term| has_stem_inlines(term))
+                        || matches!(
+                            &item.description,
+                            DescriptionListDescription::Inline(inlines) if has_stem_inlines(inlines)
+                        )
+                })
+        }
+        Block::Image(image) => has_stem_inlines(&image.title),
+        Block::DelimitedBlock(delimited) => {
+            has_stem_inlines(&delimited.title)
+                || match &delimited.inner {
+                    DelimitedBlockType::DelimitedVerse(inlines) => has_stem_inlines(inlines),
+                    DelimitedBlockType::DelimitedPass(_) => is_stem_block(delimited),
+                    _ => false,
+                }
+        }
+        _ => false,
+    }
+}
+
+/// A titled stem block, which is numbered when the `equation-caption` attribute is set
+/// where it appears.
+pub(crate) struct Equation {
+    /// Id of the block, if it has one.
+    pub(crate) id: Option<String>,
+    /// Caption of the block, e.g. "Equation 1", when it's numbered.
+    pub(crate) caption: Option<String>,
+}
+
+/// Numbers the titled stem blocks of `document`, returning them in the order they're
+/// rendered in.
+///
+/// Attribute entries in the body are followed as they come, so that unsetting
+/// `equation-caption` halfway through stops numbering the equations after it.
+pub(crate) fn collect_equations(document: &Document) -> Vec<Equation> {
+    let mut label = match document.header_attributes().get("equation-caption") {
+        Some(AttributeValue::String(label)) => Some(escape_attribute(label)),
+        _ => None,
+    };
+    let mut count = 0;
+    let mut equations = Vec::new();
+    acdc_parser::walk_blocks(&document.blocks, &mut |block| match block {
+        Block::DocumentAttribute(attribute) if attribute.name == "equation-caption" => {
+            label = match &attribute.value {
+                AttributeValue::String(label) => Some(escape_attribute(label)),
+                _ => None,
+            };
+        }
+        Block::DelimitedBlock(delimited)
+            if is_stem_block(delimited) && !delimited.title.is_empty() =>
+        {
+            let caption = label.as_ref().map(|label| {
+                count += 1;
+                format!("{label} {count}")
+            });
+            equations.push(Equation {
+                id: crate::xref::block_id(&delimited.metadata).map(ToString::to_string),
+                caption,
+            });
+        }
+        _ => {}
+    });
+    equations
+}
+
+pub(crate) fn has_stem_inlines(inlines: &[InlineNode]) -> bool {
+    inlines.iter().any(|inline| match inline {
+        InlineNode::Macro(InlineMacro::Stem(_)) => true,
+        InlineNode::BoldText(bold) => has_stem_inlines(&bold.content),
+        InlineNode::ItalicText(italic) => has_stem_inlines(&italic.content),
+        InlineNode::MonospaceText(monospace) => has_stem_inlines(&monospace.content),
+        InlineNode::HighlightText(highlight) => has_stem_inlines(&highlight.content),
+        InlineNode::SubscriptText(subscript) => has_stem_inlines(&subscript.content),
+        InlineNode::SuperscriptText(superscript) => has_stem_inlines(&superscript.content),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use acdc_converters_common::{Config, Processable, SafeMode, Source};
+
+    use super::MathRenderer;
+    use crate::{render_string, Processor};
+
+    #[test]
+    fn test_render_stem_in_section_title() {
+        let html =
+            render_string("= Document\n:stem:\n\n== The stem:[sqrt(4) = 2] section\n\nText.\n");
+        assert!(html.contains("\">The \\$sqrt(4) = 2\\$ section</h2>"));
+        assert!(html.contains("MathJax.js"));
+    }
+
+    #[test]
+    fn test_render_stem_special_characters_only() {
+        let html = render_string("= Document\n:stem:\n\nSee stem:[a -- b < c].\n");
+        assert!(html.contains("See \\$a -- b &lt; c\\$."));
+    }
+
+    #[test]
+    fn test_render_stem_in_list_items() {
+        let html = render_string("= Document\n:stem:\n\n. Energy is stem:[E=mc^2]\n");
+        assert!(html.contains("<li>\n<p>\nEnergy is \\$E=mc^2\\$</p>\n</li>"));
+        assert!(html.contains("MathJax.js"));
+
+        let html = render_string("= Document\n:stem:\n\n* Nested *stem:[x^2]* too\n");
+        assert!(html.contains("<strong>\\$x^2\\$</strong>"));
+        assert!(html.contains("MathJax.js"));
+    }
+
+    #[test]
+    fn test_render_stem_in_description_lists() {
+        let html = render_string("= Document\n:stem:\n\nTerm:: desc with *bold* and stem:[x]\n");
+        assert!(html.contains("<p>desc with <strong>bold</strong> and \\$x\\$</p>"));
+        assert!(html.contains("MathJax.js"));
+
+        let html = render_string("= Document\n:stem:\n\nstem:[x]:: The unknown.\n");
+        assert!(html.contains("<dt class=\"hdlist1\">\\$x\\$</dt>"));
+        assert!(html.contains("MathJax.js"));
+    }
+
+    #[test]
+    fn test_render_without_stem() {
+        let html = render_string("= Document\n\n== Plain section\n\nText.\n");
+        assert!(!html.contains("MathJax"));
+
+        let html = render_string("= Document\n\nThe stem:[sqrt(4) = 2] is unset.\n");
+        assert!(!html.contains("MathJax"));
+
+        let html = render_string("= Document\n:stem!:\n\n[stem]\n++++\nx^2\n++++\n");
+        assert!(!html.contains("MathJax"));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn test_render_math_script_choice() {
+        let content = "= Document\n:stem: latexmath\n\nInline stem:[E = mc^2].\n";
+        let processor = |safe_mode| {
+            Processor::new(Config {
+                source: Source::String(content.to_string()),
+                safe_mode,
+                ..Config::default()
+            })
+        };
+
+        let html = processor(SafeMode::Unsafe)
+            .with_math_renderer(MathRenderer::Katex)
+            .output()
+            .unwrap();
+        assert!(html.contains("Inline \\(E = mc^2\\)."));
+        assert!(html.contains("katex.min.js"));
+        assert!(!html.contains("MathJax"));
+
+        let html = processor(SafeMode::Secure).output().unwrap();
+        assert!(html.contains("Inline \\(E = mc^2\\)."));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn test_render_stem_block_notation() {
+        let block = "[stem]\n++++\nsqrt(4) = 2\n++++\n";
+        let html = render_string(&format!("= Document\n:stem: asciimath\n\n{block}"));
+        assert!(html.contains("<div class=\"content\">\n\\$sqrt(4) = 2\\$\n</div>"));
+
+        let html = render_string(&format!("= Document\n:stem: latexmath\n\n{block}"));
+        assert!(html.contains("<div class=\"content\">\n\\[sqrt(4) = 2\\]\n</div>"));
+
+        let html =
+            render_string("= Document\n:stem: latexmath\n\n[asciimath]\n++++\nsqrt(4) = 2\n++++\n");
+        assert!(html.contains("<div class=\"content\">\n\\$sqrt(4) = 2\\$\n</div>"));
+
+        let html = render_string("= Document\n:stem:\n\n[stem]\n++++\na -- b < c\n++++\n");
+        assert!(html.contains("<div class=\"content\">\n\\$a -- b &lt; c\\$\n</div>"));
+    }
+
+    #[test]
+    fn test_render_numbered_equation_and_xref() {
+        let html = render_string(
+            "= Document\n:stem:\n:equation-caption: Equation\n\n[[eq1]]\n.Mass-energy equivalence\n[latexmath]\n++++\nE = mc^2\n++++\n\nAs shown in <<eq1>>.\n",
+        );
+        assert!(html.contains(
+            "<div id=\"eq1\" class=\"stemblock\">\n<div class=\"title\">Equation 1. Mass-energy equivalence</div>\n<div class=\"content\">\n\\[E = mc^2\\]\n</div>"
+        ));
+        assert!(html.contains("As shown in <a href=\"#eq1\">Equation 1</a>."));
+        assert!(html.contains("MathJax.js"));
+    }
+
+    #[test]
+    fn test_render_numbered_equations_in_nested_blocks() {
+        let html = render_string(
+            "= Document\n:stem:\n:equation-caption: Equation\n\n|===\na|\n.First\n[stem]\n++++\nx\n++++\n|===\n\nTerm::\n[[eq2]]\n.Second\n[stem]\n++++\ny\n++++\n\n[[eq3]]\n.Third\n[stem]\n++++\nz\n++++\n\nSee <<eq2>> and <<eq3>>.\n",
+        );
+        assert!(html.contains("Equation 1. First"));
+        assert!(html.contains("Equation 2. Second"));
+        assert!(html.contains("Equation 3. Third"));
+        assert!(html.contains("<a href=\"#eq2\">Equation 2</a>"));
+        assert!(html.contains("<a href=\"#eq3\">Equation 3</a>"));
+
+        let html = render_string(
+            "= Document\n:stem:\n:equation-caption: Equation\n\n.First\n[stem]\n++++\nx\n++++\n\n== Section\n\n[NOTE]\n====\n.Second\n[stem]\n++++\ny\n++++\n====\n\n****\n[[eq3]]\n.Third\n[stem]\n++++\nz\n++++\n****\n\nSee <<eq3>>.\n",
+        );
+        assert!(html.contains("Equation 1. First"));
+        assert!(html.contains("Equation 2. Second"));
+        assert!(html.contains("Equation 3. Third"));
+        assert!(html.contains("<a href=\"#eq3\">Equation 3</a>"));
+    }
+
+    #[test]
+    fn test_render_equations_after_unsetting_caption() {
+        let html = render_string(
+            "= Document\n:stem:\n:equation-caption: Eq\n\n[[a]]\n.First\n[stem]\n++++\nx\n++++\n\n:equation-caption!:\n\n[[b]]\n.Second\n[stem]\n++++\ny\n++++\n\nSee <<a>> and <<b>>.\n",
+        );
+        assert!(html.contains("<div class=\"title\">Eq 1. First</div>"));
+        assert!(html.contains("<div class=\"title\">Second</div>"));
+        assert!(html.contains("See <a href=\"#a\">Eq 1</a> and <a href=\"#b\">[b]</a>."));
+    }
+
+    #[test]
+    fn test_render_numbered_equation_in_table_footer() {
+        let html = render_string(
+            "= Document\n:stem:\n:equation-caption: Equation\n\n[%footer]\n|===\na|\n.First\n[stem]\n++++\nx\n++++\na|\n.Second\n[stem]\n++++\ny\n++++\n|===\n\n[[eq3]]\n.Third\n[stem]\n++++\nz\n++++\n\nSee <<eq3>>.\n",
+        );
+        assert!(html.contains("<tfoot>\n<tr>\n<td><div class=\"content\">"));
+        assert!(html.contains("Equation 2. Second"));
+        assert!(html.contains("Equation 3. Third"));
+        assert!(html.contains("<a href=\"#eq3\">Equation 3</a>"));
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use acdc_converters_common::SafeMode;
+use acdc_parser::{AttributeValue, DocumentAttributes};
+
+use crate::{Processor, RenderOptions};
+
+/// Writes the document's stylesheet into the head: the default one, or the file the
+/// `stylesheet` attribute names, looked up in `stylesdir`.
+///
+/// A custom stylesheet is embedded unless it can't be read, which includes the secure
+/// safe mode and, short of the unsafe mode, files outside the document directory, in
+/// which case it's linked instead. In the unsafe mode, its `@import`
+/// statements are replaced with the stylesheets they import. Any `</` in the embedded
+/// stylesheet is written as the equivalent CSS escape `<\/` so it can't close the
+/// `<style>` element.
+pub(crate) fn render<W: Write>(
+    attributes: &DocumentAttributes,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    let stylesheet = match attributes.get("stylesheet") {
+        Some(AttributeValue::String(stylesheet)) if !stylesheet.is_empty() => stylesheet,
+        _ => {
+            writeln!(w, "<style>")?;
+            writeln!(w, "{}", include_str!("../static/asciidoctor.css"))?;
+            writeln!(w, "</style>")?;
+            return Ok(());
+        }
+    };
+    let href = match attributes.get("stylesdir") {
+        Some(AttributeValue::String(stylesdir)) if !stylesdir.is_empty() => {
+            format!("{}/{stylesheet}", stylesdir.trim_end_matches('/'))
+        }
+        _ => stylesheet.clone(),
+    };
+    let css = if matches!(processor.config.safe_mode, SafeMode::Secure) {
+        None
+    } else {
+        crate::resolve_path(&href, processor, options).and_then(
+            |path| match std::fs::read_to_string(&path) {
+                Ok(css) if matches!(processor.config.safe_mode, SafeMode::Unsafe) => {
+                    Some(inline_imports(&css, path.parent().unwrap_or(Path::new(""))))
+                }
+                Ok(css) => Some(css),
+                Err(e) => {
+                    tracing::warn!(?path, "failed to read stylesheet to embed it: {e}");
+                    None
+                }
+            },
+        )
+    };
+    match css {
+        Some(css) => {
+            writeln!(w, "<style>")?;
+            writeln!(w, "{}", css.trim_end().replace("</", "<\\/"))?;
+            writeln!(w, "</style>")?;
+        }
+        None => writeln!(
+            w,
+            "<link rel=\"stylesheet\" href=\"{}\">",
+            crate::inlines::escape_attribute(&href)
+        )?,
+    }
+    Ok(())
+}
+
+/// Replaces each `@import` statement in `css` with the contents of the stylesheet it
+/// imports, resolved against `dir`. Only one level is resolved: imports in the imported
+/// stylesheets are left as they are.
+///
+/// Remote stylesheets, imports with media queries and stylesheets that can't be read are
+/// kept as imports, moved above everything inlined since CSS ignores an `@import` that
+/// follows other rules.
+fn inline_imports(css: &str, dir: &Path) -> String {
+    let mut imports = String::new();
+    let mut inlined = String::with_capacity(css.len());
+    for line in css.split_inclusive('\n') {
+        let imported = import_target(line).and_then(|target| {
+            let path = dir.join(target);
+            std::fs::read_to_string(&path)
+                .inspect_err(|e| tracing::warn!(?path, "failed to read imported stylesheet: {e}"))
+                .ok()
+        });
+        match imported {
+            Some(imported) => {
+                inlined.push_str(imported.trim_end());
+                inlined.push('\n');
+            }
+            None if line.trim_start().starts_with("@import") => imports.push_str(line),
+            None => inlined.push_str(line),
+        }
+    }
+    imports + &inlined
+}
+
+/// The local file a line consisting of an `@import` statement imports, such as the
+/// `base.css` of `@import "base.css";` or `@import url(base.css);`.
+fn import_target(line: &str) -> Option<PathBuf> {
+    let rest = line
+        .trim()
+        .strip_prefix("@import")?
+        .strip_suffix(';')?
+        .trim();
+    let target = match rest.strip_prefix("url(") {
+        Some(url) => url.strip_suffix(')')?.trim(),
+        None => rest,
+    };
+    let target = target
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .or_else(|| target.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')))
+        .unwrap_or(target);
+    if target.is_empty()
+        || target.contains(['"', '\'', ' ', '('])
+        || target.contains("://")
+        || target.starts_with("//")
+    {
+        return None;
+    }
+    Some(PathBuf::from(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use acdc_converters_common::{Config, Processable, SafeMode, Source};
+
+    use crate::{render_string, Processor};
+
+    #[test]
+    fn test_render_stylesheet_imports() {
+        let dir = std::env::temp_dir().join(format!("acdc-stylesheet-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("styles")).unwrap();
+        std::fs::write(
+            dir.join("styles/custom.css"),
+            "@import \"base.css\";\n@import url(https://example.com/fonts.css);\nh1 { color: red; }\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("styles/base.css"), "body { margin: 0; }\n").unwrap();
+        let file = dir.join("document.adoc");
+        std::fs::write(
+            &file,
+            "= Document\n:stylesdir: styles\n:stylesheet: custom.css\n\nText.\n",
+        )
+        .unwrap();
+        let processor = |safe_mode| {
+            Processor::new(Config {
+                source: Source::Files(vec![file.clone()]),
+                safe_mode,
+                ..Config::default()
+            })
+            .output()
+            .unwrap()
+        };
+
+        let html = processor(SafeMode::Unsafe);
+        assert!(html.contains(
+            "<style>\n@import url(https://example.com/fonts.css);\nbody { margin: 0; }\nh1 { color: red; }\n</style>"
+        ));
+        assert!(!html.contains("Asciidoctor default stylesheet"));
+
+        let html = processor(SafeMode::Safe);
+        assert!(html.contains("<style>\n@import \"base.css\";\n@import url("));
+
+        let html = processor(SafeMode::Secure);
+        assert!(html.contains("<link rel=\"stylesheet\" href=\"styles/custom.css\">"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_stylesheet_outside_docdir() {
+        let dir = std::env::temp_dir().join(format!("acdc-stylesheet-jail-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        std::fs::write(dir.join("secret.css"), "p { color: red; }\n").unwrap();
+        let file = dir.join("docs/document.adoc");
+        let secret = dir.join("secret.css");
+        let processor = |stylesheet: &str, safe_mode| {
+            std::fs::write(
+                &file,
+                format!("= Document\n:stylesheet: {stylesheet}\n\nText.\n"),
+            )
+            .unwrap();
+            Processor::new(Config {
+                source: Source::Files(vec![file.clone()]),
+                safe_mode,
+                ..Config::default()
+            })
+            .output()
+            .unwrap()
+        };
+
+        let html = processor("../secret.css", SafeMode::Unsafe);
+        assert!(html.contains("<style>\np { color: red; }\n</style>"));
+
+        for safe_mode in [SafeMode::Safe, SafeMode::Server] {
+            let html = processor("../secret.css", safe_mode.clone());
+            assert!(!html.contains("color: red"));
+            assert!(html.contains("<link rel=\"stylesheet\" href=\"../secret.css\">"));
+
+            let html = processor(&secret.display().to_string(), safe_mode);
+            assert!(!html.contains("color: red"));
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_stylesheet_escapes() {
+        let dir = std::env::temp_dir().join(format!("acdc-stylesheet-esc-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("x.css"),
+            "p::after { content: \"</style><b>\"; }\n",
+        )
+        .unwrap();
+        let html = Processor::new(Config {
+            source: Source::String(format!(
+                "= Document\n:stylesdir: {}\n:stylesheet: x.css\n\nText.\n",
+                dir.display()
+            )),
+            ..Config::default()
+        })
+        .output()
+        .unwrap();
+        assert!(html.contains("content: \"<\\/style><b>\";"));
+
+        let html = Processor::new(Config {
+            source: Source::String("= Document\n:stylesheet: a\"b.css\n\nText.\n".to_string()),
+            safe_mode: SafeMode::Secure,
+            ..Config::default()
+        })
+        .output()
+        .unwrap();
+        assert!(html.contains("<link rel=\"stylesheet\" href=\"a&quot;b.css\">"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_default_stylesheet() {
+        let html = render_string("= Document\n\nText.\n");
+        assert!(html.contains("<style>\n/*! Asciidoctor default stylesheet"));
+    }
+}
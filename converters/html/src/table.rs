@@ -1,46 +1,351 @@
 use std::io::Write;
 
-use acdc_parser::Table;
+use acdc_parser::{
+    AttributeValue, ColumnFormat, ColumnWidth, DelimitedBlock, HorizontalAlignment, Table,
+    TableColumn, VerticalAlignment,
+};
 
 use crate::{Processor, Render, RenderOptions};
 
-impl Render for Table {
-    type Error = crate::Error;
-
-    fn render<W: Write>(
-        &self,
-        w: &mut W,
-        processor: &Processor,
-        options: &RenderOptions,
-    ) -> Result<(), Self::Error> {
-        writeln!(w, "<table>")?;
-        if let Some(header) = &self.header {
-            writeln!(w, "<thead>")?;
-            writeln!(w, "<tr>")?;
-            for cell in &header.columns {
-                write!(w, "<th>")?;
-                for block in &cell.content {
-                    block.render(w, processor, options)?;
-                }
-                writeln!(w, "</th>")?;
+/// Renders a table, using the title of the enclosing delimited block (if any) as the
+/// table `<caption>`.
+///
+/// Titled tables are numbered (`Table N.`) unless a `caption` attribute is set on the
+/// block, in which case that is used as the prefix instead.
+pub(crate) fn render_table<W: Write>(
+    block: &DelimitedBlock,
+    table: &Table,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    let id = crate::xref::id_attribute(&block.metadata);
+    match table_classes(block) {
+        Some(classes) => writeln!(w, "<table{id} class=\"{classes}\">")?,
+        None => writeln!(w, "<table{id}>")?,
+    }
+    if !block.title.is_empty() {
+        write!(w, "<caption class=\"title\">")?;
+        let caption = crate::caption::prefix(
+            "table",
+            "Table",
+            &block.metadata.attributes,
+            &processor.table_counter,
+            processor,
+        );
+        write!(w, "{caption}")?;
+        crate::inlines::render_inlines(&block.title, w, processor, options)?;
+        writeln!(w, "</caption>")?;
+    }
+    let widths = column_widths(table);
+    if !widths.is_empty() {
+        writeln!(w, "<colgroup>")?;
+        for width in widths {
+            match width {
+                Some(width) => writeln!(w, "<col style=\"width: {width}%;\">")?,
+                None => writeln!(w, "<col>")?,
             }
-            writeln!(w, "</tr>")?;
-            writeln!(w, "</thead>")?;
         }
-        writeln!(w, "<tbody>")?;
-        for row in &self.rows {
-            writeln!(w, "<tr>")?;
-            for cell in &row.columns {
-                write!(w, "<td>")?;
-                for block in &cell.content {
-                    block.render(w, processor, options)?;
+        writeln!(w, "</colgroup>")?;
+    }
+    let mut spanned = Vec::new();
+    if let Some(header) = &table.header {
+        writeln!(w, "<thead>")?;
+        writeln!(w, "<tr>")?;
+        render_row(
+            &header.columns,
+            &table.columns,
+            true,
+            &mut spanned,
+            w,
+            processor,
+            options,
+        )?;
+        writeln!(w, "</tr>")?;
+        writeln!(w, "</thead>")?;
+    }
+    writeln!(w, "<tbody>")?;
+    for row in &table.rows {
+        writeln!(w, "<tr>")?;
+        render_row(
+            &row.columns,
+            &table.columns,
+            false,
+            &mut spanned,
+            w,
+            processor,
+            options,
+        )?;
+        writeln!(w, "</tr>")?;
+    }
+    writeln!(w, "</tbody>")?;
+    if let Some(footer) = &table.footer {
+        writeln!(w, "<tfoot>")?;
+        writeln!(w, "<tr>")?;
+        render_row(
+            &footer.columns,
+            &table.columns,
+            false,
+            &mut spanned,
+            w,
+            processor,
+            options,
+        )?;
+        writeln!(w, "</tr>")?;
+        writeln!(w, "</tfoot>")?;
+    }
+    writeln!(w, "</table>")?;
+    Ok(())
+}
+
+/// Returns the print orientation classes of a table: `rotate` for `[.rotate]` or
+/// `[%rotate]` and `landscape` for `orientation=landscape`.
+fn table_classes(block: &DelimitedBlock) -> Option<String> {
+    let mut classes = Vec::new();
+    if block.metadata.roles.iter().any(|r| r == "rotate")
+        || block.metadata.options.iter().any(|o| o == "rotate")
+    {
+        classes.push("rotate");
+    }
+    if let Some(AttributeValue::String(orientation)) = block.metadata.attributes.get("orientation")
+    {
+        if orientation == "landscape" {
+            classes.push("landscape");
+        }
+    }
+    (!classes.is_empty()).then(|| classes.join(" "))
+}
+
+/// Returns the width of each column of the table, as a percentage of the table width,
+/// or `None` for an autowidth column (`~`), which is as wide as its content.
+///
+/// The widths are the proportions (or percentages) given by the `cols` attribute
+/// (`cols="1,3"`), or are all the same when there isn't one. In that case the number of
+/// columns comes from the first row, where a cell spanning `n` columns counts as `n` of
+/// them but takes a single cell.
+fn column_widths(table: &Table) -> Vec<Option<f64>> {
+    let weights = if table.columns.is_empty() {
+        let first_row = table
+            .header
+            .iter()
+            .chain(&table.rows)
+            .chain(&table.footer)
+            .next();
+        let ncols = first_row.map_or(0, |row| {
+            row.columns.iter().map(|cell| cell.colspan).sum::<usize>()
+        });
+        vec![Some(1.0); ncols]
+    } else {
+        table
+            .columns
+            .iter()
+            .map(|column| match column.width {
+                ColumnWidth::Proportional(weight) | ColumnWidth::Percentage(weight) => {
+                    Some(f64::from(u32::try_from(weight.max(1)).unwrap_or(u32::MAX)))
                 }
-                writeln!(w, "</td>")?;
-            }
-            writeln!(w, "</tr>")?;
+                ColumnWidth::Auto => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let total = weights.iter().flatten().sum::<f64>();
+    if total <= 0.0 {
+        return weights.iter().map(|_| None).collect();
+    }
+    // Rounding every width but the last, which takes whatever is left, keeps the total
+    // at exactly 100%.
+    let mut widths = weights
+        .iter()
+        .map(|weight| weight.map(|weight| (weight * 100.0 / total * 10_000.0).round() / 10_000.0))
+        .collect::<Vec<_>>();
+    if let Some(last) = widths.iter().rposition(Option::is_some) {
+        let rest = widths
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != last)
+            .filter_map(|(_, width)| *width)
+            .sum::<f64>();
+        widths[last] = Some(((100.0 - rest) * 10_000.0).round() / 10_000.0);
+    }
+    widths
+}
+
+/// Renders the cells of a row, as header cells in the header row and for `h`-style
+/// cells wherever they are.
+///
+/// `spanned` holds the rows still taken in each column by cells spanning down from
+/// earlier rows, which the cells of this row skip over, and is updated for the next row.
+fn render_row<W: Write>(
+    cells: &[TableColumn],
+    columns: &[ColumnFormat],
+    header: bool,
+    spanned: &mut Vec<usize>,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    let skip_spanned = |mut column: usize, spanned: &[usize]| {
+        while spanned.get(column).is_some_and(|rows| *rows > 0) {
+            column += 1;
+        }
+        column
+    };
+    let mut started = Vec::new();
+    let mut column = skip_spanned(0, spanned);
+    for cell in cells {
+        let tag = if header || cell.style.as_deref() == Some("h") {
+            "th"
+        } else {
+            "td"
+        };
+        render_cell(cell, columns.get(column), tag, w, processor, options)?;
+        if cell.rowspan > 1 {
+            started.push((column..column + cell.colspan, cell.rowspan - 1));
         }
-        writeln!(w, "</tbody>")?;
-        writeln!(w, "</table>")?;
-        Ok(())
+        column = skip_spanned(column + cell.colspan, spanned);
+    }
+    for rows in spanned.iter_mut() {
+        *rows = rows.saturating_sub(1);
+    }
+    for (range, rows) in started {
+        if spanned.len() < range.end {
+            spanned.resize(range.end, 0);
+        }
+        spanned[range].fill(rows);
+    }
+    Ok(())
+}
+
+/// Returns the alignment classes of the cells of a column, when its spec aligns them
+/// some other way than to the top left.
+fn alignment_classes(column: &ColumnFormat) -> Option<String> {
+    if column.halign == HorizontalAlignment::default()
+        && column.valign == VerticalAlignment::default()
+    {
+        return None;
+    }
+    let halign = match column.halign {
+        HorizontalAlignment::Left => "left",
+        HorizontalAlignment::Center => "center",
+        HorizontalAlignment::Right => "right",
+    };
+    let valign = match column.valign {
+        VerticalAlignment::Top => "top",
+        VerticalAlignment::Middle => "middle",
+        VerticalAlignment::Bottom => "bottom",
+    };
+    Some(format!("tableblock halign-{halign} valign-{valign}"))
+}
+
+/// Renders a table cell as a `tag` element, spanning columns and rows as its specifier
+/// says and aligned as its `column` says. `a`-style cells hold full blocks (nested
+/// tables included), so they get wrapped in a content `<div>`.
+fn render_cell<W: Write>(
+    cell: &TableColumn,
+    column: Option<&ColumnFormat>,
+    tag: &str,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    write!(w, "<{tag}")?;
+    if let Some(classes) = column.and_then(alignment_classes) {
+        write!(w, " class=\"{classes}\"")?;
+    }
+    if cell.colspan > 1 {
+        write!(w, " colspan=\"{}\"", cell.colspan)?;
+    }
+    if cell.rowspan > 1 {
+        write!(w, " rowspan=\"{}\"", cell.rowspan)?;
+    }
+    write!(w, ">")?;
+    let asciidoc = cell.style.as_deref() == Some("a");
+    if asciidoc {
+        writeln!(w, "<div class=\"content\">")?;
+    }
+    for block in &cell.content {
+        block.render(w, processor, options)?;
+    }
+    if asciidoc {
+        write!(w, "</div>")?;
+    }
+    writeln!(w, "</{tag}>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_table_caption() {
+        let html = render_string(".First\n|===\n|a |b\n|===\n\n.Second\n|===\n|c |d\n|===\n");
+        assert!(html.contains("<table>\n<caption class=\"title\">Table 1. First</caption>"));
+        assert!(html.contains("<table>\n<caption class=\"title\">Table 2. Second</caption>"));
+
+        let html = render_string("[caption=\"<b>Tab</b> \"]\n.First\n|===\n|a |b\n|===\n");
+        assert!(html.contains("<caption class=\"title\">&lt;b&gt;Tab&lt;/b&gt; First</caption>"));
+    }
+
+    #[test]
+    fn test_render_rotated_table() {
+        let html = render_string("[.rotate]\n|===\n|a |b\n|===\n");
+        assert!(html.contains("<table class=\"rotate\">"));
+
+        let html = render_string("[%rotate,orientation=landscape]\n|===\n|a |b\n|===\n");
+        assert!(html.contains("<table class=\"rotate landscape\">"));
+    }
+
+    #[test]
+    fn test_render_nested_table() {
+        let html = render_string(
+            ".Outer\n[cols=\"1,1\"]\n|===\n|Outer cell\na|\n!===\n!a !b\n!c !d\n!===\n|===\n\n.Next\n|===\n|e |f\n|===\n",
+        );
+        assert!(html.contains("<caption class=\"title\">Table 1. Outer</caption>"));
+        assert!(html.contains("<caption class=\"title\">Table 2. Next</caption>"));
+        assert!(html.contains("<td><div class=\"content\">\n<div>\n<table>\n<colgroup>\n<col style=\"width: 50%;\">\n<col style=\"width: 50%;\">\n</colgroup>\n<tbody>\n<tr>\n<td>"));
+        assert_eq!(html.matches("<table>").count(), 3);
+        assert_eq!(html.matches("<td>").count(), 8);
+    }
+
+    #[test]
+    fn test_render_header_colspan() {
+        let html = render_string(
+            "[%header,cols=\"1,1\"]\n|===\n2+|Group\nh|First h|Second\n|a |b\n|===\n",
+        );
+        assert!(html.contains("<thead>\n<tr>\n<th colspan=\"2\">"));
+        assert!(html.contains("<tbody>\n<tr>\n<th>"));
+        assert_eq!(html.matches("<th>").count(), 2);
+        assert_eq!(html.matches("<td>").count(), 2);
+    }
+
+    #[test]
+    fn test_render_spanned_cells() {
+        let html = render_string("|===\n2+|wide |c\n.2+|tall |e |f\n|g |h\n|===\n");
+        assert!(html.contains(
+            "<colgroup>\n<col style=\"width: 33.3333%;\">\n<col style=\"width: 33.3333%;\">\n<col style=\"width: 33.3334%;\">\n</colgroup>"
+        ));
+        assert!(html.contains("<td colspan=\"2\">"));
+        assert!(html.contains("<td rowspan=\"2\">"));
+        assert_eq!(html.matches("<tr>").count(), 3);
+        assert_eq!(html.matches("<td").count(), 7);
+
+        let html = render_string("[cols=\"1,>1\"]\n|===\n.2+|tall |b\n|c\n|===\n");
+        assert!(html.contains(
+            "<tr>\n<td class=\"tableblock halign-right valign-top\"><div class=\"paragraph\">\n<p>c</p>"
+        ));
+
+        let html = render_string("[cols=\"1,3\"]\n|===\n|a |b\n|===\n");
+        assert!(html.contains("<col style=\"width: 25%;\">\n<col style=\"width: 75%;\">"));
+    }
+
+    #[test]
+    fn test_render_column_specs() {
+        let html = render_string("[cols=\"~,>.^2,a\"]\n|===\n|a |b |* item\n|===\n");
+        assert!(html.contains(
+            "<colgroup>\n<col>\n<col style=\"width: 66.6667%;\">\n<col style=\"width: 33.3333%;\">\n</colgroup>"
+        ));
+        assert!(html.contains("<tr>\n<td>"));
+        assert!(html.contains("<td class=\"tableblock halign-right valign-middle\">"));
+        assert!(html.contains("<td><div class=\"content\">\n<div class=\"ulist\">"));
     }
 }
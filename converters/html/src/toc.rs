@@ -0,0 +1,247 @@
+use std::{collections::HashMap, io::Write};
+
+use acdc_converters_common::Doctype;
+use acdc_parser::{AttributeValue, Block, Document, DocumentAttributes, TocEntry};
+
+use crate::{sectnum::SectionNumbers, Processor, RenderOptions};
+
+const DEFAULT_TOC_TITLE: &str = "Table of Contents";
+const DEFAULT_TOC_LEVELS: u8 = 2;
+
+/// Returns where the table of contents should be placed, based on the `toc` attribute.
+///
+/// `None` means there is no table of contents. An empty `toc` attribute is the same as
+/// `auto`.
+pub(crate) fn position(attributes: &DocumentAttributes) -> Option<&str> {
+    match attributes.get("toc")? {
+        AttributeValue::Bool(true) => Some("auto"),
+        AttributeValue::String(position) if position.is_empty() => Some("auto"),
+        AttributeValue::String(position) => Some(position.as_str()),
+        _ => None,
+    }
+}
+
+/// Whether the table of contents is rendered as a sidebar (`toc2`) rather than in the
+/// flow of the document.
+pub(crate) fn is_sidebar(position: &str) -> bool {
+    matches!(position, "left" | "right")
+}
+
+/// Where the table of contents is rendered for a `toc` position: the header, for `auto`
+/// and the sidebars, or the end of the preamble or the `toc::[]` macro.
+fn placement(position: &str) -> &str {
+    match position {
+        "preamble" | "macro" => position,
+        _ => "auto",
+    }
+}
+
+/// Renders the table of contents, if the `toc` attribute places it at `placement`
+/// (`auto`, `preamble` or `macro`).
+pub(crate) fn render<W: Write>(
+    document: &Document,
+    placement: &str,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    let Some(position) = position(&document.attributes) else {
+        return Ok(());
+    };
+    if self::placement(position) != placement {
+        return Ok(());
+    }
+    let entries = document.toc_tree();
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let levels = match document.attributes.get("toclevels") {
+        Some(AttributeValue::String(levels)) => levels.parse().unwrap_or(DEFAULT_TOC_LEVELS),
+        _ => DEFAULT_TOC_LEVELS,
+    };
+    let title = match document.attributes.get("toc-title") {
+        Some(AttributeValue::String(title)) => crate::inlines::escape_attribute(title),
+        _ => DEFAULT_TOC_TITLE.to_string(),
+    };
+
+    if is_sidebar(position) {
+        writeln!(w, "<div id=\"toc\" class=\"toc2\">")?;
+        writeln!(w, "<div id=\"toctitle\">{title}</div>")?;
+    } else {
+        // In the flow of the document the title sits just above the top level sections,
+        // so it takes its heading level from the doctype. Where the `toc::[]` macro puts
+        // it, it's titled like any other block.
+        let class = match (position, processor.doctype()) {
+            ("macro", _) => "title",
+            (_, Doctype::Book) => "h2",
+            _ => "h3",
+        };
+        writeln!(w, "<div id=\"toc\" class=\"toc\">")?;
+        writeln!(w, "<div id=\"toctitle\" class=\"{class}\">{title}</div>")?;
+    }
+    let mut numbers = HashMap::new();
+    section_numbers(
+        &document.blocks,
+        &mut SectionNumbers::default(),
+        &processor.document_attributes.borrow(),
+        &mut numbers,
+    );
+    render_entries(&entries, levels, &numbers, w, processor, options)?;
+    writeln!(w, "</div>")?;
+    Ok(())
+}
+
+/// Maps the id of every section in `blocks` to the number shown before its heading, so
+/// that the entries match the headings when `sectnums` is set.
+///
+/// The table of contents is rendered before the sections are, so it numbers them with
+/// its own `SectionNumbers`, in the same order.
+fn section_numbers(
+    blocks: &[Block],
+    section_numbers: &mut SectionNumbers,
+    attributes: &DocumentAttributes,
+    numbers: &mut HashMap<String, String>,
+) {
+    for block in blocks {
+        if let Block::Section(section) = block {
+            if let Some(number) = section_numbers.next(section, attributes) {
+                numbers.insert(section.id(), number);
+            }
+            self::section_numbers(&section.content, section_numbers, attributes, numbers);
+        }
+    }
+}
+
+fn render_entries<W: Write>(
+    entries: &[TocEntry],
+    levels: u8,
+    numbers: &HashMap<String, String>,
+    w: &mut W,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<(), crate::Error> {
+    let Some(first) = entries.first() else {
+        return Ok(());
+    };
+    if first.level > levels {
+        return Ok(());
+    }
+    writeln!(w, "<ul class=\"sectlevel{}\">", first.level)?;
+    for entry in entries {
+        write!(w, "<li><a href=\"#{}\">", entry.id)?;
+        if let Some(number) = numbers.get(&entry.id) {
+            write!(w, "{number}")?;
+        }
+        crate::inlines::render_inlines(&entry.title, w, processor, options)?;
+        write!(w, "</a>")?;
+        if entry
+            .children
+            .first()
+            .is_some_and(|child| child.level <= levels)
+        {
+            writeln!(w)?;
+            render_entries(&entry.children, levels, numbers, w, processor, options)?;
+        }
+        writeln!(w, "</li>")?;
+    }
+    writeln!(w, "</ul>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_toc_title_in_flow() {
+        let html = render_string("= Document\n:toc:\n\n== First\n\ncontent\n");
+        assert!(html.contains("<div id=\"toc\" class=\"toc\">\n<div id=\"toctitle\" class=\"h3\">Table of Contents</div>"));
+        assert!(html.contains("<li><a href=\"#_first\">First</a></li>"));
+    }
+
+    #[test]
+    fn test_render_toc_title_sidebar() {
+        let html = render_string("= Document\n:toc: left\n\n== First\n\ncontent\n");
+        assert!(html.contains("<body class=\"article toc2 toc-left\">"));
+        assert!(html.contains(
+            "<div id=\"toc\" class=\"toc2\">\n<div id=\"toctitle\">Table of Contents</div>"
+        ));
+    }
+
+    #[test]
+    fn test_render_toc_in_preamble() {
+        let html = render_string(
+            "= Document\n:toc: preamble\n:toc-title: Contents\n\nIntro.\n\n== First\n\ncontent\n",
+        );
+        assert_eq!(html.matches("<div id=\"toc\"").count(), 1);
+        assert!(html.contains(
+            "Intro.</p>\n</div>\n</div>\n<div id=\"toc\" class=\"toc\">\n<div id=\"toctitle\" class=\"h3\">Contents</div>"
+        ));
+    }
+
+    #[test]
+    fn test_render_toc_macro() {
+        let html = render_string(
+            "= Document\n:toc: macro\n:toclevels: 1\n\n== First\n\ntoc::[]\n\n=== Nested\n\ncontent\n",
+        );
+        assert_eq!(html.matches("<div id=\"toc\"").count(), 1);
+        assert!(html.contains(
+            "<div class=\"sectionbody\">\n<div id=\"toc\" class=\"toc\">\n<div id=\"toctitle\" class=\"title\">Table of Contents</div>\n<ul class=\"sectlevel1\">\n<li><a href=\"#_first\">First</a></li>\n</ul>\n</div>"
+        ));
+
+        let html = render_string("= Document\n:toc:\n\n== First\n\ntoc::[]\n");
+        assert_eq!(html.matches("<div id=\"toc\"").count(), 1);
+    }
+
+    #[test]
+    fn test_render_toc_title_escaped() {
+        let html =
+            render_string("= Document\n:toc:\n:toc-title: <b>X</b> & Y\n\n== First\n\ncontent\n");
+        assert!(
+            html.contains("<div id=\"toctitle\" class=\"h3\">&lt;b&gt;X&lt;/b&gt; &amp; Y</div>")
+        );
+
+        let html =
+            render_string("= Document\n:toc: right\n:toc-title: <b>X</b>\n\n== First\n\ncontent\n");
+        assert!(html.contains("<div id=\"toctitle\">&lt;b&gt;X&lt;/b&gt;</div>"));
+    }
+
+    #[test]
+    fn test_render_toc_invalid_levels() {
+        let html = render_string(
+            "= Document\n:toc:\n:toclevels: abc\n\n== First\n\n=== Second\n\n==== Third\n\ncontent\n",
+        );
+        assert!(html.contains("<a href=\"#_second\">Second</a>"));
+        assert!(!html.contains("<a href=\"#_third\">Third</a>"));
+    }
+
+    #[test]
+    fn test_render_toc_formatted_title() {
+        let html = render_string("= Document\n:toc:\n\n== The `code` section\n\ncontent\n");
+        assert!(html
+            .contains("<li><a href=\"#_the_code_section\">The <code>code</code> section</a></li>"));
+        assert!(html.contains("<h2 id=\"_the_code_section\">"));
+    }
+
+    #[test]
+    fn test_render_notoc_section() {
+        let html = render_string(
+            "= Document\n:toc:\n:sectnums:\n\n== First\n\ncontent\n\n[%unnumbered%notoc]\n== Colophon Notes\n\nSee <<_first>>.\n\n== Last\n\nBack to <<_colophon_notes>>.\n",
+        );
+        assert!(!html.contains("<a href=\"#_colophon_notes\">Colophon Notes</a></li>"));
+        assert!(html.contains("<h2 id=\"_colophon_notes\">Colophon Notes</h2>"));
+        assert!(html.contains("<h2 id=\"_last\">2. Last</h2>"));
+        assert!(html.contains("Back to <a href=\"#_colophon_notes\">Colophon Notes</a>."));
+    }
+
+    #[test]
+    fn test_render_toc_section_numbers() {
+        let html = render_string(
+            "= Document\n:toc:\n:sectnums:\n\n== Intro\n\n=== Background\n\ntext\n\n[appendix]\n== Extra\n\ntext\n",
+        );
+        assert!(html.contains("<li><a href=\"#_intro\">1. Intro</a>"));
+        assert!(html.contains("<li><a href=\"#_background\">1.1. Background</a></li>"));
+        assert!(html.contains("<li><a href=\"#_extra\">Appendix A: Extra</a></li>"));
+        assert!(html.contains("<h2 id=\"_intro\">1. Intro</h2>"));
+    }
+}
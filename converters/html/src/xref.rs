@@ -0,0 +1,200 @@
+use std::{collections::HashMap, io::Write};
+
+use acdc_parser::{
+    AttributeValue, Block, BlockMetadata, CrossReference, Document, DocumentAttributes,
+};
+
+use crate::{
+    inlines::{escape_attribute, substitution_text},
+    Processor, Render, RenderOptions,
+};
+
+impl Render for CrossReference {
+    type Error = crate::Error;
+
+    fn render<W: Write>(
+        &self,
+        w: &mut W,
+        processor: &Processor,
+        options: &RenderOptions,
+    ) -> Result<(), Self::Error> {
+        let text = match &self.text {
+            Some(text) => substitution_text(text),
+            None => processor
+                .references
+                .borrow()
+                .get(&self.target)
+                .cloned()
+                .unwrap_or_else(|| format!("[{}]", escape_attribute(&self.target))),
+        };
+        if options.inlines_basic {
+            write!(w, "{text}")?;
+        } else {
+            let href = href(&self.target, &processor.document_attributes.borrow());
+            write!(w, "<a href=\"{}\">{text}</a>", escape_attribute(&href))?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds the link to an xref target, which points to another document when it has a
+/// path before the `#` or is an `.adoc` path, e.g. `other.adoc#install` links to
+/// `other.html#install` and `other.adoc` to `other.html`.
+///
+/// The suffix of the linked document is `relfilesuffix`, falling back to `outfilesuffix`
+/// and then `.html`, so that `:relfilesuffix: /` links to `other/#install`.
+fn href(target: &str, attributes: &DocumentAttributes) -> String {
+    let (path, id) = match target.split_once('#') {
+        Some((path, id)) => (path, Some(id)),
+        None if target.ends_with(".adoc") => (target, None),
+        None => ("", Some(target)),
+    };
+    if path.is_empty() {
+        return format!("#{}", id.unwrap_or_default());
+    }
+    let path = path.strip_suffix(".adoc").unwrap_or(path);
+    let suffix = ["relfilesuffix", "outfilesuffix"]
+        .into_iter()
+        .find_map(|name| match attributes.get(name) {
+            Some(AttributeValue::String(suffix)) => Some(suffix.as_str()),
+            _ => None,
+        })
+        .unwrap_or(".html");
+    match id.filter(|id| !id.is_empty()) {
+        Some(id) => format!("{path}{suffix}#{id}"),
+        None => format!("{path}{suffix}"),
+    }
+}
+
+/// Collects the text that a cross reference without its own text shows for each id in
+/// `document`: the title of a section, the caption of a numbered equation, or the label
+/// of an anchor (`[[id,label]]`).
+///
+/// The equations are the ones the processor has already numbered for `document`.
+pub(crate) fn collect_references(
+    document: &Document,
+    processor: &Processor,
+    options: &RenderOptions,
+) -> Result<HashMap<String, String>, crate::Error> {
+    let mut references = HashMap::new();
+    for (id, label) in document.references() {
+        if let Some(label) = label {
+            references.insert(id, escape_attribute(&label));
+        }
+    }
+    collect_sections(&document.blocks, processor, options, &mut references)?;
+    for equation in processor.equations.borrow().iter() {
+        if let (Some(id), Some(caption)) = (&equation.id, &equation.caption) {
+            references.insert(id.clone(), caption.clone());
+        }
+    }
+    Ok(references)
+}
+
+/// Maps the id of every section in `blocks` to its title, including the sections left
+/// out of the table of contents.
+fn collect_sections(
+    blocks: &[Block],
+    processor: &Processor,
+    options: &RenderOptions,
+    references: &mut HashMap<String, String>,
+) -> Result<(), crate::Error> {
+    for block in blocks {
+        if let Block::Section(section) = block {
+            let mut title = Vec::new();
+            crate::inlines::render_inlines(
+                &section.title,
+                &mut title,
+                processor,
+                &RenderOptions {
+                    inlines_basic: true,
+                    ..*options
+                },
+            )?;
+            references.insert(section.id(), String::from_utf8(title)?);
+            collect_sections(&section.content, processor, options, references)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the id of a block, from its attribute list or an anchor before it.
+pub(crate) fn block_id(metadata: &BlockMetadata) -> Option<&str> {
+    metadata
+        .id
+        .as_ref()
+        .or_else(|| metadata.anchors.first())
+        .map(|anchor| anchor.id.as_str())
+}
+
+/// Returns the ` id="..."` attribute of the element a block is rendered as, which is
+/// empty when the block has no id.
+pub(crate) fn id_attribute(metadata: &BlockMetadata) -> String {
+    block_id(metadata).map_or_else(String::new, |id| format!(" id=\"{id}\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render_string;
+
+    #[test]
+    fn test_render_xref_to_section() {
+        let html = render_string(
+            "= Document\n\n== Getting Started\n\nSee <<_getting_started>> or <<_getting_started,here>>.\n\nAlso <<missing>>.\n",
+        );
+        assert!(html.contains("<a href=\"#_getting_started\">Getting Started</a>"));
+        assert!(html.contains("<a href=\"#_getting_started\">here</a>"));
+        assert!(html.contains("<a href=\"#missing\">[missing]</a>"));
+
+        let html = render_string("See <<a\"b>> and xref:x\"y.adoc#z[there].\n");
+        assert!(html.contains("<a href=\"#a&quot;b\">[a&quot;b]</a>"));
+        assert!(html.contains("<a href=\"x&quot;y.html#z\">there</a>"));
+
+        let html = render_string("See xref:reference.adoc#options[the options].\n");
+        assert!(html.contains("<a href=\"reference.html#options\">the options</a>"));
+    }
+
+    #[test]
+    fn test_render_xref_file_suffix() {
+        let html =
+            render_string("= Document\n:outfilesuffix: .htm\n\nSee xref:other.adoc#sec[other].\n");
+        assert!(html.contains("<a href=\"other.htm#sec\">other</a>"));
+
+        let html = render_string(
+            "= Document\n:outfilesuffix: .htm\n:relfilesuffix: /\n\nSee xref:other.adoc#sec[other].\n",
+        );
+        assert!(html.contains("<a href=\"other/#sec\">other</a>"));
+
+        let html = render_string(
+            "= Document\n:relfilesuffix: /\n\nSee xref:other.adoc[Other] and <<other.adoc#,X>>.\n",
+        );
+        assert!(html.contains("<a href=\"other/\">Other</a>"));
+        assert!(html.contains("<a href=\"other/\">X</a>"));
+
+        let html = render_string("See xref:other.adoc[Other] and <<other.adoc#,X>>.\n");
+        assert!(html.contains("<a href=\"other.html\">Other</a>"));
+        assert!(html.contains("<a href=\"other.html\">X</a>"));
+    }
+
+    #[test]
+    fn test_render_block_ids() {
+        let html = render_string(
+            "[[intro]]\nA paragraph.\n\n[#steps]\n* one\n\n[#data]\n|===\n|a\n|===\n\n[[sample]]\n----\ncode\n----\n\nSee <<steps>>.\n",
+        );
+        assert!(html.contains("<div id=\"intro\" class=\"paragraph\">"));
+        assert!(html.contains("<div id=\"steps\" class=\"ulist\">"));
+        assert!(html.contains("<table id=\"data\">"));
+        assert!(html.contains("<div id=\"sample\" class=\"literalblock\">"));
+        assert!(html.contains("<a href=\"#steps\">[steps]</a>"));
+    }
+
+    #[test]
+    fn test_render_inline_anchors() {
+        let html = render_string(
+            "Some text [[mark]] here and [[spot, The Spot]]there.\n\nSee <<mark>> and <<spot>>.\n",
+        );
+        assert!(html.contains("Some text <a id=\"mark\"></a> here and <a id=\"spot\"></a>there."));
+        assert!(html.contains("<a href=\"#mark\">[mark]</a>"));
+        assert!(html.contains("<a href=\"#spot\">The Spot</a>"));
+    }
+}
@@ -14,9 +14,13 @@
 ///
 /// NOTE: at present we do not do anything with the type - we simply pass the contents to
 /// the parser and write the output to `stdout`.
-use std::io::{self, BufReader, Write};
+use std::{
+    io::{self, BufReader, Write},
+    path::Path,
+};
 
 use acdc_converters_common::{Config, Processable, Source};
+use acdc_parser::Document;
 use serde::Deserialize;
 
 #[derive(Debug, thiserror::Error)]
@@ -47,7 +51,6 @@ impl Processable for Processor {
     type Config = Config;
     type Error = Error;
 
-    #[must_use]
     fn new(config: Config) -> Self {
         Self { config }
     }
@@ -78,4 +81,14 @@ impl Processable for Processor {
     fn output(&self) -> Result<String, Self::Error> {
         unimplemented!("output purposefully not implemented for the tck processor")
     }
+
+    fn convert<W: Write>(
+        &self,
+        doc: &Document,
+        _source_file: Option<&Path>,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        serde_json::to_writer(writer, doc)?;
+        Ok(())
+    }
 }
@@ -0,0 +1,27 @@
+use std::io::Write;
+
+use crate::{Options, Render};
+
+impl Render for acdc_parser::Admonition {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
+        crate::write_wrapped(w, options, |w, options| {
+            let label = self.variant.to_string().to_uppercase();
+            crate::print_styled(w, options.theme().admonition.apply(label), options)?;
+            write!(w, ": ")?;
+            if !self.title.is_empty() {
+                for node in &self.title {
+                    node.render(w, options)?;
+                }
+                writeln!(w)?;
+            }
+            let last_index = self.blocks.len().saturating_sub(1);
+            for (i, block) in self.blocks.iter().enumerate() {
+                block.render(w, options)?;
+                if i != last_index {
+                    writeln!(w)?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
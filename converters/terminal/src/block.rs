@@ -1,14 +1,17 @@
 use std::io::Write;
 
-use crate::Render;
+use crate::{Options, Render};
 
 impl Render for acdc_parser::Block {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
         match self {
-            acdc_parser::Block::Paragraph(p) => p.render(w),
-            acdc_parser::Block::DelimitedBlock(d) => d.render(w),
-            acdc_parser::Block::Section(s) => s.render(w),
-            acdc_parser::Block::UnorderedList(u) => u.render(w),
+            acdc_parser::Block::Paragraph(p) => p.render(w, options),
+            acdc_parser::Block::DelimitedBlock(d) => d.render(w, options),
+            acdc_parser::Block::Section(s) => s.render(w, options),
+            acdc_parser::Block::UnorderedList(u) => u.render(w, options),
+            acdc_parser::Block::CalloutList(c) => c.render(w, options),
+            acdc_parser::Block::Admonition(a) => a.render(w, options),
+            acdc_parser::Block::Video(v) => v.render(w, options),
             _ => {
                 tracing::warn!("Unexpected block: {:?}", self);
                 Ok(())
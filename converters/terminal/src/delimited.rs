@@ -1,13 +1,222 @@
 use std::io::Write;
 
-use crate::Render;
+use acdc_parser::{AttributeValue, Block, DelimitedBlock, InlineNode};
+use crossterm::{
+    style::{Print, Stylize},
+    QueueableCommand,
+};
 
-impl Render for acdc_parser::DelimitedBlock {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
+use crate::{Options, Render};
+
+impl Render for DelimitedBlock {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
         writeln!(w)?;
         match &self.inner {
-            acdc_parser::DelimitedBlockType::DelimitedTable(t) => t.render(w),
+            acdc_parser::DelimitedBlockType::DelimitedTable(t) => {
+                crate::table::render_table(self, t, w, options)
+            }
+            acdc_parser::DelimitedBlockType::DelimitedListing(inlines)
+            | acdc_parser::DelimitedBlockType::DelimitedLiteral(inlines) => {
+                render_listing(self, inlines, w, options)
+            }
+            acdc_parser::DelimitedBlockType::DelimitedQuote(blocks) => {
+                let mut inner = std::io::BufWriter::new(Vec::new());
+                render_quote_blocks(blocks, &mut inner, options)?;
+                inner.flush()?;
+                render_quote(self, inner.get_ref(), w, options)
+            }
+            acdc_parser::DelimitedBlockType::DelimitedVerse(inlines) => {
+                let mut inner = std::io::BufWriter::new(Vec::new());
+                inlines
+                    .iter()
+                    .try_for_each(|node| node.render(&mut inner, options))?;
+                inner.flush()?;
+                render_quote(self, inner.get_ref(), w, options)
+            }
             _ => Ok(()),
         }
     }
 }
+
+/// Renders a listing or literal block, optionally prefixing each line with a dim,
+/// right-aligned line number when `linenums` is set.
+///
+/// Numbering starts at the `start` attribute (defaulting to 1) and the gutter is as wide
+/// as the largest line number.
+fn render_listing(
+    block: &DelimitedBlock,
+    inlines: &[InlineNode],
+    w: &mut impl Write,
+    options: &Options,
+) -> std::io::Result<()> {
+    let mut inner = std::io::BufWriter::new(Vec::new());
+    inlines
+        .iter()
+        .try_for_each(|node| node.render(&mut inner, options))?;
+    inner.flush()?;
+    let content = String::from_utf8(inner.get_ref().clone()).unwrap_or_default();
+
+    let linenums = block.metadata.options.iter().any(|o| o == "linenums")
+        || block.metadata.attributes.contains_key("linenums");
+    let start = match block.metadata.attributes.get("start") {
+        Some(AttributeValue::String(start)) => start.parse::<usize>().unwrap_or(1),
+        _ => 1,
+    };
+    let lines = content.lines().collect::<Vec<_>>();
+    let last = start + lines.len().saturating_sub(1);
+    let width = last.to_string().len();
+    let mut callouts = 0;
+    for (i, line) in lines.iter().enumerate() {
+        if linenums {
+            crate::print_styled(w, format!("{:>width$} ", start + i).dim(), options)?;
+        }
+        let (code, numbers) = split_callouts(line, &mut callouts);
+        w.queue(Print(code))?;
+        for number in numbers {
+            write!(w, " ")?;
+            crate::print_styled(w, crate::list::conum(number).bold(), options)?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Splits the callout markers (`<1>`, `<.>`) at the end of a verbatim line from its code,
+/// returning the numbers they stand for. `<.>` markers are numbered automatically,
+/// continuing from `callouts`, the number of callouts seen so far in the block.
+fn split_callouts<'a>(line: &'a str, callouts: &mut usize) -> (&'a str, Vec<usize>) {
+    let mut code = line.trim_end();
+    let mut markers = Vec::new();
+    while code.ends_with('>') {
+        let Some(open) = code.rfind('<') else {
+            break;
+        };
+        let marker = &code[open..];
+        let inner = &marker[1..marker.len() - 1];
+        if inner != "." && (inner.is_empty() || !inner.chars().all(|c| c.is_ascii_digit())) {
+            break;
+        }
+        markers.push(marker);
+        code = code[..open].trim_end();
+    }
+    if markers.is_empty() {
+        return (line, Vec::new());
+    }
+    let numbers = markers
+        .into_iter()
+        .rev()
+        .map(|marker| {
+            *callouts += 1;
+            crate::list::callout_number(marker).unwrap_or(*callouts)
+        })
+        .collect();
+    (code, numbers)
+}
+
+fn render_quote_blocks(
+    blocks: &[Block],
+    w: &mut impl Write,
+    options: &Options,
+) -> std::io::Result<()> {
+    let last_index = blocks.len().saturating_sub(1);
+    for (i, block) in blocks.iter().enumerate() {
+        block.render(w, options)?;
+        if i != last_index {
+            writeln!(w)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders already rendered quote (or verse) content with a left border, followed by
+/// the attribution line built from the `attribution` and `citetitle` attributes.
+fn render_quote(
+    block: &DelimitedBlock,
+    content: &[u8],
+    w: &mut impl Write,
+    options: &Options,
+) -> std::io::Result<()> {
+    let content = String::from_utf8_lossy(content);
+    for line in content.trim_end().lines() {
+        crate::print_styled(w, "│ ".dark_grey(), options)?;
+        w.queue(Print(line))?;
+        writeln!(w)?;
+    }
+
+    let attribution = [
+        block.metadata.attributes.get("attribution"),
+        block.metadata.attributes.get("citetitle"),
+    ]
+    .into_iter()
+    .filter_map(|value| match value {
+        Some(AttributeValue::String(value)) => Some(value.as_str()),
+        _ => None,
+    })
+    .collect::<Vec<_>>();
+    if !attribution.is_empty() {
+        crate::print_styled(
+            w,
+            format!("  — {}", attribution.join(", ")).italic(),
+            options,
+        )?;
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_listing_with_linenums() {
+        let content = (1..=12)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let doc =
+            acdc_parser::parse(&format!("[%linenums,start=100]\n----\n{content}\n----\n")).unwrap();
+        let mut buffer = Vec::new();
+        doc.blocks[0]
+            .render(&mut buffer, &Options::default())
+            .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("\x1b[2m100 \x1b[0mline 1\n"));
+        assert!(output.contains("\x1b[2m111 \x1b[0mline 12\n"));
+        assert!(!output.contains("112 "));
+    }
+
+    #[test]
+    fn test_render_quote_with_attribution() {
+        let doc =
+            acdc_parser::parse("[quote,Author Name,Source Title]\n____\nQuoted text here.\n____\n")
+                .unwrap();
+        let mut buffer = Vec::new();
+        doc.blocks[0]
+            .render(&mut buffer, &Options::default())
+            .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("│ \x1b[39mQuoted text here.\n"));
+        assert!(output.contains("— Author Name, Source Title"));
+    }
+
+    #[test]
+    fn test_render_listing_with_callouts() {
+        let doc = acdc_parser::parse(
+            "[source,ruby]\n----\nputs 'one' # <1>\nputs 'two' # <2>\n----\n<1> First\n<2> Second\n",
+        )
+        .unwrap();
+        let mut buffer = Vec::new();
+        doc.blocks[0]
+            .render(&mut buffer, &Options::default())
+            .unwrap();
+        doc.blocks[1]
+            .render(&mut buffer, &Options::default())
+            .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("puts 'one' # \x1b[1m①\x1b[0m\n"));
+        assert!(output.contains("puts 'two' # \x1b[1m②\x1b[0m\n"));
+        assert!(output.contains("\x1b[1m①\x1b[0m First\n"));
+        assert!(output.contains("\x1b[1m②\x1b[0m Second\n"));
+    }
+}
@@ -1,43 +1,61 @@
 use std::io::Write;
 
 use crossterm::{
-    style::{Print, PrintStyledContent, Stylize},
+    style::{Print, Stylize},
     QueueableCommand,
 };
 
-use crate::Render;
+use crate::{Options, Render};
 
 impl Render for acdc_parser::Document {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
-        if let Some(header) = &self.header {
-            header.render(w)?;
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
+        if let Some(header) = self.header.as_ref().filter(|_| !options.embedded) {
+            header.render(w, options)?;
         }
         if !self.blocks.is_empty() {
             let last_index = self.blocks.len() - 1;
             for (i, block) in self.blocks.iter().enumerate() {
-                block.render(w)?;
+                block.render(w, options)?;
                 if i != last_index {
                     writeln!(w)?;
                 }
             }
         }
+        // Footnotes are gathered at the end, as endnotes.
+        if !self.footnotes().is_empty() {
+            writeln!(w)?;
+            crate::print_styled(w, "Notes".bold(), options)?;
+            writeln!(w)?;
+            for footnote in self.footnotes() {
+                writeln!(
+                    w,
+                    "[{}] {}",
+                    footnote.number,
+                    footnote.text.as_deref().unwrap_or_default()
+                )?;
+            }
+        }
         Ok(())
     }
 }
 
 impl Render for acdc_parser::Header {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
+        let mut title = std::io::BufWriter::new(Vec::new());
         for node in &self.title {
-            node.render(w)?;
+            node.render(&mut title, options)?;
         }
+        title.flush()?;
+        let title = String::from_utf8_lossy(title.get_ref());
+        crate::print_styled(w, options.theme().heading.apply(title), options)?;
         if !self.authors.is_empty() {
-            w.queue(PrintStyledContent("by ".italic()))?;
+            crate::print_styled(w, "by ".italic(), options)?;
             // Join the authors with commas, except for the last one, using a functional approach
             self.authors
                 .iter()
                 .enumerate()
                 .try_for_each(|(i, author)| {
-                    author.render(w)?;
+                    author.render(w, options)?;
                     if i != self.authors.len() - 1 {
                         w.queue(Print(", "))?;
                     }
@@ -51,14 +69,14 @@ impl Render for acdc_parser::Header {
 }
 
 impl Render for acdc_parser::Author {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
-        w.queue(PrintStyledContent(format!("{} ", self.first_name).italic()))?;
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
+        crate::print_styled(w, format!("{} ", self.first_name).italic(), options)?;
         if let Some(middle_name) = &self.middle_name {
-            w.queue(PrintStyledContent(format!("{middle_name} ").italic()))?;
+            crate::print_styled(w, format!("{middle_name} ").italic(), options)?;
         }
-        w.queue(PrintStyledContent(self.last_name.clone().italic()))?;
+        crate::print_styled(w, self.last_name.clone().italic(), options)?;
         if let Some(email) = &self.email {
-            w.queue(PrintStyledContent(format!(" <{email}>").italic()))?;
+            crate::print_styled(w, format!(" <{email}>").italic(), options)?;
         }
         Ok(())
     }
@@ -76,7 +94,7 @@ mod tests {
     fn test_render_document() {
         let doc = Document::default();
         let mut buffer = Vec::new();
-        doc.render(&mut buffer).unwrap();
+        doc.render(&mut buffer, &Options::default()).unwrap();
         assert_eq!(buffer, b"");
     }
 
@@ -101,8 +119,40 @@ mod tests {
         });
         doc.blocks = vec![];
         let mut buffer = Vec::new();
-        doc.render(&mut buffer).unwrap();
-        assert_eq!(buffer, b"Title\x1b[3mby \x1b[0m\x1b[3mJohn \x1b[0m\x1b[3mM \x1b[0m\x1b[3mDoe\x1b[0m\x1b[3m <johndoe@example.com>\x1b[0m\n\n\n");
+        doc.render(&mut buffer, &Options::default()).unwrap();
+        assert_eq!(buffer, b"\x1b[38;5;14m\x1b[1mTitle\x1b[0m\x1b[3mby \x1b[0m\x1b[3mJohn \x1b[0m\x1b[3mM \x1b[0m\x1b[3mDoe\x1b[0m\x1b[3m <johndoe@example.com>\x1b[0m\n\n\n");
+    }
+
+    #[test]
+    fn test_render_embedded_document() {
+        let doc = acdc_parser::parse("= Title\nJohn Doe\n\nHello.\n").unwrap();
+        let options = Options {
+            theme: Some(crate::TerminalTheme::none()),
+            embedded: true,
+            ..Options::default()
+        };
+        let mut buffer = Vec::new();
+        doc.render(&mut buffer, &options).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "Hello.\n");
+    }
+
+    #[test]
+    fn test_render_document_with_footnotes() {
+        let doc = acdc_parser::parse(
+            "Rust.footnote:lang[A language.] Again.footnote:lang[] Pest.footnote:[A parser.]\n",
+        )
+        .unwrap();
+        let options = Options {
+            theme: Some(crate::TerminalTheme::none()),
+            ..Options::default()
+        };
+        let mut buffer = Vec::new();
+        doc.render(&mut buffer, &options).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(
+            output,
+            "Rust.[1] Again.[1] Pest.[2]\n\nNotes\n[1] A language.\n[2] A parser.\n"
+        );
     }
 
     #[test]
@@ -135,10 +185,14 @@ mod tests {
                 location: Location::default(),
                 level: 1,
                 metadata: BlockMetadata::default(),
+                generated_id: None,
             }),
         ];
         let mut buffer = Vec::new();
-        doc.render(&mut buffer).unwrap();
-        assert_eq!(buffer, b"Hello, world!\n\n> Section <\nHello, section!\n\n");
+        doc.render(&mut buffer, &Options::default()).unwrap();
+        assert_eq!(
+            buffer,
+            b"Hello, world!\n\n\x1b[38;5;14m\x1b[1m> Section <\x1b[0m\nHello, section!\n\n"
+        );
     }
 }
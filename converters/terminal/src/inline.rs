@@ -1,14 +1,11 @@
 use std::io::Write;
 
-use crossterm::{
-    style::{PrintStyledContent, Stylize},
-    QueueableCommand,
-};
+use crossterm::style::Stylize;
 
-use crate::Render;
+use crate::{Options, Render};
 
 impl Render for acdc_parser::InlineNode {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
         match self {
             acdc_parser::InlineNode::PlainText(p) => {
                 write!(w, "{}", p.content.clone())
@@ -24,63 +21,66 @@ impl Render for acdc_parser::InlineNode {
                 let mut inner = std::io::BufWriter::new(Vec::new());
                 i.content
                     .iter()
-                    .try_for_each(|node| node.render(&mut inner))?;
+                    .try_for_each(|node| node.render(&mut inner, options))?;
                 inner.flush()?;
-                w.queue(PrintStyledContent(
+                crate::print_styled(
+                    w,
                     String::from_utf8(inner.get_ref().clone())
                         .unwrap_or_default()
                         .trim()
                         .italic(),
-                ))?;
+                    options,
+                )?;
                 Ok(())
             }
             acdc_parser::InlineNode::BoldText(b) => {
                 let mut inner = std::io::BufWriter::new(Vec::new());
                 b.content
                     .iter()
-                    .try_for_each(|node| node.render(&mut inner))?;
+                    .try_for_each(|node| node.render(&mut inner, options))?;
                 inner.flush()?;
-                w.queue(PrintStyledContent(
+                crate::print_styled(
+                    w,
                     String::from_utf8(inner.get_ref().clone())
                         .unwrap_or_default()
                         .trim()
                         .bold(),
-                ))?;
+                    options,
+                )?;
                 Ok(())
             }
             acdc_parser::InlineNode::HighlightText(h) => {
                 let mut inner = std::io::BufWriter::new(Vec::new());
                 h.content
                     .iter()
-                    .try_for_each(|node| node.render(&mut inner))?;
+                    .try_for_each(|node| node.render(&mut inner, options))?;
                 inner.flush()?;
-                w.queue(PrintStyledContent(
-                    String::from_utf8(inner.get_ref().clone())
-                        .unwrap_or_default()
-                        .trim()
-                        .black()
-                        .on_yellow(),
-                ))?;
+                let text = String::from_utf8(inner.get_ref().clone()).unwrap_or_default();
+                // With a role, `#text#` is a span for that role rather than highlighted
+                // text. Only `line-through` has a terminal equivalent, the others (like
+                // `small` and `big`) are left as plain text.
+                match h.role.as_deref() {
+                    None => crate::print_styled(w, text.trim().black().on_yellow(), options)?,
+                    Some("line-through") => {
+                        crate::print_styled(w, text.trim().crossed_out(), options)?;
+                    }
+                    Some(_) => write!(w, "{}", text.trim())?,
+                }
                 Ok(())
             }
             acdc_parser::InlineNode::MonospaceText(m) => {
                 let mut inner = std::io::BufWriter::new(Vec::new());
                 m.content
                     .iter()
-                    .try_for_each(|node| node.render(&mut inner))?;
+                    .try_for_each(|node| node.render(&mut inner, options))?;
                 inner.flush()?;
-                w.queue(PrintStyledContent(
-                    String::from_utf8(inner.get_ref().clone())
-                        .unwrap_or_default()
-                        .trim()
-                        .black()
-                        .on_grey(),
-                ))?;
+                let text = String::from_utf8(inner.get_ref().clone()).unwrap_or_default();
+                crate::print_styled(w, options.theme().code.apply(text.trim()), options)?;
                 Ok(())
             }
             // implement macro link
             acdc_parser::InlineNode::Macro(m) => {
-                m.render(w)?;
+                m.render(w, options)?;
                 Ok(())
             }
             unknown => unimplemented!("GAH: {:?}", unknown),
@@ -89,11 +89,135 @@ impl Render for acdc_parser::InlineNode {
 }
 
 impl Render for acdc_parser::InlineMacro {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
         match self {
-            acdc_parser::InlineMacro::Link(l) => write!(w, "{}", l.target)?,
+            acdc_parser::InlineMacro::Link(l) => {
+                render_link(&l.target, l.text.as_deref(), w, options)?;
+            }
+            acdc_parser::InlineMacro::Url(u) => {
+                render_link(&u.target, u.text.as_deref(), w, options)?;
+            }
+            acdc_parser::InlineMacro::Autolink(a) => render_link(&a.url, None, w, options)?,
+            acdc_parser::InlineMacro::CrossReference(x) => match &x.text {
+                Some(text) => write!(w, "{text}")?,
+                None => write!(w, "[{}]", x.target)?,
+            },
+            acdc_parser::InlineMacro::Footnote(f) => match (f.number, &f.id) {
+                (0, Some(id)) => write!(w, "[{id}]")?,
+                (number, _) => write!(w, "[{number}]")?,
+            },
+            // Terminals can't typeset math, so the expression is shown as written.
+            acdc_parser::InlineMacro::Stem(s) => {
+                crate::print_styled(w, options.theme().code.apply(s.content.trim()), options)?;
+            }
             unknown => unimplemented!("GAH: {:?}", unknown),
         }
         Ok(())
     }
 }
+
+/// Writes a link as an OSC 8 hyperlink showing its text, or the text followed by the
+/// target in parentheses when hyperlinks are off. A link without text shows its target.
+///
+/// The text is styled by the theme, and hyperlinks are never emitted by themes without
+/// escape codes.
+pub(crate) fn render_link(
+    target: &str,
+    text: Option<&str>,
+    w: &mut impl Write,
+    options: &Options,
+) -> std::io::Result<()> {
+    let text = text.filter(|text| !text.is_empty());
+    let style = options.theme().link;
+    if options.hyperlinks && options.theme().ansi {
+        write!(w, "\x1b]8;;{target}\x1b\\")?;
+        crate::print_styled(w, style.apply(text.unwrap_or(target)), options)?;
+        write!(w, "\x1b]8;;\x1b\\")
+    } else if let Some(text) = text {
+        crate::print_styled(w, style.apply(text), options)?;
+        write!(w, " ({target})")
+    } else {
+        crate::print_styled(w, style.apply(target), options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TerminalTheme;
+
+    fn render(content: &str, options: &Options) -> String {
+        let doc = acdc_parser::parse(content).unwrap();
+        let mut buffer = Vec::new();
+        doc.blocks[0].render(&mut buffer, options).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_render_links() {
+        let content = "See https://example.com[the site] or https://rust-lang.org\n";
+        let link = TerminalTheme::dark().link;
+
+        let output = render(content, &Options::default());
+        assert!(output.contains(&format!(
+            "\x1b]8;;https://example.com\x1b\\{}\x1b]8;;\x1b\\",
+            link.apply("the site")
+        )));
+        assert!(output.contains(&format!(
+            "\x1b]8;;https://rust-lang.org\x1b\\{}\x1b]8;;\x1b\\",
+            link.apply("https://rust-lang.org")
+        )));
+
+        let options = Options {
+            hyperlinks: false,
+            ..Options::default()
+        };
+        let output = render(content, &options);
+        assert!(output.contains(&format!("{} (https://example.com)", link.apply("the site"))));
+        assert!(!output.contains("\x1b]8"));
+    }
+
+    #[test]
+    fn test_render_without_ansi() {
+        let content = "See *bold*, `code` and https://example.com[the site]\n";
+        let options = Options {
+            hyperlinks: true,
+            theme: Some(TerminalTheme::none()),
+            ..Options::default()
+        };
+        let output = render(content, &options);
+        assert!(!output.contains('\x1b'));
+        assert!(output.contains("bold"));
+        assert!(output.contains("code"));
+        assert!(output.contains("the site (https://example.com)"));
+    }
+
+    #[test]
+    fn test_render_inline_stem() {
+        let options = Options {
+            theme: Some(TerminalTheme::none()),
+            ..Options::default()
+        };
+        let output = render("The area is stem:[pi r^2] here.\n", &options);
+        assert!(output.contains("pi r^2"));
+        assert!(!output.contains("stem:"));
+
+        let output = render("NOTE: Square it: stem:[x^2]\n", &Options::default());
+        assert!(output.contains(&TerminalTheme::dark().code.apply("x^2").to_string()));
+    }
+
+    #[test]
+    fn test_render_line_through() {
+        let output = render("[.line-through]#gone#\n", &Options::default());
+        assert!(output.contains("\x1b[9mgone"));
+    }
+
+    #[test]
+    fn test_render_size_roles() {
+        let output = render(
+            "[.small]#fine print# and [.big]#large#\n",
+            &Options::default(),
+        );
+        assert_eq!(output, "fine print and large\n");
+    }
+}
@@ -1,7 +1,14 @@
-use std::io::Write;
+use std::{
+    io::{IsTerminal, Write},
+    path::Path,
+};
 
 use acdc_converters_common::{Config, Processable, Source};
-use acdc_parser::Document;
+use acdc_parser::{AttributeValue, Document};
+use crossterm::{
+    style::{Attribute, Attributes, Color, ContentStyle, Print, PrintStyledContent, StyledContent},
+    QueueableCommand,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -19,10 +26,10 @@ pub enum Error {
 }
 
 trait ToTerminal: Render {
-    fn to_terminal(&self) -> std::io::Result<()> {
+    fn to_terminal(&self, options: &Options) -> std::io::Result<()> {
         let stdout = std::io::stdout();
         let mut writer = std::io::BufWriter::new(stdout.lock());
-        self.render(&mut writer)?;
+        self.render(&mut writer, options)?;
         writer.flush()?;
         Ok(())
     }
@@ -31,11 +38,275 @@ trait ToTerminal: Render {
 /// A simple trait for helping in rendering `AsciiDoc` content.
 trait Render {
     #[allow(clippy::missing_errors_doc)]
-    fn render(&self, w: &mut impl std::io::Write) -> std::io::Result<()>;
+    fn render(&self, w: &mut impl std::io::Write, options: &Options) -> std::io::Result<()>;
+}
+
+/// Options that change how documents are rendered to the terminal.
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Whether links are emitted as OSC 8 hyperlinks, which most modern terminals make
+    /// clickable. They're never emitted with a theme that doesn't emit ANSI escape codes.
+    pub hyperlinks: bool,
+    /// The theme to style the output with. When unset, the document picks it with its
+    /// `terminal-theme` attribute, and otherwise it's `none` if `NO_COLOR` is set and
+    /// `dark` if it isn't.
+    pub theme: Option<TerminalTheme>,
+    /// Whether the document is rendered as a fragment to embed in other output, leaving
+    /// out its title banner.
+    pub embedded: bool,
+    /// The number of columns paragraphs and admonitions are wrapped at, and tables fit
+    /// in. When unset, text isn't wrapped.
+    pub width: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            hyperlinks: true,
+            theme: None,
+            embedded: false,
+            width: None,
+        }
+    }
+}
+
+impl Options {
+    /// The theme to style the output with, `dark` unless one was picked.
+    fn theme(&self) -> TerminalTheme {
+        self.theme.unwrap_or_default()
+    }
+
+    /// These options for writing to stdout: hyperlinks are turned off when it isn't a
+    /// terminal, and an unset width is the terminal's.
+    #[must_use]
+    pub fn for_stdout(self) -> Self {
+        let is_terminal = std::io::stdout().is_terminal();
+        Self {
+            hyperlinks: self.hyperlinks && is_terminal,
+            width: self.width.or_else(|| {
+                is_terminal
+                    .then(crossterm::terminal::size)
+                    .and_then(Result::ok)
+                    .map(|(columns, _)| usize::from(columns))
+            }),
+            ..self
+        }
+    }
+}
+
+/// The colors and weight used for one kind of element.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ElementStyle {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+}
+
+impl ElementStyle {
+    /// Styles `content` this way.
+    #[must_use]
+    pub fn apply<D: std::fmt::Display>(self, content: D) -> StyledContent<D> {
+        ContentStyle {
+            foreground_color: self.foreground,
+            background_color: self.background,
+            attributes: if self.bold {
+                Attributes::from(Attribute::Bold)
+            } else {
+                Attributes::default()
+            },
+            ..ContentStyle::default()
+        }
+        .apply(content)
+    }
+}
+
+/// How headings, admonition labels, inline code and links are styled, and whether any
+/// ANSI escape codes are emitted at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalTheme {
+    pub heading: ElementStyle,
+    pub admonition: ElementStyle,
+    pub code: ElementStyle,
+    pub link: ElementStyle,
+    /// Whether to emit ANSI escape codes. Without them the output is plain text, with no
+    /// styling and no hyperlinks, which is what you want when writing to a file.
+    pub ansi: bool,
+}
+
+impl Default for TerminalTheme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl TerminalTheme {
+    /// A theme for terminals with a dark background.
+    #[must_use]
+    pub fn dark() -> Self {
+        Self {
+            heading: ElementStyle {
+                foreground: Some(Color::Cyan),
+                bold: true,
+                ..ElementStyle::default()
+            },
+            admonition: ElementStyle {
+                foreground: Some(Color::Yellow),
+                bold: true,
+                ..ElementStyle::default()
+            },
+            code: ElementStyle {
+                foreground: Some(Color::Black),
+                background: Some(Color::Grey),
+                bold: false,
+            },
+            link: ElementStyle {
+                foreground: Some(Color::Blue),
+                ..ElementStyle::default()
+            },
+            ansi: true,
+        }
+    }
+
+    /// A theme for terminals with a light background.
+    #[must_use]
+    pub fn light() -> Self {
+        Self {
+            heading: ElementStyle {
+                foreground: Some(Color::DarkBlue),
+                bold: true,
+                ..ElementStyle::default()
+            },
+            admonition: ElementStyle {
+                foreground: Some(Color::DarkRed),
+                bold: true,
+                ..ElementStyle::default()
+            },
+            code: ElementStyle {
+                foreground: Some(Color::DarkMagenta),
+                ..ElementStyle::default()
+            },
+            link: ElementStyle {
+                foreground: Some(Color::DarkBlue),
+                ..ElementStyle::default()
+            },
+            ansi: true,
+        }
+    }
+
+    /// A theme without any styling at all.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            heading: ElementStyle::default(),
+            admonition: ElementStyle::default(),
+            code: ElementStyle::default(),
+            link: ElementStyle::default(),
+            ansi: false,
+        }
+    }
+
+    /// Returns the theme called `name`: `dark`, `light` or `none`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "none" => Some(Self::none()),
+            _ => None,
+        }
+    }
+
+    /// Returns the theme picked by the `terminal-theme` attribute of `document`, falling
+    /// back to `none` when the `NO_COLOR` environment variable is set and to `dark`
+    /// otherwise.
+    fn for_document(document: &Document) -> Self {
+        if let Some(AttributeValue::String(name)) = document.attributes.get("terminal-theme") {
+            match Self::from_name(name) {
+                Some(theme) => return theme,
+                None => tracing::warn!("unknown terminal-theme '{name}', ignoring it"),
+            }
+        }
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            Self::none()
+        } else {
+            Self::dark()
+        }
+    }
+}
+
+/// Writes the output of `render` to `w`, wrapped at the width in `options` if there is
+/// one. `render` gets options without a width, so that nothing inside it is wrapped on
+/// its own first.
+fn write_wrapped(
+    w: &mut impl Write,
+    options: &Options,
+    render: impl FnOnce(&mut Vec<u8>, &Options) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    render(
+        &mut buffer,
+        &Options {
+            width: None,
+            ..*options
+        },
+    )?;
+    match options.width {
+        Some(width) => write!(
+            w,
+            "{}",
+            wrap::wrap(&String::from_utf8_lossy(&buffer), width)
+        ),
+        None => w.write_all(&buffer),
+    }
+}
+
+/// Queues `content` with its style, or as plain text when the theme doesn't emit ANSI
+/// escape codes.
+fn print_styled<D: std::fmt::Display>(
+    w: &mut impl Write,
+    content: StyledContent<D>,
+    options: &Options,
+) -> std::io::Result<()> {
+    if options.theme().ansi {
+        w.queue(PrintStyledContent(content))?;
+    } else {
+        w.queue(Print(content.content()))?;
+    }
+    Ok(())
 }
 
 pub struct Processor {
     config: Config,
+    options: Options,
+}
+
+impl Processor {
+    #[must_use]
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Styles the output with `theme`, whatever the document's `terminal-theme` says.
+    #[must_use]
+    pub fn with_theme(mut self, theme: TerminalTheme) -> Self {
+        self.options.theme = Some(theme);
+        self
+    }
+
+    /// The options to render `document` with, resolving its theme and turning hyperlinks
+    /// off when the theme doesn't emit escape codes.
+    fn render_options(&self, document: &Document) -> Options {
+        let theme = self
+            .options
+            .theme
+            .unwrap_or_else(|| TerminalTheme::for_document(document));
+        Options {
+            hyperlinks: self.options.hyperlinks && theme.ansi,
+            theme: Some(theme),
+            ..self.options
+        }
+    }
 }
 
 impl ToTerminal for Document {}
@@ -44,25 +315,30 @@ impl Processable for Processor {
     type Config = Config;
     type Error = Error;
 
-    #[must_use]
     fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            options: Options::default(),
+        }
     }
 
     fn run(&self) -> Result<(), Error> {
         match &self.config.source {
             Source::Files(files) => {
                 for file in files {
-                    acdc_parser::parse_file(file)?.to_terminal()?;
+                    let doc = acdc_parser::parse_file(file)?;
+                    doc.to_terminal(&self.render_options(&doc).for_stdout())?;
                 }
             }
             Source::String(content) => {
-                acdc_parser::parse(content)?.to_terminal()?;
+                let doc = acdc_parser::parse(content)?;
+                doc.to_terminal(&self.render_options(&doc).for_stdout())?;
             }
             Source::Stdin => {
                 let stdin = std::io::stdin();
                 let mut reader = std::io::BufReader::new(stdin.lock());
-                acdc_parser::parse_from_reader(&mut reader)?.to_terminal()?;
+                let doc = acdc_parser::parse_from_reader(&mut reader)?;
+                doc.to_terminal(&self.render_options(&doc).for_stdout())?;
             }
         }
 
@@ -72,37 +348,37 @@ impl Processable for Processor {
     fn output(&self) -> Result<String, Self::Error> {
         match &self.config.source {
             Source::Files(files) => {
-                let buffer = Vec::new();
-                let mut writer = std::io::BufWriter::new(buffer);
+                let mut buffer = Vec::new();
                 for file in files {
                     let doc = acdc_parser::parse_file(file)?;
-                    doc.render(&mut writer)?;
+                    self.convert(&doc, Some(file), &mut buffer)?;
                 }
-                writer.flush()?;
-                Ok(String::from_utf8(writer.into_inner()?)?)
-            }
-            Source::String(content) => {
-                let doc = acdc_parser::parse(content)?;
-                let buffer = Vec::new();
-                let mut writer = std::io::BufWriter::new(buffer);
-                doc.render(&mut writer)?;
-                writer.flush()?;
-                Ok(String::from_utf8(writer.into_inner()?)?)
+                Ok(String::from_utf8(buffer)?)
             }
+            Source::String(content) => self.convert_to_string(&acdc_parser::parse(content)?, None),
             Source::Stdin => {
                 let stdin = std::io::stdin();
                 let mut reader = std::io::BufReader::new(stdin.lock());
                 let doc = acdc_parser::parse_from_reader(&mut reader)?;
-                let buffer = Vec::new();
-                let mut writer = std::io::BufWriter::new(buffer);
-                doc.render(&mut writer)?;
-                writer.flush()?;
-                Ok(String::from_utf8(writer.into_inner()?)?)
+                self.convert_to_string(&doc, None)
             }
         }
     }
+
+    fn convert<W: Write>(
+        &self,
+        doc: &Document,
+        _source_file: Option<&Path>,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        let mut writer = std::io::BufWriter::new(writer);
+        doc.render(&mut writer, &self.render_options(doc))?;
+        writer.flush()?;
+        Ok(())
+    }
 }
 
+mod admonition;
 mod block;
 mod delimited;
 mod document;
@@ -111,3 +387,49 @@ mod list;
 mod paragraph;
 mod section;
 mod table;
+mod video;
+mod wrap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_theme_attribute() {
+        let doc = acdc_parser::parse("= Doc\n:terminal-theme: none\n\nText.\n").unwrap();
+        let processor = Processor::new(Config::default());
+        let options = processor.render_options(&doc);
+        assert_eq!(options.theme, Some(TerminalTheme::none()));
+        assert!(!options.hyperlinks);
+
+        let processor = Processor::new(Config::default()).with_theme(TerminalTheme::light());
+        assert_eq!(
+            processor.render_options(&doc).theme,
+            Some(TerminalTheme::light())
+        );
+    }
+
+    #[test]
+    fn test_convert_to_string() {
+        let doc = acdc_parser::parse("= Doc\n:terminal-theme: none\n\nSome text.\n").unwrap();
+        let processor = Processor::new(Config::default());
+        let output = processor.convert_to_string(&doc, None).unwrap();
+        assert!(output.contains("Some text."));
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_convert_to_string_uses_explicit_options() {
+        let doc = acdc_parser::parse(
+            "= Doc\n:terminal-theme: dark\n\nSee https://example.com[the site] for the words in this paragraph.\n",
+        )
+        .unwrap();
+        let processor = Processor::new(Config::default()).with_options(Options {
+            width: Some(20),
+            ..Options::default()
+        });
+        let output = processor.convert_to_string(&doc, None).unwrap();
+        assert!(output.contains("\x1b]8;;https://example.com"));
+        assert!(output.lines().count() > 2);
+    }
+}
@@ -1,11 +1,8 @@
 use std::io::Write;
 
-use crossterm::{
-    style::{PrintStyledContent, Stylize},
-    QueueableCommand,
-};
+use crossterm::style::Stylize;
 
-use crate::Render;
+use crate::{Options, Render};
 
 /*
    pub title: Vec<InlineNode>,
@@ -16,42 +13,81 @@ use crate::Render;
 */
 
 impl Render for acdc_parser::UnorderedList {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
         if !self.title.is_empty() {
             let mut inner = std::io::BufWriter::new(Vec::new());
             self.title
                 .iter()
-                .try_for_each(|node| node.render(&mut inner))?;
+                .try_for_each(|node| node.render(&mut inner, options))?;
             inner.flush()?;
-            w.queue(PrintStyledContent(
+            crate::print_styled(
+                w,
                 String::from_utf8(inner.get_ref().clone())
                     .unwrap_or_default()
                     .trim()
                     .italic(),
-            ))?;
+                options,
+            )?;
         }
         writeln!(w)?;
-        self.items.iter().try_for_each(|item| item.render(w))?;
+        self.items
+            .iter()
+            .try_for_each(|item| item.render(w, options))?;
         Ok(())
     }
 }
 
+impl Render for acdc_parser::CalloutList {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
+        for (i, item) in self.items.iter().enumerate() {
+            let number = callout_number(&item.marker).unwrap_or(i + 1);
+            crate::print_styled(w, conum(number).bold(), options)?;
+            write!(w, " ")?;
+            item.content
+                .iter()
+                .try_for_each(|node| node.render(w, options))?;
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the number in a callout marker such as `<1>`, or `None` for an
+/// automatically numbered `<.>` marker (or anything else).
+pub(crate) fn callout_number(marker: &str) -> Option<usize> {
+    marker
+        .strip_prefix('<')
+        .and_then(|marker| marker.strip_suffix('>'))
+        .and_then(|number| number.parse().ok())
+}
+
+/// Returns the glyph used for the callout `number`: a circled number where Unicode has
+/// one (1 through 20) and a parenthesized number otherwise.
+pub(crate) fn conum(number: usize) -> String {
+    match u32::try_from(number) {
+        Ok(n @ 1..=20) => {
+            char::from_u32(0x2460 + n - 1).map_or_else(|| format!("({number})"), String::from)
+        }
+        _ => format!("({number})"),
+    }
+}
+
 impl Render for acdc_parser::ListItem {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
         write!(w, "{}", self.marker)?;
         if let Some(checked) = self.checked {
             write!(w, " ")?;
             if checked {
-                w.queue(PrintStyledContent("✔".bold()))?;
+                crate::print_styled(w, "✔".bold(), options)?;
             } else {
-                w.queue(PrintStyledContent("✘".bold()))?;
+                crate::print_styled(w, "✘".bold(), options)?;
             }
         }
         write!(w, " ")?;
         // render each node with a space between them
         let last_index = self.content.len() - 1;
         for (i, node) in self.content.iter().enumerate() {
-            node.render(w)?;
+            node.render(w, options)?;
             if i != last_index {
                 write!(w, " ")?;
             }
@@ -1,20 +1,39 @@
 use std::io::Write;
 
-use crate::Render;
+use crate::{Options, Render};
 
 impl Render for acdc_parser::Paragraph {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
-        for node in &self.title {
-            node.render(w)?;
-        }
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
+        crate::write_wrapped(w, options, |w, options| {
+            for node in &self.title {
+                node.render(w, options)?;
+            }
 
-        let last_index = self.content.len() - 1;
-        for (i, node) in self.content.iter().enumerate() {
-            node.render(w)?;
-            if i != last_index {
-                write!(w, " ")?;
+            for node in &self.content {
+                node.render(w, options)?;
             }
-        }
-        Ok(())
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Options, Render};
+
+    #[test]
+    fn test_render_wrapped_paragraph() {
+        let doc = acdc_parser::parse("Une crème brûlée, très sucrée, pour le goûter.\n").unwrap();
+        let options = Options {
+            theme: Some(crate::TerminalTheme::none()),
+            width: Some(16),
+            ..Options::default()
+        };
+        let mut buffer = Vec::new();
+        doc.blocks[0].render(&mut buffer, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "Une crème\nbrûlée, très\nsucrée, pour le\ngoûter.\n"
+        );
     }
 }
@@ -1,17 +1,24 @@
 use std::io::Write;
 
-use crate::Render;
+use crate::{Options, Render};
 
 impl Render for acdc_parser::Section {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
-        write!(w, "> ")?;
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
+        let mut title = std::io::BufWriter::new(Vec::new());
         for node in &self.title {
-            node.render(w)?;
+            node.render(&mut title, options)?;
         }
-        writeln!(w, " <")?;
+        title.flush()?;
+        let title = String::from_utf8_lossy(title.get_ref());
+        crate::print_styled(
+            w,
+            options.theme().heading.apply(format!("> {title} <")),
+            options,
+        )?;
+        writeln!(w)?;
         let last_index = self.content.len() - 1;
         for (i, block) in self.content.iter().enumerate() {
-            block.render(w)?;
+            block.render(w, options)?;
             if i != last_index {
                 writeln!(w)?;
             }
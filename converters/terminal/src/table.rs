@@ -1,59 +1,192 @@
 use std::io::Write;
 
-use comfy_table::{Cell, Color, ContentArrangement, Table};
+use acdc_parser::{AttributeValue, DelimitedBlock};
+use comfy_table::{Cell, Color, ContentArrangement, Table, TableComponent};
 
-use crate::Render;
+use crate::{Options, Render};
 
-impl Render for acdc_parser::Table {
-    fn render(&self, w: &mut impl Write) -> std::io::Result<()> {
-        let mut table = Table::new();
-        table
-            .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_width(80)
-            .load_preset(comfy_table::presets::UTF8_FULL)
-            .apply_modifier(comfy_table::modifiers::UTF8_ROUND_CORNERS);
-
-        if let Some(header) = &self.header {
-            let header_cells = header
-                .columns
-                .iter()
-                .map(|col| {
-                    let mut inner = std::io::BufWriter::new(Vec::new());
-                    col.content
-                        .iter()
-                        .try_for_each(|block| block.render(&mut inner))?;
-                    inner.flush()?;
-                    Ok(
-                        Cell::new(String::from_utf8(inner.get_ref().clone()).unwrap_or_default())
-                            .fg(Color::Green)
-                            .add_attribute(comfy_table::Attribute::Bold),
-                    )
-                })
-                .collect::<Result<Vec<_>, acdc_parser::Error>>()
-                .expect("this should have been ok, and I need to not use expect");
-            table.set_header(header_cells);
-        }
+const CORNERS: [TableComponent; 4] = [
+    TableComponent::TopLeftCorner,
+    TableComponent::TopRightCorner,
+    TableComponent::BottomLeftCorner,
+    TableComponent::BottomRightCorner,
+];
 
-        for row in &self.rows {
-            let cells = row
-                .columns
-                .iter()
-                .map(|col| {
-                    let mut inner = std::io::BufWriter::new(Vec::new());
-                    col.content
-                        .iter()
-                        .try_for_each(|block| block.render(&mut inner))?;
-                    inner.flush()?;
-                    Ok(Cell::new(
-                        String::from_utf8(inner.get_ref().clone()).unwrap_or_default(),
-                    ))
-                })
-                .collect::<Result<Vec<_>, acdc_parser::Error>>()
-                .expect("this should have been ok, and I need to not use expect");
-            table.add_row(cells);
+/// Renders a table, drawing only the borders asked for by the block's `frame` (`all`,
+/// `topbot`/`ends`, `sides` or `none`) and `grid` (`all`, `rows`, `cols` or `none`)
+/// attributes. With the `autowidth` option, columns are sized to their content instead
+/// of filling the terminal width.
+pub(crate) fn render_table(
+    block: &DelimitedBlock,
+    table: &acdc_parser::Table,
+    w: &mut impl Write,
+    options: &Options,
+) -> std::io::Result<()> {
+    let attribute = |name: &str| match block.metadata.attributes.get(name) {
+        Some(AttributeValue::String(value)) => value.as_str(),
+        _ => "all",
+    };
+    let mut removed = Vec::new();
+    match attribute("frame") {
+        "none" => {
+            removed.extend(CORNERS);
+            removed.extend([
+                TableComponent::TopBorder,
+                TableComponent::BottomBorder,
+                TableComponent::LeftBorder,
+                TableComponent::RightBorder,
+                TableComponent::TopBorderIntersections,
+                TableComponent::BottomBorderIntersections,
+                TableComponent::LeftBorderIntersections,
+                TableComponent::RightBorderIntersections,
+                TableComponent::LeftHeaderIntersection,
+                TableComponent::RightHeaderIntersection,
+            ]);
+        }
+        "topbot" | "ends" => {
+            removed.extend(CORNERS);
+            removed.extend([
+                TableComponent::LeftBorder,
+                TableComponent::RightBorder,
+                TableComponent::LeftBorderIntersections,
+                TableComponent::RightBorderIntersections,
+                TableComponent::LeftHeaderIntersection,
+                TableComponent::RightHeaderIntersection,
+            ]);
+        }
+        "sides" => {
+            removed.extend(CORNERS);
+            removed.extend([
+                TableComponent::TopBorder,
+                TableComponent::BottomBorder,
+                TableComponent::TopBorderIntersections,
+                TableComponent::BottomBorderIntersections,
+            ]);
+        }
+        _ => {}
+    }
+    let no_vertical_lines = [
+        TableComponent::VerticalLines,
+        TableComponent::MiddleIntersections,
+        TableComponent::MiddleHeaderIntersections,
+        TableComponent::TopBorderIntersections,
+        TableComponent::BottomBorderIntersections,
+    ];
+    let no_horizontal_lines = [
+        TableComponent::HorizontalLines,
+        TableComponent::MiddleIntersections,
+        TableComponent::LeftBorderIntersections,
+        TableComponent::RightBorderIntersections,
+    ];
+    match attribute("grid") {
+        "none" => {
+            removed.extend(no_vertical_lines);
+            removed.extend(no_horizontal_lines);
         }
+        "rows" => removed.extend(no_vertical_lines),
+        "cols" => removed.extend(no_horizontal_lines),
+        _ => {}
+    }
+
+    let mut output = Table::new();
+    output
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .apply_modifier(comfy_table::modifiers::UTF8_ROUND_CORNERS);
+    if block.metadata.options.iter().any(|o| o == "autowidth") {
+        output.set_content_arrangement(ContentArrangement::Disabled);
+    } else {
+        output
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(
+                options
+                    .width
+                    .and_then(|width| u16::try_from(width).ok())
+                    .unwrap_or(80),
+            );
+    }
+    for component in removed {
+        output.remove_style(component);
+    }
+    if !options.theme().ansi {
+        output.force_no_tty();
+    }
+    // The cells are wrapped to fit in their columns by the table itself.
+    add_rows(
+        table,
+        &mut output,
+        &Options {
+            width: None,
+            ..*options
+        },
+    )?;
+    writeln!(w, "{output}")?;
+    Ok(())
+}
+
+/// Adds the header and rows of `source` to `table`.
+fn add_rows(
+    source: &acdc_parser::Table,
+    table: &mut Table,
+    options: &Options,
+) -> std::io::Result<()> {
+    if let Some(header) = &source.header {
+        let header_cells = header
+            .columns
+            .iter()
+            .map(|col| {
+                let mut inner = std::io::BufWriter::new(Vec::new());
+                col.content
+                    .iter()
+                    .try_for_each(|block| block.render(&mut inner, options))?;
+                inner.flush()?;
+                Ok(Cell::new(
+                    String::from_utf8(inner.get_ref().clone())
+                        .unwrap_or_default()
+                        .trim_end(),
+                )
+                .fg(Color::Green)
+                .add_attribute(comfy_table::Attribute::Bold))
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        table.set_header(header_cells);
+    }
+
+    for row in &source.rows {
+        let cells = row
+            .columns
+            .iter()
+            .map(|col| {
+                let mut inner = std::io::BufWriter::new(Vec::new());
+                col.content
+                    .iter()
+                    .try_for_each(|block| block.render(&mut inner, options))?;
+                inner.flush()?;
+                Ok(Cell::new(
+                    String::from_utf8(inner.get_ref().clone())
+                        .unwrap_or_default()
+                        .trim_end(),
+                ))
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        table.add_row(cells);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Options, Render};
 
-        writeln!(w, "{table}")?;
-        Ok(())
+    #[test]
+    fn test_render_borderless_table() {
+        let doc = acdc_parser::parse("[frame=none,grid=none]\n|===\n|Name |Value\n|a |1\n|===\n")
+            .unwrap();
+        let mut buffer = Vec::new();
+        doc.blocks[0]
+            .render(&mut buffer, &Options::default())
+            .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(" Name  Value \n a     1     \n"));
+        assert!(!output.contains(['│', '─', '╭', '╰', '┼']));
     }
 }
@@ -0,0 +1,90 @@
+use std::io::Write;
+
+use acdc_parser::{AttributeValue, Video, VideoSource};
+
+use crate::{Options, Render};
+
+/// Options that change how a video plays, which are listed next to its link.
+const PLAYBACK_OPTIONS: &[&str] = &["autoplay", "loop", "muted", "nocontrols"];
+
+/// A video can't be played in a terminal, so it's described instead: its title, a link
+/// to watch it with its playback options and the poster image, if there is one.
+impl Render for Video {
+    fn render(&self, w: &mut impl Write, options: &Options) -> std::io::Result<()> {
+        write!(w, "▶ ")?;
+        if self.title.is_empty() {
+            write!(w, "Video")?;
+        } else {
+            for node in &self.title {
+                node.render(w, options)?;
+            }
+        }
+        let playback = self
+            .metadata
+            .options
+            .iter()
+            .filter(|option| PLAYBACK_OPTIONS.contains(&option.as_str()))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        for source in &self.sources {
+            write!(w, "\n  Watch: ")?;
+            crate::inline::render_link(&watch_url(self, source), None, w, options)?;
+            if !playback.is_empty() {
+                write!(w, " ({})", playback.join(", "))?;
+            }
+        }
+        if let Some(AttributeValue::String(poster)) = self.metadata.attributes.get("poster") {
+            write!(w, "\n  Poster: {poster}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Where to watch `source`, which is a video id for the `youtube` and `vimeo` styles.
+fn watch_url(video: &Video, source: &VideoSource) -> String {
+    let target = match source {
+        VideoSource::Path(path) => path,
+        VideoSource::Url(url) => url,
+    };
+    let attributes = &video.metadata.attributes;
+    if attributes.contains_key("youtube") {
+        format!("https://www.youtube.com/watch?v={target}")
+    } else if attributes.contains_key("vimeo") {
+        format!("https://vimeo.com/{target}")
+    } else {
+        target.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Options, Render, TerminalTheme};
+
+    fn render(content: &str) -> String {
+        let doc = acdc_parser::parse(content).unwrap();
+        let options = Options {
+            hyperlinks: false,
+            theme: Some(TerminalTheme::none()),
+            ..Options::default()
+        };
+        let mut buffer = Vec::new();
+        doc.blocks[0].render(&mut buffer, &options).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_render_youtube_video() {
+        let output =
+            render(".Intro talk\nvideo::dQw4w9WgXcQ[youtube,poster=intro.png,opts=autoplay]\n");
+        assert_eq!(
+            output,
+            "▶ Intro talk\n  Watch: https://www.youtube.com/watch?v=dQw4w9WgXcQ (autoplay)\n  Poster: intro.png\n"
+        );
+    }
+
+    #[test]
+    fn test_render_video_file() {
+        let output = render("video::media/clip.mp4[%loop%muted]\n");
+        assert_eq!(output, "▶ Video\n  Watch: media/clip.mp4 (loop, muted)\n");
+    }
+}
@@ -0,0 +1,137 @@
+use unicode_width::UnicodeWidthChar;
+
+/// Wraps `text` so that no line is wider than `width` columns.
+///
+/// Lines are broken at the spaces between words. Only a word wider than a whole line is
+/// broken inside, between characters but never before a combining mark or a joined
+/// character. ANSI escape sequences take up no room.
+pub(crate) fn wrap(text: &str, width: usize) -> String {
+    let width = width.max(1);
+    let mut output = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            output.push('\n');
+        }
+        let mut column = 0;
+        for (j, word) in line.split(' ').enumerate() {
+            let word_width = units(word).map(|(_, width)| width).sum::<usize>();
+            if j > 0 {
+                if column > 0 && column + 1 + word_width > width {
+                    output.push('\n');
+                    column = 0;
+                } else {
+                    output.push(' ');
+                    column += 1;
+                }
+            }
+            if column + word_width <= width {
+                output.push_str(word);
+                column += word_width;
+                continue;
+            }
+            for (unit, unit_width) in units(word) {
+                if column > 0 && column + unit_width > width {
+                    output.push('\n');
+                    column = 0;
+                }
+                output.push_str(unit);
+                column += unit_width;
+            }
+        }
+    }
+    output
+}
+
+/// Splits `text` into the pieces a line can't be broken inside of, along with the
+/// number of columns each takes up: ANSI escape sequences, which take none, and
+/// characters together with the combining marks and joined characters that follow them.
+fn units(text: &str) -> impl Iterator<Item = (&str, usize)> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let mut chars = rest.char_indices().peekable();
+        let (_, first) = chars.next()?;
+        let (end, width) = if first == '\x1b' {
+            (escape_sequence_len(rest), 0)
+        } else {
+            let mut width = first.width().unwrap_or_default();
+            let mut joined = false;
+            let mut end = rest.len();
+            while let Some(&(i, c)) = chars.peek() {
+                let attached = c.width() == Some(0) && c != '\x1b';
+                if !attached && !joined {
+                    end = i;
+                    break;
+                }
+                if joined {
+                    width = width.max(c.width().unwrap_or_default());
+                }
+                joined = c == '\u{200D}';
+                chars.next();
+            }
+            (end, width)
+        };
+        let (unit, tail) = rest.split_at(end);
+        rest = tail;
+        Some((unit, width))
+    })
+}
+
+/// The length of the ANSI escape sequence `text` starts with: a control sequence such
+/// as `\x1b[1m`, or an operating system command such as the `\x1b]8;;url\x1b\\` of a
+/// hyperlink.
+fn escape_sequence_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    match bytes.get(1) {
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7E).contains(b))
+            .map_or(text.len(), |i| i + 3),
+        Some(b']') => {
+            let terminator = (2..bytes.len()).find_map(|i| match bytes[i] {
+                0x07 => Some(i + 1),
+                0x1b if bytes.get(i + 1) == Some(&b'\\') => Some(i + 2),
+                _ => None,
+            });
+            terminator.unwrap_or(text.len())
+        }
+        Some(_) => 2,
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wrap;
+
+    #[test]
+    fn test_wrap_at_word_boundaries() {
+        assert_eq!(
+            wrap("The quick brown fox jumps over the lazy dog", 15),
+            "The quick brown\nfox jumps over\nthe lazy dog"
+        );
+        assert_eq!(wrap("short\nlines stay", 20), "short\nlines stay");
+    }
+
+    #[test]
+    fn test_wrap_multibyte_characters() {
+        assert_eq!(
+            wrap("Une crème brûlée très sucrée", 11),
+            "Une crème\nbrûlée très\nsucrée"
+        );
+        // Wide characters take two columns and a word is only broken between them.
+        assert_eq!(wrap("日本語のテキスト", 5), "日本\n語の\nテキ\nスト");
+        // A combining mark stays with the character before it.
+        assert_eq!(wrap("abe\u{301}cd", 3), "abe\u{301}\ncd");
+    }
+
+    #[test]
+    fn test_wrap_ignores_escape_sequences() {
+        assert_eq!(
+            wrap(
+                "\x1b[1mbold\x1b[0m and \x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\ text",
+                13
+            ),
+            "\x1b[1mbold\x1b[0m and \x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\\ntext"
+        );
+    }
+}